@@ -1,6 +1,10 @@
 use crate::map::Map;
 use crate::*;
-use specs::saveload::{MarkedBuilder, SimpleMarker};
+
+// How far a faction's opinion of the player moves when its members are hurt
+const BYSTANDER_ATTACKED: i32 = -5;
+const MEMBER_KILLED: i32 = -10;
+const RIVAL_KILLED: i32 = 2;
 
 pub fn inflict_damage(ecs: &mut World, damage: &EffectSpawner, target: Entity) {
     let mut pools = ecs.write_storage::<Pools>();
@@ -12,7 +16,32 @@ pub fn inflict_damage(ecs: &mut World, damage: &EffectSpawner, target: Entity) {
                     return;
                 }
             }
-            if let EffectType::Damage { amount } = damage.effect_type {
+            if let EffectType::Damage {
+                amount,
+                damage_type,
+            } = damage.effect_type
+            {
+                let amount = match damage_type {
+                    Some(damage_type) => {
+                        let resist = total_resistance(
+                            target,
+                            damage_type,
+                            &ecs.entities(),
+                            &ecs.read_storage::<Resistances>(),
+                            &ecs.read_storage::<Equipped>(),
+                            &ecs.read_storage::<StatusEffect>(),
+                            &ecs.read_storage::<Durability>(),
+                        );
+                        if resist != 0 {
+                            learn_resistance(ecs, target, damage_type);
+                        }
+                        resist_damage(amount, resist)
+                    }
+                    None => amount,
+                };
+                if amount < 1 {
+                    return;
+                }
                 pool.hit_points.current -= amount;
                 add_effect(None, EffectType::Bloodstain, Targets::Single { target });
                 add_effect(
@@ -33,6 +62,9 @@ pub fn inflict_damage(ecs: &mut World, damage: &EffectSpawner, target: Entity) {
                     if creator == *player_entity {
                         crate::gamelog::record_event("Damage Inflicted", amount);
                     }
+                    if acts_for_player(ecs, creator) {
+                        attacked_bystander(ecs, target);
+                    }
                 }
                 if pool.hit_points.current < 1 {
                     add_effect(
@@ -46,6 +78,46 @@ pub fn inflict_damage(ecs: &mut World, damage: &EffectSpawner, target: Entity) {
     }
 }
 
+/// Watching something shrug off (or reel from) a kind of damage teaches the player how that
+/// sort of creature takes it
+fn learn_resistance(ecs: &World, target: Entity, damage_type: DamageType) {
+    let seen = entity_position(ecs, target)
+        .is_some_and(|idx| ecs.fetch::<Map>().visible_tiles[idx as usize]);
+    if let (Some(name), true) = (ecs.read_storage::<Name>().get(target), seen) {
+        ecs.fetch_mut::<MasterDungeonMap>()
+            .known_resistances
+            .insert((name.name.clone(), damage_type));
+    }
+}
+
+/// Is this the player, or someone fighting on their behalf?
+fn acts_for_player(ecs: &World, entity: Entity) -> bool {
+    entity == *ecs.fetch::<Entity>() || ecs.read_storage::<Ally>().get(entity).is_some()
+}
+
+fn attacked_bystander(ecs: &World, target: Entity) {
+    if let Some(faction) = ecs.read_storage::<Faction>().get(target) {
+        let mut dm = ecs.fetch_mut::<MasterDungeonMap>();
+        let reaction =
+            dm.faction_reaction(&faction.name, "Player", &crate::raws::RAWS.lock().unwrap());
+        if reaction != crate::raws::Reaction::Attack {
+            dm.adjust_reputation(&faction.name, BYSTANDER_ATTACKED);
+        }
+    }
+}
+
+fn killed_faction_member(ecs: &World, target: Entity) {
+    if let Some(faction) = ecs.read_storage::<Faction>().get(target) {
+        let mut dm = ecs.fetch_mut::<MasterDungeonMap>();
+        dm.adjust_reputation(&faction.name, MEMBER_KILLED);
+        for rival in
+            crate::raws::faction_rivals(&faction.name, &crate::raws::RAWS.lock().unwrap()).iter()
+        {
+            dm.adjust_reputation(rival, RIVAL_KILLED);
+        }
+    }
+}
+
 pub fn heal_damage(ecs: &mut World, heal: &EffectSpawner, target: Entity) {
     let mut pools = ecs.write_storage::<Pools>();
     if let Some(pool) = pools.get_mut(target) {
@@ -90,7 +162,19 @@ pub fn bloodstain(ecs: &mut World, tile_idx: i32) {
     map.bloodstains.insert(tile_idx as usize);
 }
 
+pub fn hazard(ecs: &mut World, effect: &EffectSpawner, tile_idx: i32) {
+    if let EffectType::Hazard { kind, turns } = &effect.effect_type {
+        let mut map = ecs.fetch_mut::<Map>();
+        map.add_hazard(tile_idx as usize, *kind, *turns);
+    }
+}
+
 pub fn death(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    // A polymorphed creature only loses its borrowed shape; see revert_fatal_forms
+    if polymorph_status(ecs, target).is_some() {
+        return;
+    }
+
     let mut xp_gain = 0;
     let mut gold_gain = 0.0f32;
 
@@ -108,6 +192,29 @@ pub fn death(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
         effect.creator.unwrap()
     };
 
+    // Conjured creatures are worth nothing to anyone
+    if ecs.read_storage::<Summoned>().get(target).is_some() {
+        return;
+    }
+
+    if acts_for_player(ecs, source) {
+        killed_faction_member(ecs, target);
+    }
+
+    // Allies earn their own experience; the loot goes to the player
+    if ecs.read_storage::<Ally>().get(source).is_some() {
+        if let Some(stats) = pools.get(target) {
+            xp_gain += stats.level * 100;
+            gold_gain += stats.gold;
+        }
+        let player_entity = *ecs.fetch::<Entity>();
+        if let Some(player_stats) = pools.get_mut(player_entity) {
+            player_stats.gold += gold_gain;
+        }
+        ally_experience(ecs, &mut pools, &mut attributes, source, xp_gain);
+        return;
+    }
+
     if ecs.read_storage::<Player>().get(source).is_none() {
         return;
     }
@@ -217,33 +324,102 @@ pub fn death(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
     }
 }
 
+fn ally_experience(
+    ecs: &World,
+    pools: &mut WriteStorage<Pools>,
+    attributes: &mut WriteStorage<Attributes>,
+    ally: Entity,
+    xp_gain: i32,
+) {
+    let (stats, attr) =
+        if let (Some(stats), Some(attr)) = (pools.get_mut(ally), attributes.get_mut(ally)) {
+            (stats, attr)
+        } else {
+            return;
+        };
+
+    stats.xp += xp_gain;
+    if stats.xp < stats.level * 1000 {
+        return;
+    }
+
+    stats.level += 1;
+    match crate::tutorial::rng::roll_dice(1, 4) {
+        1 => attr.might.base += 1,
+        2 => attr.fitness.base += 1,
+        3 => attr.quickness.base += 1,
+        _ => attr.intelligence.base += 1,
+    }
+
+    if let Some(skills) = ecs.write_storage::<Skills>().get_mut(ally) {
+        for sk in skills.skills.iter_mut() {
+            *sk.1 += 1;
+        }
+    }
+
+    stats.hit_points.max = npc_hp(attr.fitness.base + attr.fitness.modifiers, stats.level);
+    stats.hit_points.current = stats.hit_points.max;
+    stats.mana.max = mana_at_level(
+        attr.intelligence.base + attr.intelligence.modifiers,
+        stats.level,
+    );
+    stats.mana.current = stats.mana.max;
+
+    ecs.write_storage::<EquipmentChanged>()
+        .insert(ally, EquipmentChanged {})
+        .expect("Insert failed");
+
+    if let Some(name) = ecs.read_storage::<Name>().get(ally) {
+        crate::gamelog::Logger::new()
+            .npc_name(&name.name)
+            .color(rltk::MAGENTA)
+            .append(format!("is now level {}", stats.level))
+            .log();
+    }
+}
+
 pub fn add_confusion(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
     if let EffectType::Confusion { turns } = &effect.effect_type {
-        ecs.create_entity()
-            .with(StatusEffect { target })
-            .with(Confusion {})
-            .with(Duration { turns: *turns })
-            .with(Name {
-                name: "Confusion".to_string(),
-            })
-            .build();
+        let kind = StatusKind::Confused;
+        if let StatusApplied::New(status) = apply_status(ecs, target, kind, kind.name(), *turns) {
+            ecs.write_storage::<Confusion>()
+                .insert(status, Confusion {})
+                .expect("Unable to insert");
+        }
+    }
+}
+
+pub fn add_stun(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::Stun { turns } = &effect.effect_type {
+        let kind = StatusKind::Stunned;
+        if let StatusApplied::New(status) = apply_status(ecs, target, kind, kind.name(), *turns) {
+            ecs.write_storage::<Stunned>()
+                .insert(status, Stunned {})
+                .expect("Unable to insert");
+        }
     }
 }
 
 pub fn attribute_effect(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
     if let EffectType::AttributeEffect {
         bonus,
+        resistances,
         name,
         duration,
     } = &effect.effect_type
     {
-        ecs.create_entity()
-            .with(StatusEffect { target })
-            .with(bonus.clone())
-            .with(Duration { turns: *duration })
-            .with(Name { name: name.clone() })
-            .marked::<SimpleMarker<SerializeMe>>()
-            .build();
+        if let StatusApplied::New(status) =
+            apply_status(ecs, target, StatusKind::Attribute, name, *duration)
+        {
+            ecs.write_storage::<AttributeBonus>()
+                .insert(status, bonus.clone())
+                .expect("Unable to insert");
+            if let Some(resistances) = resistances {
+                ecs.write_storage::<Resistances>()
+                    .insert(status, resistances.clone())
+                    .expect("Unable to insert");
+            }
+        }
         ecs.write_storage::<EquipmentChanged>()
             .insert(target, EquipmentChanged {})
             .expect("Unable to insert");
@@ -251,35 +427,68 @@ pub fn attribute_effect(ecs: &mut World, effect: &EffectSpawner, target: Entity)
 }
 
 pub fn slow(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
-    if let EffectType::Slow { initiative_penalty } = &effect.effect_type {
-        ecs.create_entity()
-            .with(StatusEffect { target })
-            .with(Slow {
-                initiative_penalty: *initiative_penalty,
-            })
-            .with(Duration { turns: 5 })
-            .with(Name {
-                name: if *initiative_penalty > 0.0 {
-                    "Slowed".to_string()
-                } else {
-                    "Hasted".to_string()
-                },
-            })
-            .marked::<SimpleMarker<SerializeMe>>()
-            .build();
+    if let EffectType::Slow {
+        initiative_penalty,
+        turns,
+    } = &effect.effect_type
+    {
+        let kind = if *initiative_penalty > 0.0 {
+            StatusKind::Slowed
+        } else {
+            StatusKind::Hasted
+        };
+        match apply_status(ecs, target, kind, kind.name(), *turns) {
+            StatusApplied::New(status) => {
+                ecs.write_storage::<Slow>()
+                    .insert(
+                        status,
+                        Slow {
+                            initiative_penalty: *initiative_penalty,
+                        },
+                    )
+                    .expect("Unable to insert");
+            }
+            StatusApplied::Stacked(status) => {
+                // A stronger dose replaces a weaker one
+                if let Some(slow) = ecs.write_storage::<Slow>().get_mut(status) {
+                    if initiative_penalty.abs() > slow.initiative_penalty.abs() {
+                        slow.initiative_penalty = *initiative_penalty;
+                    }
+                }
+            }
+            StatusApplied::Immune => {}
+        }
     }
 }
 
 pub fn damage_over_time(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
-    if let EffectType::DamageOverTime { damage } = &effect.effect_type {
-        ecs.create_entity()
-            .with(StatusEffect { target })
-            .with(DamageOverTime { damage: *damage })
-            .with(Duration { turns: 5 })
-            .with(Name {
-                name: "Damage Over Time".to_string(),
-            })
-            .marked::<SimpleMarker<SerializeMe>>()
-            .build();
+    if let EffectType::DamageOverTime {
+        damage,
+        damage_type,
+        turns,
+    } = &effect.effect_type
+    {
+        let kind = damage_over_time_kind(*damage_type);
+        match apply_status(ecs, target, kind, kind.name(), *turns) {
+            StatusApplied::New(status) => {
+                ecs.write_storage::<DamageOverTime>()
+                    .insert(
+                        status,
+                        DamageOverTime {
+                            damage: *damage,
+                            damage_type: *damage_type,
+                        },
+                    )
+                    .expect("Unable to insert");
+            }
+            StatusApplied::Stacked(status) => {
+                if kind.stacking() == StackPolicy::Intensify {
+                    if let Some(dot) = ecs.write_storage::<DamageOverTime>().get_mut(status) {
+                        dot.damage += *damage;
+                    }
+                }
+            }
+            StatusApplied::Immune => {}
+        }
     }
 }
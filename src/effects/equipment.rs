@@ -0,0 +1,171 @@
+use super::*;
+use crate::map::Map;
+use crate::{
+    Ammunition, Durability, EquipmentChanged, Equipped, InBackpack, ItemCondition, Name, Position,
+    Stackable, Weapon,
+};
+
+/// Knocks a weapon out of its owner's hands and onto the floor at their feet
+pub fn drop_weapon(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::DropWeapon { item } = effect.effect_type {
+        let owner = match ecs.read_storage::<Equipped>().get(item) {
+            Some(equipped) if equipped.owner == target => equipped.owner,
+            _ => return,
+        };
+        let pos = match ecs.read_storage::<Position>().get(owner) {
+            Some(pos) => pos.clone(),
+            None => return,
+        };
+
+        ecs.write_storage::<Equipped>().remove(item);
+        ecs.write_storage::<Position>()
+            .insert(item, pos)
+            .expect("Unable to insert");
+        ecs.write_storage::<EquipmentChanged>()
+            .insert(owner, EquipmentChanged {})
+            .expect("Unable to insert");
+
+        if let Some(owner_name) = ecs.read_storage::<Name>().get(owner) {
+            crate::gamelog::Logger::new()
+                .npc_name(&owner_name.name)
+                .color(rltk::WHITE)
+                .append("loses their grip on the")
+                .item_name(crate::gui::get_item_display_name(ecs, item))
+                .log();
+        }
+    }
+}
+
+/// A clumsy blow notches the blade: it loses a chunk of durability, or if it has none, is
+/// permanently blunted
+pub fn damage_weapon(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::DamageWeapon { item } = effect.effect_type {
+        if ecs.read_storage::<Durability>().get(item).is_some() {
+            wear_down(ecs, target, item, FUMBLE_WEAR);
+        } else if let Some(weapon) = ecs.write_storage::<Weapon>().get_mut(item) {
+            weapon.damage_bonus -= 1;
+        }
+
+        if let Some(owner_name) = ecs.read_storage::<Name>().get(target) {
+            crate::gamelog::Logger::new()
+                .npc_name(&owner_name.name)
+                .color(rltk::WHITE)
+                .append("strikes the ground and damages the")
+                .item_name(crate::gui::get_item_display_name(ecs, item))
+                .log();
+        }
+    }
+}
+
+/// Hits and blocks slowly wear down weapons and armour
+pub fn wear(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::Wear { item, amount } = effect.effect_type {
+        wear_down(ecs, target, item, amount);
+    }
+}
+
+/// Durability lost when a fumble damages a weapon
+const FUMBLE_WEAR: i32 = 10;
+
+fn wear_down(ecs: &mut World, owner: Entity, item: Entity, amount: i32) {
+    let (before, after) = {
+        let mut durabilities = ecs.write_storage::<Durability>();
+        let durability = if let Some(durability) = durabilities.get_mut(item) {
+            durability
+        } else {
+            return;
+        };
+        let before = durability.condition();
+        durability.current = i32::max(0, durability.current - amount);
+        (before, durability.condition())
+    };
+    if before == after {
+        return;
+    }
+
+    // Armour class, resistances and attribute bonuses all depend on the item's condition
+    ecs.write_storage::<EquipmentChanged>()
+        .insert(owner, EquipmentChanged {})
+        .expect("Unable to insert");
+
+    if owner == *ecs.fetch::<Entity>() {
+        let message = if after == ItemCondition::Broken {
+            "breaks!".to_string()
+        } else {
+            format!("is now {}.", after.name().to_lowercase())
+        };
+        crate::gamelog::Logger::new()
+            .append("Your")
+            .item_name(crate::gui::get_single_item_name(ecs, item))
+            .color(rltk::ORANGE)
+            .append(message)
+            .log();
+    }
+}
+
+/// A thrown item comes to rest on the tile it was thrown at, out of its owner's hands
+pub fn land_item(ecs: &mut World, effect: &EffectSpawner, tile_idx: i32) {
+    if let EffectType::LandItem { item } = effect.effect_type {
+        let owner = if let Some(equipped) = ecs.read_storage::<Equipped>().get(item) {
+            Some(equipped.owner)
+        } else {
+            ecs.read_storage::<InBackpack>()
+                .get(item)
+                .map(|pack| pack.owner)
+        };
+        if let Some(owner) = owner {
+            ecs.write_storage::<EquipmentChanged>()
+                .insert(owner, EquipmentChanged {})
+                .expect("Unable to insert");
+        }
+        ecs.write_storage::<Equipped>().remove(item);
+        ecs.write_storage::<InBackpack>().remove(item);
+
+        let width = ecs.fetch::<Map>().width;
+        ecs.write_storage::<Position>()
+            .insert(
+                item,
+                Position {
+                    x: tile_idx % width,
+                    y: tile_idx / width,
+                },
+            )
+            .expect("Unable to insert");
+    }
+}
+
+/// Drops a spent arrow or bolt on the tile, adding it to any matching bundle already lying there
+pub fn recover_ammo(ecs: &mut World, effect: &EffectSpawner, tile_idx: i32) {
+    if let EffectType::RecoverAmmo { name } = &effect.effect_type {
+        let width = ecs.fetch::<Map>().width;
+        let (x, y) = (tile_idx % width, tile_idx / width);
+
+        {
+            let entities = ecs.entities();
+            let names = ecs.read_storage::<Name>();
+            let positions = ecs.read_storage::<Position>();
+            let ammunition = ecs.read_storage::<Ammunition>();
+            let mut stackables = ecs.write_storage::<Stackable>();
+            for (_entity, item_name, pos, _ammo, stack) in
+                (&entities, &names, &positions, &ammunition, &mut stackables).join()
+            {
+                if pos.x == x && pos.y == y && item_name.name == *name {
+                    stack.quantity += 1;
+                    return;
+                }
+            }
+        }
+
+        let spawned = crate::raws::spawn_named_entity(
+            &crate::raws::RAWS.lock().unwrap(),
+            ecs,
+            name,
+            crate::raws::SpawnType::AtPosition { x, y },
+        );
+        if let Some(spawned) = spawned {
+            if let Some(stack) = ecs.write_storage::<Stackable>().get_mut(spawned) {
+                stack.quantity = 1;
+            }
+        }
+    }
+}
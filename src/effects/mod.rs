@@ -1,7 +1,7 @@
 use std::collections::{HashSet, VecDeque};
 use std::sync::Mutex;
 
-use crate::AttributeBonus;
+use crate::{AttributeBonus, DamageType, HazardKind, Resistances, StatusHooks, StatusKind};
 
 use specs::prelude::*;
 
@@ -25,6 +25,30 @@ pub use hunger::*;
 mod movement;
 pub use movement::*;
 
+mod equipment;
+pub use equipment::*;
+
+mod script;
+pub use script::*;
+
+mod status;
+pub use status::*;
+
+mod summon;
+pub use summon::*;
+
+mod polymorph;
+pub use polymorph::*;
+
+mod traps;
+pub use traps::*;
+
+// How long effects last when whatever caused them doesn't say
+pub const DEFAULT_SLOW_TURNS: i32 = 5;
+pub const DEFAULT_DAMAGE_OVER_TIME_TURNS: i32 = 5;
+pub const DEFAULT_ATTRIBUTE_TURNS: i32 = 10;
+pub const DEFAULT_POLYMORPH_TURNS: i32 = 20;
+
 lazy_static! {
     pub static ref EFFECT_QUEUE: Mutex<VecDeque<EffectSpawner>> = Mutex::new(VecDeque::new());
 }
@@ -32,8 +56,13 @@ lazy_static! {
 pub enum EffectType {
     Damage {
         amount: i32,
+        damage_type: Option<DamageType>,
     },
     Bloodstain,
+    Hazard {
+        kind: HazardKind,
+        turns: i32,
+    },
     Particle {
         glyph: rltk::FontCharType,
         fg: rltk::RGB,
@@ -76,14 +105,71 @@ pub enum EffectType {
     },
     AttributeEffect {
         bonus: AttributeBonus,
+        resistances: Option<Resistances>,
         name: String,
         duration: i32,
     },
     Slow {
         initiative_penalty: f32,
+        turns: i32,
     },
     DamageOverTime {
         damage: i32,
+        damage_type: DamageType,
+        turns: i32,
+    },
+    Stun {
+        turns: i32,
+    },
+    Knockback {
+        distance: i32,
+    },
+    Pull {
+        distance: i32,
+    },
+    Blink,
+    RandomTeleport {
+        range: i32,
+    },
+    SwapPlaces,
+    DropWeapon {
+        item: Entity,
+    },
+    DamageWeapon {
+        item: Entity,
+    },
+    Wear {
+        item: Entity,
+        amount: i32,
+    },
+    LandItem {
+        item: Entity,
+    },
+    RecoverAmmo {
+        name: String,
+    },
+    Script {
+        node: EffectNode,
+    },
+    Status {
+        kind: StatusKind,
+        turns: i32,
+        on_apply: Option<EffectNode>,
+        hooks: Option<StatusHooks>,
+    },
+    Summon {
+        mob: String,
+        count: i32,
+        duration: i32,
+        faction: String,
+    },
+    Polymorph {
+        form: String,
+        turns: i32,
+    },
+    RevertForm,
+    DeployTrap {
+        prop: String,
     },
 }
 
@@ -136,6 +222,23 @@ fn target_applicator(ecs: &mut World, effect: &mut EffectSpawner) {
         triggers::spell_trigger(effect.creator, spell, &effect.targets, ecs);
     } else if let EffectType::TriggerFire { trigger } = effect.effect_type {
         triggers::trigger(effect.creator, trigger, &effect.targets, ecs);
+    } else if let EffectType::Script { node } = &effect.effect_type {
+        script::run_script(ecs, effect.creator, node, &effect.targets);
+    } else if let EffectType::Summon { .. } = effect.effect_type {
+        summon::summon(ecs, effect);
+    } else if let EffectType::Blink = effect.effect_type {
+        // Only one place to land, however wide the targeting was
+        let tile_idx = match &effect.targets {
+            Targets::Tile { tile_idx } => Some(*tile_idx),
+            Targets::Tiles { tiles } => tiles.first().copied(),
+            Targets::Single { target } => entity_position(ecs, *target),
+            Targets::TargetList { targets } => targets
+                .first()
+                .and_then(|target| entity_position(ecs, *target)),
+        };
+        if let Some(tile_idx) = tile_idx {
+            movement::blink(ecs, effect, tile_idx);
+        }
     } else {
         match &effect.targets.clone() {
             Targets::Tile { tile_idx } => affect_tile(ecs, effect, *tile_idx),
@@ -162,6 +265,13 @@ fn tile_effect_hits_entities(effect: &EffectType) -> bool {
             | EffectType::AttributeEffect { .. }
             | EffectType::Slow { .. }
             | EffectType::DamageOverTime { .. }
+            | EffectType::Stun { .. }
+            | EffectType::Knockback { .. }
+            | EffectType::Pull { .. }
+            | EffectType::RandomTeleport { .. }
+            | EffectType::SwapPlaces
+            | EffectType::Status { .. }
+            | EffectType::Polymorph { .. }
     )
 }
 
@@ -174,8 +284,12 @@ fn affect_tile(ecs: &mut World, effect: &mut EffectSpawner, tile_idx: i32) {
 
     match &effect.effect_type {
         EffectType::Bloodstain => damage::bloodstain(ecs, tile_idx),
+        EffectType::Hazard { .. } => damage::hazard(ecs, effect, tile_idx),
         EffectType::Particle { .. } => particles::particle_to_tile(ecs, tile_idx, effect),
         EffectType::ParticleProjectile { .. } => particles::projectile(ecs, tile_idx, effect),
+        EffectType::LandItem { .. } => equipment::land_item(ecs, effect, tile_idx),
+        EffectType::RecoverAmmo { .. } => equipment::recover_ammo(ecs, effect, tile_idx),
+        EffectType::DeployTrap { .. } => traps::deploy_trap(ecs, effect, tile_idx),
         _ => {}
     }
 }
@@ -193,6 +307,11 @@ fn affect_entity(ecs: &mut World, effect: &mut EffectSpawner, target: Entity) {
                 damage::bloodstain(ecs, pos)
             }
         }
+        EffectType::Hazard { .. } => {
+            if let Some(pos) = entity_position(ecs, target) {
+                damage::hazard(ecs, effect, pos)
+            }
+        }
         EffectType::Particle { .. } => {
             if let Some(pos) = entity_position(ecs, target) {
                 particles::particle_to_tile(ecs, pos, effect)
@@ -206,6 +325,22 @@ fn affect_entity(ecs: &mut World, effect: &mut EffectSpawner, target: Entity) {
         EffectType::AttributeEffect { .. } => damage::attribute_effect(ecs, effect, target),
         EffectType::Slow { .. } => damage::slow(ecs, effect, target),
         EffectType::DamageOverTime { .. } => damage::damage_over_time(ecs, effect, target),
+        EffectType::Stun { .. } => damage::add_stun(ecs, effect, target),
+        EffectType::Knockback { .. } => movement::knockback(ecs, effect, target),
+        EffectType::Pull { .. } => movement::pull(ecs, effect, target),
+        EffectType::RandomTeleport { .. } => movement::random_teleport(ecs, effect, target),
+        EffectType::SwapPlaces => movement::swap_places(ecs, effect, target),
+        EffectType::DropWeapon { .. } => equipment::drop_weapon(ecs, effect, target),
+        EffectType::DamageWeapon { .. } => equipment::damage_weapon(ecs, effect, target),
+        EffectType::Wear { .. } => equipment::wear(ecs, effect, target),
+        EffectType::Status { .. } => status::add_status(ecs, effect, target),
+        EffectType::Polymorph { .. } => polymorph::polymorph(ecs, effect, target),
+        EffectType::RevertForm => polymorph::revert_form(ecs, target, false),
+        EffectType::DeployTrap { .. } => {
+            if let Some(pos) = entity_position(ecs, target) {
+                traps::deploy_trap(ecs, effect, pos)
+            }
+        }
         _ => {}
     }
 }
@@ -1,5 +1,11 @@
 use super::*;
 use crate::components::ApplyTeleport;
+use crate::map::Map;
+use crate::{tile_walkable, EntityMoved, Pools, Position, TileSize, Viewshed};
+use rltk::Point;
+
+// How many random spots a teleport tries before giving up
+const TELEPORT_ATTEMPTS: i32 = 20;
 
 pub fn apply_teleport(ecs: &mut World, destination: &EffectSpawner, target: Entity) {
     let player_entity = ecs.fetch::<Entity>();
@@ -27,3 +33,184 @@ pub fn apply_teleport(ecs: &mut World, destination: &EffectSpawner, target: Enti
         }
     }
 }
+
+/// Pushes the target directly away from whoever caused the effect, stopping at the first
+/// wall or creature in the way
+pub fn knockback(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let (EffectType::Knockback { distance }, Some(creator)) =
+        (&effect.effect_type, effect.creator)
+    {
+        shove(ecs, creator, target, *distance, true);
+    }
+}
+
+/// Drags the target toward whoever caused the effect, until something is in the way
+pub fn pull(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let (EffectType::Pull { distance }, Some(creator)) = (&effect.effect_type, effect.creator) {
+        shove(ecs, creator, target, *distance, false);
+    }
+}
+
+/// Moves the creator to a tile it can see
+pub fn blink(ecs: &mut World, effect: &EffectSpawner, tile_idx: i32) {
+    let Some(creator) = effect.creator else {
+        return;
+    };
+    let tile_idx = tile_idx as usize;
+    let (point, walkable) = {
+        let map = ecs.fetch::<Map>();
+        (
+            Point::new(tile_idx as i32 % map.width, tile_idx as i32 / map.width),
+            tile_walkable(map.tiles[tile_idx]),
+        )
+    };
+    let in_sight = ecs
+        .read_storage::<Viewshed>()
+        .get(creator)
+        .is_some_and(|vs| vs.visible_tiles.contains(&point));
+
+    if in_sight && walkable && !crate::spatial::is_blocked(tile_idx) {
+        relocate(ecs, creator, tile_idx);
+    } else if creator == *ecs.fetch::<Entity>() {
+        crate::gamelog::Logger::new()
+            .append("The space there resists you.")
+            .log();
+    }
+}
+
+/// Throws the target to a random open tile nearby, sight unseen
+pub fn random_teleport(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::RandomTeleport { range } = &effect.effect_type {
+        if !is_creature(ecs, target) {
+            return;
+        }
+        let Some(start) = entity_position(ecs, target) else {
+            return;
+        };
+        let dest = {
+            let map = ecs.fetch::<Map>();
+            let (sx, sy) = (start % map.width, start / map.width);
+            (0..TELEPORT_ATTEMPTS).find_map(|_| {
+                let x = sx + crate::tutorial::rng::range(-range, range + 1);
+                let y = sy + crate::tutorial::rng::range(-range, range + 1);
+                if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+                    return None;
+                }
+                let idx = map.xy_idx(x, y);
+                (idx as i32 != start
+                    && tile_walkable(map.tiles[idx])
+                    && !crate::spatial::is_blocked(idx))
+                .then_some(idx)
+            })
+        };
+        if let Some(dest) = dest {
+            relocate(ecs, target, dest);
+            add_effect(
+                None,
+                EffectType::Particle {
+                    glyph: rltk::to_cp437('*'),
+                    fg: rltk::RGB::named(rltk::MAGENTA),
+                    bg: rltk::RGB::named(rltk::BLACK),
+                    lifespan: 200.0,
+                },
+                Targets::Tile { tile_idx: start },
+            );
+        }
+    }
+}
+
+/// Trades places between the creator and the target
+pub fn swap_places(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    let Some(creator) = effect.creator else {
+        return;
+    };
+    if creator == target || !is_creature(ecs, target) {
+        return;
+    }
+    // Big creatures don't fit in a small one's place
+    {
+        let sizes = ecs.read_storage::<TileSize>();
+        if sizes.get(creator).is_some() || sizes.get(target).is_some() {
+            return;
+        }
+    }
+    if let (Some(from), Some(to)) = (entity_position(ecs, creator), entity_position(ecs, target)) {
+        relocate(ecs, creator, to as usize);
+        relocate(ecs, target, from as usize);
+    }
+}
+
+fn is_creature(ecs: &World, entity: Entity) -> bool {
+    ecs.read_storage::<Pools>().get(entity).is_some()
+}
+
+/// Slides the target along the line between it and the creator, stopping at the first wall
+/// or creature in the way
+fn shove(ecs: &mut World, creator: Entity, target: Entity, distance: i32, away: bool) {
+    let (from, start) = match (entity_position(ecs, creator), entity_position(ecs, target)) {
+        (Some(from), Some(start)) => (from, start),
+        _ => return,
+    };
+    let dest = {
+        let map = ecs.fetch::<Map>();
+        let from = Point::new(from % map.width, from / map.width);
+        let start = Point::new(start % map.width, start / map.width);
+        if from == start {
+            return;
+        }
+        let end = if away {
+            let (vx, vy) = ((start.x - from.x) as f32, (start.y - from.y) as f32);
+            let length = f32::sqrt(vx * vx + vy * vy);
+            Point::new(
+                start.x + (vx / length * distance as f32).round() as i32,
+                start.y + (vy / length * distance as f32).round() as i32,
+            )
+        } else {
+            from
+        };
+
+        let mut dest = None;
+        for step in rltk::line2d(rltk::LineAlg::Bresenham, start, end)
+            .into_iter()
+            .skip(1)
+            .take(distance as usize)
+        {
+            if step.x < 1 || step.x >= map.width - 1 || step.y < 1 || step.y >= map.height - 1 {
+                break;
+            }
+            let idx = map.xy_idx(step.x, step.y);
+            if crate::spatial::is_blocked(idx) {
+                break;
+            }
+            dest = Some(idx);
+        }
+        dest
+    };
+
+    if let Some(dest_idx) = dest {
+        relocate(ecs, target, dest_idx);
+    }
+}
+
+/// Moves an entity straight to a tile on this level, keeping the spatial index, its
+/// viewshed and the player's position in step
+fn relocate(ecs: &mut World, entity: Entity, dest_idx: usize) {
+    let map = ecs.fetch::<Map>();
+    let mut positions = ecs.write_storage::<Position>();
+    let Some(pos) = positions.get_mut(entity) else {
+        return;
+    };
+    crate::spatial::move_entity(entity, map.xy_idx(pos.x, pos.y), dest_idx);
+    pos.x = dest_idx as i32 % map.width;
+    pos.y = dest_idx as i32 / map.width;
+
+    if let Some(vs) = ecs.write_storage::<Viewshed>().get_mut(entity) {
+        vs.dirty = true;
+    }
+    ecs.write_storage::<EntityMoved>()
+        .insert(entity, EntityMoved {})
+        .expect("Unable to insert");
+    if entity == *ecs.fetch::<Entity>() {
+        *ecs.write_resource::<Point>() = Point::new(pos.x, pos.y);
+    }
+}
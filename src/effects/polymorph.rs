@@ -0,0 +1,272 @@
+use super::*;
+use crate::raws::{mob_form, random_beast_form, RAWS};
+use crate::{
+    mana_at_level, npc_hp, player_hp_at_level, Ally, Attribute, Attributes, CreatureForm, Duration,
+    EquipmentChanged, Faction, Map, Name, NaturalAttackDefense, Player, Pool, Pools, Renderable,
+    StatusEffect, StatusKind, TrueForm,
+};
+
+pub fn polymorph(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::Polymorph { form, turns } = &effect.effect_type {
+        if ecs.read_storage::<Pools>().get(target).is_none() {
+            return;
+        }
+        let key = if form == "random" {
+            let Some(key) = random_beast_form(&RAWS.lock().unwrap()) else {
+                return;
+            };
+            key
+        } else {
+            form.clone()
+        };
+        let Some(new_form) = mob_form(&RAWS.lock().unwrap(), &key) else {
+            rltk::console::log(format!(
+                "Warning: cannot polymorph into unknown mob [{}]",
+                key
+            ));
+            return;
+        };
+        let status_name = format!("{} Form", key);
+
+        // Changing again keeps the true form from the first change, not the shape in between
+        if let Some(status) = polymorph_status(ecs, target) {
+            if let Some(true_form) = ecs.write_storage::<TrueForm>().get_mut(status) {
+                bank_growth(ecs, target, true_form);
+                true_form.borrowed = new_form.attributes.clone();
+            }
+            ecs.write_storage::<Name>()
+                .insert(status, Name { name: status_name })
+                .expect("Unable to insert");
+            ecs.write_storage::<Duration>()
+                .insert(status, Duration { turns: *turns })
+                .expect("Unable to insert");
+        } else {
+            let Some(original) = current_form(ecs, target) else {
+                return;
+            };
+            let level = ecs
+                .read_storage::<Pools>()
+                .get(target)
+                .map_or(1, |stats| stats.level);
+            match apply_status(ecs, target, StatusKind::Polymorphed, &status_name, *turns) {
+                StatusApplied::Immune => return,
+                StatusApplied::New(status) | StatusApplied::Stacked(status) => {
+                    ecs.write_storage::<TrueForm>()
+                        .insert(
+                            status,
+                            TrueForm {
+                                form: original,
+                                level,
+                                borrowed: new_form.attributes.clone(),
+                            },
+                        )
+                        .expect("Unable to insert");
+                }
+            }
+        }
+
+        take_form(ecs, target, &new_form);
+        announce(ecs, target, &format!("turns into a {}!", key));
+    }
+}
+
+/// Puts a polymorphed creature back the way it was. A creature forced out of its borrowed
+/// shape by a killing blow comes back with the health it had when it changed.
+pub fn revert_form(ecs: &mut World, target: Entity, fatal: bool) {
+    let Some(status) = polymorph_status(ecs, target) else {
+        return;
+    };
+    let Some(mut true_form) = ecs.read_storage::<TrueForm>().get(status).cloned() else {
+        return;
+    };
+    bank_growth(ecs, target, &mut true_form);
+    let true_form = true_form.form;
+
+    take_form(ecs, target, &true_form);
+    if fatal {
+        if let Some(pools) = ecs.write_storage::<Pools>().get_mut(target) {
+            pools.hit_points.current = i32::max(1, true_form.hit_points.current);
+        }
+    }
+
+    // The status goes at once, so nothing later this turn mistakes it for a live polymorph
+    ecs.write_storage::<StatusEffect>().remove(status);
+    ecs.entities().delete(status).expect("Unable to delete");
+    announce(ecs, target, "returns to its true form!");
+}
+
+/// Called before the dead are cleared away: anyone killed while polymorphed survives as themselves
+pub fn revert_fatal_forms(ecs: &mut World) {
+    let dying: Vec<Entity> = {
+        let statuses = ecs.read_storage::<StatusEffect>();
+        let true_forms = ecs.read_storage::<TrueForm>();
+        let pools = ecs.read_storage::<Pools>();
+        (&statuses, &true_forms)
+            .join()
+            .filter(|(status, _)| {
+                pools
+                    .get(status.target)
+                    .is_some_and(|stats| stats.hit_points.current < 1)
+            })
+            .map(|(status, _)| status.target)
+            .collect()
+    };
+    for target in dying {
+        revert_form(ecs, target, true);
+    }
+}
+
+/// The polymorph status currently holding the target, if any
+pub fn polymorph_status(ecs: &World, target: Entity) -> Option<Entity> {
+    let entities = ecs.entities();
+    let statuses = ecs.read_storage::<StatusEffect>();
+    let true_forms = ecs.read_storage::<TrueForm>();
+    (&entities, &statuses, &true_forms)
+        .join()
+        .find(|(_, status, _)| status.target == target)
+        .map(|(entity, _, _)| entity)
+}
+
+/// Carries levels and attribute points earned in a borrowed shape over to the true form,
+/// the same way a level up would have grown it
+fn bank_growth(ecs: &World, target: Entity, true_form: &mut TrueForm) {
+    let attributes = ecs.read_storage::<Attributes>();
+    let pools = ecs.read_storage::<Pools>();
+    let (Some(current), Some(stats)) = (attributes.get(target), pools.get(target)) else {
+        return;
+    };
+
+    let grow = |attr: &mut Attribute, now: &Attribute, then: &Attribute| {
+        attr.base += now.base - then.base;
+    };
+    let form = &mut true_form.form;
+    grow(
+        &mut form.attributes.might,
+        &current.might,
+        &true_form.borrowed.might,
+    );
+    grow(
+        &mut form.attributes.fitness,
+        &current.fitness,
+        &true_form.borrowed.fitness,
+    );
+    grow(
+        &mut form.attributes.quickness,
+        &current.quickness,
+        &true_form.borrowed.quickness,
+    );
+    grow(
+        &mut form.attributes.intelligence,
+        &current.intelligence,
+        &true_form.borrowed.intelligence,
+    );
+
+    if stats.level > true_form.level {
+        let fitness = form.attributes.fitness.base + form.attributes.fitness.modifiers;
+        let intelligence =
+            form.attributes.intelligence.base + form.attributes.intelligence.modifiers;
+        form.hit_points.max = if ecs.read_storage::<Player>().get(target).is_some() {
+            player_hp_at_level(fitness, stats.level)
+        } else {
+            npc_hp(fitness, stats.level)
+        };
+        form.mana.max = mana_at_level(intelligence, stats.level);
+    }
+
+    true_form.level = stats.level;
+    true_form.borrowed = current.clone();
+}
+
+fn current_form(ecs: &World, target: Entity) -> Option<CreatureForm> {
+    let renderables = ecs.read_storage::<Renderable>();
+    let pools = ecs.read_storage::<Pools>();
+    let renderable = renderables.get(target)?;
+    let stats = pools.get(target)?;
+    Some(CreatureForm {
+        glyph: renderable.glyph,
+        fg: renderable.fg,
+        natural: ecs
+            .read_storage::<NaturalAttackDefense>()
+            .get(target)
+            .cloned(),
+        attributes: ecs
+            .read_storage::<Attributes>()
+            .get(target)
+            .cloned()
+            .unwrap_or_default(),
+        hit_points: stats.hit_points.clone(),
+        mana: stats.mana.clone(),
+        faction: ecs
+            .read_storage::<Faction>()
+            .get(target)
+            .map(|faction| faction.name.clone()),
+    })
+}
+
+/// Swaps a form onto the target; level, experience, skills and belongings stay as they are
+fn take_form(ecs: &mut World, target: Entity, form: &CreatureForm) {
+    if let Some(renderable) = ecs.write_storage::<Renderable>().get_mut(target) {
+        renderable.glyph = form.glyph;
+        renderable.fg = form.fg;
+    }
+
+    let mut naturals = ecs.write_storage::<NaturalAttackDefense>();
+    if let Some(natural) = &form.natural {
+        naturals
+            .insert(target, natural.clone())
+            .expect("Unable to insert");
+    } else {
+        naturals.remove(target);
+    }
+
+    ecs.write_storage::<Attributes>()
+        .insert(target, form.attributes.clone())
+        .expect("Unable to insert");
+
+    // Wounds carry across: a creature at half health stays at half health
+    if let Some(pools) = ecs.write_storage::<Pools>().get_mut(target) {
+        rescale(&mut pools.hit_points, form.hit_points.max);
+        rescale(&mut pools.mana, form.mana.max);
+    }
+
+    // The player and their followers never change sides, whatever they look like
+    if let Some(faction) = &form.faction {
+        if ecs.read_storage::<Player>().get(target).is_none()
+            && ecs.read_storage::<Ally>().get(target).is_none()
+        {
+            ecs.write_storage::<Faction>()
+                .insert(
+                    target,
+                    Faction {
+                        name: faction.clone(),
+                    },
+                )
+                .expect("Unable to insert");
+        }
+    }
+
+    ecs.write_storage::<EquipmentChanged>()
+        .insert(target, EquipmentChanged {})
+        .expect("Unable to insert");
+}
+
+fn rescale(pool: &mut Pool, max: i32) {
+    let fraction = if pool.max > 0 {
+        pool.current as f32 / pool.max as f32
+    } else {
+        1.0
+    };
+    pool.max = max;
+    pool.current = i32::min(max, (max as f32 * fraction).ceil() as i32);
+}
+
+fn announce(ecs: &World, target: Entity, text: &str) {
+    let visible = entity_position(ecs, target)
+        .is_some_and(|idx| ecs.fetch::<Map>().visible_tiles[idx as usize]);
+    if let (Some(name), true) = (ecs.read_storage::<Name>().get(target), visible) {
+        crate::gamelog::Logger::new()
+            .npc_name(&name.name)
+            .append(text)
+            .log();
+    }
+}
@@ -0,0 +1,357 @@
+use super::*;
+use crate::map::Map;
+use crate::{Faction, Player, Pools, Position};
+use serde::{Deserialize, Serialize};
+
+/// One step of a scripted effect, compiled from the raws. Control nodes decide who gets
+/// what; the rest become ordinary effects on the queue.
+#[derive(Serialize, Deserialize, Clone)]
+pub enum EffectNode {
+    Sequence(Vec<EffectNode>),
+    Chance {
+        percent: i32,
+        effect: Box<EffectNode>,
+    },
+    IfTarget {
+        condition: TargetCondition,
+        then: Box<EffectNode>,
+        otherwise: Option<Box<EffectNode>>,
+    },
+    Area {
+        shape: AreaShape,
+        effect: Box<EffectNode>,
+    },
+    OnKill(Box<EffectNode>),
+    Damage {
+        n_dice: i32,
+        die_type: i32,
+        bonus: i32,
+        damage_type: DamageType,
+    },
+    Healing {
+        amount: i32,
+    },
+    Mana {
+        amount: i32,
+    },
+    Confusion {
+        turns: i32,
+    },
+    Slow {
+        initiative_penalty: f32,
+        turns: i32,
+    },
+    DamageOverTime {
+        damage: i32,
+        damage_type: DamageType,
+        turns: i32,
+    },
+    Attributes {
+        name: String,
+        bonus: AttributeBonus,
+        turns: i32,
+    },
+    Stun {
+        turns: i32,
+    },
+    Knockback {
+        distance: i32,
+    },
+    Pull {
+        distance: i32,
+    },
+    Blink,
+    RandomTeleport {
+        range: i32,
+    },
+    SwapPlaces,
+    Particle {
+        glyph: rltk::FontCharType,
+        fg: rltk::RGB,
+        lifespan: f32,
+    },
+    Status {
+        kind: StatusKind,
+        turns: i32,
+        on_apply: Option<Box<EffectNode>>,
+        on_tick: Option<Box<EffectNode>>,
+        on_expire: Option<Box<EffectNode>>,
+    },
+    Hazard {
+        kind: HazardKind,
+        turns: i32,
+    },
+    Summon {
+        mob: String,
+        count: i32,
+        duration: i32,
+        faction: String,
+    },
+    Polymorph {
+        form: String,
+        turns: i32,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum TargetCondition {
+    Faction(String),
+    HealthBelow(i32),
+    HealthAbove(i32),
+    IsPlayer,
+    Dead,
+    Not(Box<TargetCondition>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum AreaShape {
+    Circle { radius: i32 },
+    Square { radius: i32 },
+}
+
+/// Walks a script, queueing the effects it describes against the given targets
+pub fn run_script(ecs: &World, creator: Option<Entity>, node: &EffectNode, targets: &Targets) {
+    match node {
+        EffectNode::Sequence(nodes) => nodes
+            .iter()
+            .for_each(|node| run_script(ecs, creator, node, targets)),
+        EffectNode::Chance { percent, effect } => {
+            if crate::tutorial::rng::roll_dice(1, 100) <= *percent {
+                run_script(ecs, creator, effect, targets);
+            }
+        }
+        EffectNode::IfTarget {
+            condition,
+            then,
+            otherwise,
+        } => {
+            let (matching, rest): (Vec<Entity>, Vec<Entity>) = target_entities(ecs, targets)
+                .into_iter()
+                .partition(|target| condition_holds(ecs, *target, condition));
+            if !matching.is_empty() {
+                let targets = Targets::TargetList { targets: matching };
+                run_script(ecs, creator, then, &targets);
+            }
+            if let (Some(otherwise), false) = (otherwise, rest.is_empty()) {
+                let targets = Targets::TargetList { targets: rest };
+                run_script(ecs, creator, otherwise, &targets);
+            }
+        }
+        EffectNode::Area { shape, effect } => {
+            let tiles = area_tiles(ecs, targets, *shape);
+            if !tiles.is_empty() {
+                run_script(ecs, creator, effect, &Targets::Tiles { tiles });
+            }
+        }
+        EffectNode::OnKill(effect) => {
+            // Queued behind whatever damage came first in the script, so it sees the result
+            add_effect(
+                creator,
+                EffectType::Script {
+                    node: EffectNode::IfTarget {
+                        condition: TargetCondition::Dead,
+                        then: effect.clone(),
+                        otherwise: None,
+                    },
+                },
+                targets.clone(),
+            );
+        }
+        _ => {
+            if let Some(mut effect_type) = leaf_effect(node) {
+                if let EffectType::Summon { faction, .. } = &mut effect_type {
+                    *faction = summon_faction(ecs, creator, faction);
+                }
+                add_effect(creator, effect_type, targets.clone());
+            }
+        }
+    }
+}
+
+fn leaf_effect(node: &EffectNode) -> Option<EffectType> {
+    Some(match node {
+        EffectNode::Damage {
+            n_dice,
+            die_type,
+            bonus,
+            damage_type,
+        } => EffectType::Damage {
+            amount: i32::max(
+                0,
+                crate::tutorial::rng::roll_dice(*n_dice, *die_type) + bonus,
+            ),
+            damage_type: Some(*damage_type),
+        },
+        EffectNode::Healing { amount } => EffectType::Healing { amount: *amount },
+        EffectNode::Mana { amount } => EffectType::Mana { amount: *amount },
+        EffectNode::Confusion { turns } => EffectType::Confusion { turns: *turns },
+        EffectNode::Slow {
+            initiative_penalty,
+            turns,
+        } => EffectType::Slow {
+            initiative_penalty: *initiative_penalty,
+            turns: *turns,
+        },
+        EffectNode::DamageOverTime {
+            damage,
+            damage_type,
+            turns,
+        } => EffectType::DamageOverTime {
+            damage: *damage,
+            damage_type: *damage_type,
+            turns: *turns,
+        },
+        EffectNode::Attributes { name, bonus, turns } => EffectType::AttributeEffect {
+            bonus: bonus.clone(),
+            resistances: None,
+            name: name.clone(),
+            duration: *turns,
+        },
+        EffectNode::Stun { turns } => EffectType::Stun { turns: *turns },
+        EffectNode::Knockback { distance } => EffectType::Knockback {
+            distance: *distance,
+        },
+        EffectNode::Pull { distance } => EffectType::Pull {
+            distance: *distance,
+        },
+        EffectNode::Blink => EffectType::Blink,
+        EffectNode::RandomTeleport { range } => EffectType::RandomTeleport { range: *range },
+        EffectNode::SwapPlaces => EffectType::SwapPlaces,
+        EffectNode::Particle {
+            glyph,
+            fg,
+            lifespan,
+        } => EffectType::Particle {
+            glyph: *glyph,
+            fg: *fg,
+            bg: rltk::RGB::named(rltk::BLACK),
+            lifespan: *lifespan,
+        },
+        EffectNode::Status {
+            kind,
+            turns,
+            on_apply,
+            on_tick,
+            on_expire,
+        } => EffectType::Status {
+            kind: *kind,
+            turns: *turns,
+            on_apply: on_apply.as_deref().cloned(),
+            hooks: if on_tick.is_some() || on_expire.is_some() {
+                Some(StatusHooks {
+                    on_tick: on_tick.as_deref().cloned(),
+                    on_expire: on_expire.as_deref().cloned(),
+                })
+            } else {
+                None
+            },
+        },
+        EffectNode::Hazard { kind, turns } => EffectType::Hazard {
+            kind: *kind,
+            turns: *turns,
+        },
+        EffectNode::Summon {
+            mob,
+            count,
+            duration,
+            faction,
+        } => EffectType::Summon {
+            mob: mob.clone(),
+            count: *count,
+            duration: *duration,
+            faction: faction.clone(),
+        },
+        EffectNode::Polymorph { form, turns } => EffectType::Polymorph {
+            form: form.clone(),
+            turns: *turns,
+        },
+        _ => return None,
+    })
+}
+
+/// The creatures a set of targets refers to; tiles count whoever is standing on them
+fn target_entities(ecs: &World, targets: &Targets) -> Vec<Entity> {
+    let pools = ecs.read_storage::<Pools>();
+    let mut result = Vec::new();
+    let mut on_tile = |tile_idx: i32| {
+        crate::spatial::for_each_tile_content(tile_idx as usize, |entity| {
+            if pools.get(entity).is_some() {
+                result.push(entity);
+            }
+        });
+    };
+    match targets {
+        Targets::Single { target } => return vec![*target],
+        Targets::TargetList { targets } => return targets.clone(),
+        Targets::Tile { tile_idx } => on_tile(*tile_idx),
+        Targets::Tiles { tiles } => tiles.iter().for_each(|tile_idx| on_tile(*tile_idx)),
+    }
+    result
+}
+
+fn condition_holds(ecs: &World, target: Entity, condition: &TargetCondition) -> bool {
+    let health_percent = || {
+        ecs.read_storage::<Pools>()
+            .get(target)
+            .map(|p| p.hit_points.current * 100 / i32::max(1, p.hit_points.max))
+    };
+    match condition {
+        TargetCondition::Faction(name) => ecs
+            .read_storage::<Faction>()
+            .get(target)
+            .is_some_and(|f| f.name == *name),
+        TargetCondition::HealthBelow(percent) => health_percent().is_some_and(|hp| hp < *percent),
+        TargetCondition::HealthAbove(percent) => health_percent().is_some_and(|hp| hp > *percent),
+        TargetCondition::IsPlayer => ecs.read_storage::<Player>().get(target).is_some(),
+        TargetCondition::Dead => ecs
+            .read_storage::<Pools>()
+            .get(target)
+            .is_some_and(|p| p.hit_points.current < 1),
+        TargetCondition::Not(inner) => !condition_holds(ecs, target, inner),
+    }
+}
+
+/// Grows each target into an area around it, without counting any tile twice
+fn area_tiles(ecs: &World, targets: &Targets, shape: AreaShape) -> Vec<i32> {
+    let map = ecs.fetch::<Map>();
+    let positions = ecs.read_storage::<Position>();
+    let centers: Vec<i32> = match targets {
+        Targets::Single { target } => positions
+            .get(*target)
+            .map(|pos| map.xy_idx(pos.x, pos.y) as i32)
+            .into_iter()
+            .collect(),
+        Targets::TargetList { targets } => targets
+            .iter()
+            .filter_map(|target| positions.get(*target))
+            .map(|pos| map.xy_idx(pos.x, pos.y) as i32)
+            .collect(),
+        Targets::Tile { tile_idx } => vec![*tile_idx],
+        Targets::Tiles { tiles } => tiles.clone(),
+    };
+
+    let mut tiles = Vec::new();
+    for center in centers.iter() {
+        let center = rltk::Point::new(center % map.width, center / map.width);
+        let area = match shape {
+            AreaShape::Circle { radius } => aoe_tiles(&map, center, radius),
+            AreaShape::Square { radius } => {
+                let mut square = Vec::new();
+                for y in center.y - radius..=center.y + radius {
+                    for x in center.x - radius..=center.x + radius {
+                        if x > 0 && x < map.width - 1 && y > 0 && y < map.height - 1 {
+                            square.push(map.xy_idx(x, y) as i32);
+                        }
+                    }
+                }
+                square
+            }
+        };
+        for tile in area {
+            if !tiles.contains(&tile) {
+                tiles.push(tile);
+            }
+        }
+    }
+    tiles
+}
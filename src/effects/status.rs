@@ -0,0 +1,106 @@
+use super::*;
+use crate::{
+    Duration, Name, SerializeMe, StackPolicy, StatusEffect, StatusHooks, StatusImmunities,
+    StatusKind,
+};
+use specs::saveload::{MarkedBuilder, SimpleMarker};
+
+pub enum StatusApplied {
+    /// The target shrugged it off
+    Immune,
+    /// A fresh status entity, ready for whatever components make it work
+    New(Entity),
+    /// An existing status the new one was folded into
+    Stacked(Entity),
+}
+
+/// Puts a status on the target, or folds it into a matching one it already has
+/// according to the kind's stacking policy.
+pub fn apply_status(
+    ecs: &mut World,
+    target: Entity,
+    kind: StatusKind,
+    name: &str,
+    turns: i32,
+) -> StatusApplied {
+    if ecs
+        .read_storage::<StatusImmunities>()
+        .get(target)
+        .is_some_and(|immune| immune.kinds.contains(&kind))
+    {
+        if let Some(target_name) = ecs.read_storage::<Name>().get(target) {
+            crate::gamelog::Logger::new()
+                .npc_name(&target_name.name)
+                .append(format!(
+                    "is immune to being {}.",
+                    kind.name().to_lowercase()
+                ))
+                .log();
+        }
+        return StatusApplied::Immune;
+    }
+
+    if kind.stacking() != StackPolicy::Independent {
+        let entities = ecs.entities();
+        let statuses = ecs.read_storage::<StatusEffect>();
+        let names = ecs.read_storage::<Name>();
+        let mut durations = ecs.write_storage::<Duration>();
+        for (entity, status, status_name, duration) in
+            (&entities, &statuses, &names, &mut durations).join()
+        {
+            if status.target == target && status.kind == kind && status_name.name == name {
+                duration.turns = i32::max(duration.turns, turns);
+                return StatusApplied::Stacked(entity);
+            }
+        }
+    }
+
+    StatusApplied::New(
+        ecs.create_entity()
+            .with(StatusEffect { target, kind })
+            .with(Duration { turns })
+            .with(Name {
+                name: name.to_string(),
+            })
+            .marked::<SimpleMarker<SerializeMe>>()
+            .build(),
+    )
+}
+
+pub fn add_status(ecs: &mut World, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::Status {
+        kind,
+        turns,
+        on_apply,
+        hooks,
+    } = &effect.effect_type
+    {
+        let status = match apply_status(ecs, target, *kind, kind.name(), *turns) {
+            StatusApplied::Immune => return,
+            StatusApplied::New(status) | StatusApplied::Stacked(status) => status,
+        };
+        if let Some(hooks) = hooks {
+            ecs.write_storage::<StatusHooks>()
+                .insert(status, hooks.clone())
+                .expect("Unable to insert");
+        }
+        if let Some(node) = on_apply {
+            add_effect(
+                effect.creator,
+                EffectType::Script { node: node.clone() },
+                Targets::Single { target },
+            );
+        }
+    }
+}
+
+/// Which lingering condition a damage type leaves behind
+pub fn damage_over_time_kind(damage_type: DamageType) -> StatusKind {
+    match damage_type {
+        DamageType::Slashing | DamageType::Piercing | DamageType::Blunt => StatusKind::Bleeding,
+        DamageType::Fire => StatusKind::Burning,
+        DamageType::Cold => StatusKind::Chilled,
+        DamageType::Poison => StatusKind::Poisoned,
+        DamageType::Arcane => StatusKind::Withering,
+    }
+}
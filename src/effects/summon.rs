@@ -0,0 +1,151 @@
+use super::*;
+use crate::map::Map;
+use crate::raws::{spawn_named_mob, SpawnType, RAWS};
+use crate::{tile_walkable, Ally, AllyCommand, Faction, LootTable, Player, Summoned};
+
+// How far from the target a summoned creature may appear
+const SUMMON_RADIUS: i32 = 3;
+
+pub fn summon(ecs: &mut World, effect: &EffectSpawner) {
+    if let EffectType::Summon {
+        mob,
+        count,
+        duration,
+        faction,
+    } = &effect.effect_type
+    {
+        // Summons happen once per effect, around the middle of whatever was targeted
+        let center = match &effect.targets {
+            Targets::Tile { tile_idx } => Some(*tile_idx),
+            Targets::Tiles { tiles } => tiles.first().copied(),
+            Targets::Single { target } => entity_position(ecs, *target),
+            Targets::TargetList { targets } => targets
+                .first()
+                .and_then(|target| entity_position(ecs, *target)),
+        };
+        let Some(center) = center else {
+            return;
+        };
+
+        for _ in 0..*count {
+            let Some(spot) = free_tile_near(&ecs.fetch::<Map>(), center) else {
+                break;
+            };
+            let (x, y, visible) = {
+                let map = ecs.fetch::<Map>();
+                (
+                    spot as i32 % map.width,
+                    spot as i32 / map.width,
+                    map.visible_tiles[spot],
+                )
+            };
+            let Some(creature) = spawn_named_mob(
+                &RAWS.lock().unwrap(),
+                ecs,
+                mob,
+                SpawnType::AtPosition { x, y },
+            ) else {
+                rltk::console::log(format!("Warning: cannot summon unknown mob [{}]", mob));
+                return;
+            };
+            crate::spatial::set_blocked(spot, true);
+
+            // Conjured creatures don't carry treasure
+            ecs.write_storage::<LootTable>().remove(creature);
+            ecs.write_storage::<Summoned>()
+                .insert(
+                    creature,
+                    Summoned {
+                        turns: if *duration > 0 { Some(*duration) } else { None },
+                    },
+                )
+                .expect("Unable to insert");
+            take_sides(ecs, creature, faction);
+
+            add_effect(
+                None,
+                EffectType::Particle {
+                    glyph: rltk::to_cp437('☼'),
+                    fg: rltk::RGB::named(rltk::MAGENTA),
+                    bg: rltk::RGB::named(rltk::BLACK),
+                    lifespan: 300.0,
+                },
+                Targets::Tile {
+                    tile_idx: spot as i32,
+                },
+            );
+            if visible {
+                crate::gamelog::Logger::new()
+                    .npc_name(mob)
+                    .append("appears!")
+                    .log();
+            }
+        }
+    }
+}
+
+/// Works out which side a "caster" summoning is on, while the caster is still around to ask:
+/// the player and their followers summon for the player, anyone else for their own faction.
+/// Anything other than "caster" names a faction outright.
+pub fn summon_faction(ecs: &World, creator: Option<Entity>, faction: &str) -> String {
+    if faction != "caster" {
+        return faction.to_string();
+    }
+    let Some(creator) = creator else {
+        return faction.to_string();
+    };
+    if ecs.read_storage::<Player>().get(creator).is_some()
+        || ecs.read_storage::<Ally>().get(creator).is_some()
+    {
+        "Player".to_string()
+    } else if let Some(caster_faction) = ecs.read_storage::<Faction>().get(creator) {
+        caster_faction.name.clone()
+    } else {
+        faction.to_string()
+    }
+}
+
+/// Signs a summoned creature up with a faction from `summon_faction`. A "caster" nobody
+/// could pin down leaves the creature with its own faction.
+fn take_sides(ecs: &mut World, creature: Entity, faction: &str) {
+    if faction == "caster" {
+        return;
+    }
+    if faction == "Player" {
+        ecs.write_storage::<Ally>()
+            .insert(
+                creature,
+                Ally {
+                    command: AllyCommand::Follow,
+                },
+            )
+            .expect("Unable to insert");
+    }
+    ecs.write_storage::<Faction>()
+        .insert(
+            creature,
+            Faction {
+                name: faction.to_string(),
+            },
+        )
+        .expect("Unable to insert");
+}
+
+fn free_tile_near(map: &Map, center: i32) -> Option<usize> {
+    let cx = center % map.width;
+    let cy = center / map.width;
+    for radius in 0..=SUMMON_RADIUS {
+        for y in cy - radius..=cy + radius {
+            for x in cx - radius..=cx + radius {
+                if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+                    continue;
+                }
+                let idx = map.xy_idx(x, y);
+                if tile_walkable(map.tiles[idx]) && !crate::spatial::is_blocked(idx) {
+                    return Some(idx);
+                }
+            }
+        }
+    }
+    None
+}
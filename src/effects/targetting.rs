@@ -1,5 +1,6 @@
-use crate::components::{Equipped, InBackpack, Position};
+use crate::components::{AoeShape, Equipped, InBackpack, Position};
 use crate::map::Map;
+use rltk::{BaseMap, DistanceAlg, Point};
 use specs::prelude::*;
 
 pub fn entity_position(ecs: &World, target: Entity) -> Option<i32> {
@@ -21,6 +22,94 @@ pub fn aoe_tiles(map: &Map, target: rltk::Point, radius: i32) -> Vec<i32> {
         .collect()
 }
 
+/// The tiles an area effect covers when aimed from `origin` at `target`
+pub fn shape_tiles(map: &Map, origin: Point, target: Point, shape: AoeShape) -> Vec<i32> {
+    // Aimed shapes need somewhere to aim; used on the spot they just go off in a circle
+    let aimed = origin != target;
+    let tiles: Vec<Point> = match shape {
+        AoeShape::Circle { radius } => return aoe_tiles(map, target, radius),
+        AoeShape::Blast { radius } => return aoe_tiles(map, origin, radius),
+        AoeShape::Cone { length, .. } | AoeShape::Line { length } | AoeShape::Wall { length }
+            if !aimed =>
+        {
+            return aoe_tiles(map, origin, length / 2)
+        }
+        AoeShape::Ring { radius } => rltk::field_of_view(target, radius + 1, map)
+            .into_iter()
+            .filter(|p| {
+                let distance = DistanceAlg::Pythagoras.distance2d(target, *p);
+                (distance - radius as f32).abs() < 0.5
+            })
+            .collect(),
+        AoeShape::Cone { length, angle } => {
+            let aim = direction(origin, target);
+            let half_angle = (angle as f32 / 2.0).to_radians();
+            rltk::field_of_view(origin, length, map)
+                .into_iter()
+                .filter(|p| {
+                    *p != origin && {
+                        let to_tile = direction(origin, *p);
+                        let dot = aim.0 * to_tile.0 + aim.1 * to_tile.1;
+                        dot.clamp(-1.0, 1.0).acos() <= half_angle
+                    }
+                })
+                .collect()
+        }
+        AoeShape::Line { length } => {
+            // A beam carries on past the target until it runs out or hits a wall
+            let aim = direction(origin, target);
+            let end = Point::new(
+                origin.x + (aim.0 * length as f32).round() as i32,
+                origin.y + (aim.1 * length as f32).round() as i32,
+            );
+            let mut beam = Vec::new();
+            for p in rltk::line2d(rltk::LineAlg::Bresenham, origin, end)
+                .into_iter()
+                .skip(1)
+            {
+                if !in_bounds(map, p) || map.is_opaque(map.xy_idx(p.x, p.y)) {
+                    break;
+                }
+                beam.push(p);
+            }
+            beam
+        }
+        AoeShape::Wall { length } => {
+            // Laid across the line of aim, centred on the target. The cross direction is
+            // snapped to one of the eight compass steps so every section is its own tile.
+            let aim = direction(origin, target);
+            let across = (
+                ((-aim.1).round() as i32).signum(),
+                (aim.0.round() as i32).signum(),
+            );
+            let start = -(length - 1) / 2;
+            (start..start + length)
+                .map(|step| Point::new(target.x + across.0 * step, target.y + across.1 * step))
+                .filter(|p| in_bounds(map, *p) && !map.is_opaque(map.xy_idx(p.x, p.y)))
+                .collect()
+        }
+    };
+
+    let mut result = Vec::new();
+    for p in tiles.iter().filter(|p| in_bounds(map, **p)) {
+        let idx = map.xy_idx(p.x, p.y) as i32;
+        if !result.contains(&idx) {
+            result.push(idx);
+        }
+    }
+    result
+}
+
+fn direction(from: Point, to: Point) -> (f32, f32) {
+    let (dx, dy) = ((to.x - from.x) as f32, (to.y - from.y) as f32);
+    let length = f32::max(0.001, (dx * dx + dy * dy).sqrt());
+    (dx / length, dy / length)
+}
+
+fn in_bounds(map: &Map, p: Point) -> bool {
+    p.x > 0 && p.x < map.width - 1 && p.y > 0 && p.y < map.height - 1
+}
+
 pub fn find_item_position(ecs: &World, target: Entity, creator: Option<Entity>) -> Option<i32> {
     let positions = ecs.read_storage::<Position>();
     let map = ecs.fetch::<Map>();
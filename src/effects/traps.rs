@@ -0,0 +1,54 @@
+use super::*;
+use crate::map::Map;
+use crate::raws::{spawn_named_entity, SpawnType, RAWS};
+use crate::{tile_walkable, EntryTrigger, Hidden, Name};
+
+/// Is there room on this tile for a trap: open floor, nobody standing on it, no trap already?
+pub fn trap_site_open(map: &Map, tile_idx: usize, triggers: &ReadStorage<EntryTrigger>) -> bool {
+    let mut has_trigger = false;
+    crate::spatial::for_each_tile_content(tile_idx, |entity| {
+        if triggers.get(entity).is_some() {
+            has_trigger = true;
+        }
+    });
+    tile_walkable(map.tiles[tile_idx]) && !crate::spatial::is_blocked(tile_idx) && !has_trigger
+}
+
+/// Sets a trap prop on the tile, in plain sight of whoever set it
+pub fn deploy_trap(ecs: &mut World, effect: &EffectSpawner, tile_idx: i32) {
+    if let EffectType::DeployTrap { prop } = &effect.effect_type {
+        let (x, y, open) = {
+            let map = ecs.fetch::<Map>();
+            (
+                tile_idx % map.width,
+                tile_idx / map.width,
+                trap_site_open(&map, tile_idx as usize, &ecs.read_storage::<EntryTrigger>()),
+            )
+        };
+        if !open {
+            crate::gamelog::Logger::new()
+                .append("There is no room to set a trap there.")
+                .log();
+            return;
+        }
+
+        let Some(trap) = spawn_named_entity(
+            &RAWS.lock().unwrap(),
+            ecs,
+            prop,
+            SpawnType::AtPosition { x, y },
+        ) else {
+            rltk::console::log(format!("Warning: cannot deploy unknown trap [{}]", prop));
+            return;
+        };
+        ecs.write_storage::<Hidden>().remove(trap);
+        crate::spatial::index_entity(trap, tile_idx as usize, false);
+
+        if let Some(name) = ecs.read_storage::<Name>().get(trap) {
+            crate::gamelog::Logger::new()
+                .append("You set a")
+                .item_name(&name.name)
+                .log();
+        }
+    }
+}
@@ -22,10 +22,16 @@ pub fn item_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, ec
         return;
     }
 
-    // If it was a consumable, then it gets deleted
-    if let Some(c) = ecs.read_storage::<Consumable>().get(item) {
+    // If it was a consumable, then it gets deleted - or one comes off the stack
+    if let Some(c) = ecs.write_storage::<Consumable>().get_mut(item) {
         if c.max_charges == 0 {
-            ecs.entities().delete(item).expect("Delete failed");
+            let mut stacks = ecs.write_storage::<Stackable>();
+            if let Some(stack) = stacks.get_mut(item).filter(|s| s.quantity > 1) {
+                stack.quantity -= 1;
+                c.charges = 1;
+            } else {
+                ecs.entities().delete(item).expect("Delete failed");
+            }
         }
     }
 }
@@ -185,10 +191,17 @@ fn event_trigger(
 
     // Damage
     if let Some(damage) = ecs.read_storage::<InflictsDamage>().get(entity) {
+        let attack = crate::spell_profile(damage.damage, damage.damage_type);
+        let result = crate::resolve_attack(
+            &attack,
+            &crate::DefenseProfile::default(),
+            &mut crate::GameDice,
+        );
         add_effect(
             creator,
             EffectType::Damage {
-                amount: damage.damage,
+                amount: result.damage.total(),
+                damage_type: Some(damage.damage_type),
             },
             targets.clone(),
         );
@@ -215,6 +228,7 @@ fn event_trigger(
             creator,
             EffectType::Slow {
                 initiative_penalty: slow.initiative_penalty,
+                turns: DEFAULT_SLOW_TURNS,
             },
             targets.clone(),
         );
@@ -227,6 +241,101 @@ fn event_trigger(
             creator,
             EffectType::DamageOverTime {
                 damage: damage.damage,
+                damage_type: damage.damage_type,
+                turns: DEFAULT_DAMAGE_OVER_TIME_TURNS,
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+
+    // Lingering hazards left on the ground
+    if let Some(hazard) = ecs.read_storage::<LeavesHazard>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::Hazard {
+                kind: hazard.kind,
+                turns: hazard.turns,
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+
+    // Summoning
+    if let Some(summon) = ecs.read_storage::<SummonsCreature>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::Summon {
+                mob: summon.mob.clone(),
+                count: summon.count,
+                duration: summon.duration,
+                faction: summon_faction(ecs, creator, &summon.faction),
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+
+    // Shapechanging
+    if let Some(polymorph) = ecs.read_storage::<Polymorphs>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::Polymorph {
+                form: polymorph.form.clone(),
+                turns: polymorph.turns,
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+
+    // Setting traps
+    if let Some(trap) = ecs.read_storage::<DeploysTrap>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::DeployTrap {
+                prop: trap.prop.clone(),
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+
+    // Short-range movement
+    if ecs.read_storage::<Blink>().get(entity).is_some() {
+        add_effect(creator, EffectType::Blink, targets.clone());
+        did_something = true;
+    }
+    if let Some(teleport) = ecs.read_storage::<RandomTeleport>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::RandomTeleport {
+                range: teleport.range,
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+    if ecs.read_storage::<SwapPlaces>().get(entity).is_some() {
+        add_effect(creator, EffectType::SwapPlaces, targets.clone());
+        did_something = true;
+    }
+    if let Some(knockback) = ecs.read_storage::<Knockback>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::Knockback {
+                distance: knockback.distance,
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+    if let Some(pull) = ecs.read_storage::<Pull>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::Pull {
+                distance: pull.distance,
             },
             targets.clone(),
         );
@@ -248,13 +357,37 @@ fn event_trigger(
         did_something = true;
     }
 
-    // Attribute Modifiers
-    if let Some(attr) = ecs.read_storage::<AttributeBonus>().get(entity) {
+    // Scripted effects, described in the raws
+    if let Some(script) = ecs.read_storage::<EffectScript>().get(entity) {
+        add_effect(
+            creator,
+            EffectType::Script {
+                node: script.root.clone(),
+            },
+            targets.clone(),
+        );
+        did_something = true;
+    }
+
+    // Attribute Modifiers, and resistances granted by drinking or reading something
+    let attributes = ecs.read_storage::<AttributeBonus>().get(entity).cloned();
+    let resistances = if ecs.read_storage::<Consumable>().get(entity).is_some() {
+        ecs.read_storage::<Resistances>().get(entity).cloned()
+    } else {
+        None
+    };
+    if attributes.is_some() || resistances.is_some() {
         add_effect(
             creator,
             EffectType::AttributeEffect {
-                bonus: attr.clone(),
-                duration: 10,
+                bonus: attributes.unwrap_or(AttributeBonus {
+                    might: None,
+                    fitness: None,
+                    quickness: None,
+                    intelligence: None,
+                }),
+                resistances,
+                duration: DEFAULT_ATTRIBUTE_TURNS,
                 name: ecs.read_storage::<Name>().get(entity).unwrap().name.clone(),
             },
             targets.clone(),
@@ -307,13 +440,25 @@ fn spawn_line_particles(ecs: &World, start: i32, end: i32, part: &SpawnParticleL
 pub fn spell_trigger(creator: Option<Entity>, spell: Entity, targets: &Targets, ecs: &mut World) {
     let mut targetting = targets.clone();
     let mut self_destruct = false;
-    if let Some(template) = ecs.read_storage::<SpellTemplate>().get(spell) {
-        let mut pools = ecs.write_storage::<Pools>();
+    let template = ecs.read_storage::<SpellTemplate>().get(spell).cloned();
+    if let Some(template) = template {
         if let Some(caster) = creator {
-            if let Some(pool) = pools.get_mut(caster) {
-                if template.mana_cost <= pool.mana.current {
-                    pool.mana.current -= template.mana_cost;
+            // A creature's dying act costs nothing and never fails
+            let dying = ecs
+                .read_storage::<Pools>()
+                .get(caster)
+                .is_some_and(|pools| pools.hit_points.current < 1);
+            if !dying && !spend_mana(ecs, caster, spell, template.mana_cost) {
+                return;
+            }
+
+            // Trained casters can fumble; innate abilities always work
+            if !dying && ecs.read_storage::<KnownSpells>().get(caster).is_some() {
+                if crate::tutorial::rng::roll_dice(1, 100) <= spell_failure(ecs, caster, spell) {
+                    log_spell(ecs, caster, spell, "fizzles!");
+                    return;
                 }
+                practice_school(ecs, caster, template.school);
             }
 
             // Handle self-targeting override
@@ -322,7 +467,12 @@ pub fn spell_trigger(creator: Option<Entity>, spell: Entity, targets: &Targets,
                     let map = ecs.fetch::<Map>();
                     targetting = if let Some(aoe) = ecs.read_storage::<AreaOfEffect>().get(spell) {
                         Targets::Tiles {
-                            tiles: aoe_tiles(&map, rltk::Point::new(pos.x, pos.y), aoe.radius),
+                            tiles: shape_tiles(
+                                &map,
+                                rltk::Point::new(pos.x, pos.y),
+                                rltk::Point::new(pos.x, pos.y),
+                                aoe.shape,
+                            ),
                         }
                     } else {
                         Targets::Tile {
@@ -344,3 +494,97 @@ pub fn spell_trigger(creator: Option<Entity>, spell: Entity, targets: &Targets,
             .expect("Unable to delete owner");
     }
 }
+
+/// The chance, in percent, that this caster botches this spell
+pub fn spell_failure(ecs: &World, caster: Entity, spell: Entity) -> i32 {
+    let template = ecs.read_storage::<SpellTemplate>();
+    let Some(template) = template.get(spell) else {
+        return 0;
+    };
+    let magic_skill = ecs
+        .read_storage::<Skills>()
+        .get(caster)
+        .map_or(0, |skills| skill_bonus(Skill::Magic, skills));
+    let school_skill = ecs
+        .read_storage::<SchoolSkills>()
+        .get(caster)
+        .and_then(|schools| schools.levels.get(&template.school).copied())
+        .unwrap_or(0);
+    let intelligence_bonus = ecs
+        .read_storage::<Attributes>()
+        .get(caster)
+        .map_or(0, |attr| attr.intelligence.bonus);
+
+    let equipped = ecs.read_storage::<Equipped>();
+    let wearables = ecs.read_storage::<Wearable>();
+    let items = ecs.read_storage::<Item>();
+    let armor_weight: f32 = (&equipped, &wearables, &items)
+        .join()
+        .filter(|(equipped, _, _)| equipped.owner == caster)
+        .map(|(_, _, item)| item.weight_lbs)
+        .sum();
+
+    spell_failure_chance(
+        template.mana_cost,
+        magic_skill,
+        school_skill,
+        intelligence_bonus,
+        armor_weight,
+    )
+}
+
+fn spend_mana(ecs: &mut World, caster: Entity, spell: Entity, cost: i32) -> bool {
+    let has_mana = {
+        let mut pools = ecs.write_storage::<Pools>();
+        match pools.get_mut(caster) {
+            Some(pool) if pool.mana.current >= cost => {
+                pool.mana.current -= cost;
+                true
+            }
+            Some(_) => false,
+            None => true,
+        }
+    };
+    if !has_mana {
+        log_spell(ecs, caster, spell, "fails for lack of mana.");
+    }
+    has_mana
+}
+
+fn practice_school(ecs: &mut World, caster: Entity, school: SpellSchool) {
+    let mut schools = ecs.write_storage::<SchoolSkills>();
+    if let Some(schools) = schools.get_mut(caster) {
+        let level = schools.levels.get(&school).copied().unwrap_or(0);
+        let practice = schools.practice.entry(school).or_insert(0);
+        *practice += 1;
+        if *practice >= school_practice_needed(level) {
+            *practice = 0;
+            schools.levels.insert(school, level + 1);
+            if caster == *ecs.fetch::<Entity>() {
+                crate::gamelog::Logger::new()
+                    .color(rltk::MAGENTA)
+                    .append(format!(
+                        "Your grasp of {} improves to {}.",
+                        school.name(),
+                        level + 1
+                    ))
+                    .log();
+            }
+        }
+    }
+}
+
+fn log_spell(ecs: &World, caster: Entity, spell: Entity, what: &str) {
+    if caster != *ecs.fetch::<Entity>() {
+        return;
+    }
+    if let Some(name) = ecs.read_storage::<Name>().get(spell) {
+        crate::gamelog::Logger::new()
+            .append("Your")
+            .color(rltk::CYAN)
+            .append(&name.name)
+            .color(rltk::WHITE)
+            .append(what)
+            .log();
+    }
+}
@@ -26,22 +26,32 @@ fn main() -> rltk::BError {
         dispatcher: systems::build(),
     };
 
+    gs.ecs.register::<Ally>();
+    gs.ecs.register::<AllyTarget>();
     gs.ecs.register::<AlwaysTargetsSelf>();
+    gs.ecs.register::<Ammunition>();
     gs.ecs.register::<ApplyMove>();
     gs.ecs.register::<ApplyTeleport>();
     gs.ecs.register::<AreaOfEffect>();
     gs.ecs.register::<AttributeBonus>();
     gs.ecs.register::<Attributes>();
+    gs.ecs.register::<Bag>();
+    gs.ecs.register::<Blink>();
     gs.ecs.register::<BlocksTile>();
     gs.ecs.register::<BlocksVisibility>();
     gs.ecs.register::<Chasing>();
     gs.ecs.register::<Confusion>();
     gs.ecs.register::<Consumable>();
+    gs.ecs.register::<Container>();
+    gs.ecs.register::<ContainerTrap>();
     gs.ecs.register::<CursedItem>();
     gs.ecs.register::<DMSerializationHelper>();
     gs.ecs.register::<DamageOverTime>();
+    gs.ecs.register::<DeploysTrap>();
     gs.ecs.register::<Door>();
     gs.ecs.register::<Duration>();
+    gs.ecs.register::<Durability>();
+    gs.ecs.register::<EffectScript>();
     gs.ecs.register::<EntityMoved>();
     gs.ecs.register::<EntryTrigger>();
     gs.ecs.register::<EquipmentChanged>();
@@ -52,10 +62,13 @@ fn main() -> rltk::BError {
     gs.ecs.register::<HungerClock>();
     gs.ecs.register::<IdentifiedItem>();
     gs.ecs.register::<InBackpack>();
+    gs.ecs.register::<InContainer>();
     gs.ecs.register::<InflictsDamage>();
     gs.ecs.register::<Initiative>();
     gs.ecs.register::<Item>();
+    gs.ecs.register::<Knockback>();
     gs.ecs.register::<KnownSpells>();
+    gs.ecs.register::<LeavesHazard>();
     gs.ecs.register::<LightSource>();
     gs.ecs.register::<LootTable>();
     gs.ecs.register::<MagicItem>();
@@ -69,6 +82,7 @@ fn main() -> rltk::BError {
     gs.ecs.register::<OtherLevelPosition>();
     gs.ecs.register::<ParticleLifetime>();
     gs.ecs.register::<Player>();
+    gs.ecs.register::<Polymorphs>();
     gs.ecs.register::<Pools>();
     gs.ecs.register::<Position>();
     gs.ecs.register::<ProvidesFood>();
@@ -76,9 +90,17 @@ fn main() -> rltk::BError {
     gs.ecs.register::<ProvidesIdentification>();
     gs.ecs.register::<ProvidesMana>();
     gs.ecs.register::<ProvidesRemoveCurse>();
+    gs.ecs.register::<Pull>();
     gs.ecs.register::<Quips>();
+    gs.ecs.register::<RandomTeleport>();
     gs.ecs.register::<Ranged>();
+    gs.ecs.register::<Recruiter>();
     gs.ecs.register::<Renderable>();
+    gs.ecs.register::<RepairService>();
+    gs.ecs.register::<Resistances>();
+    gs.ecs.register::<Resting>();
+    gs.ecs.register::<Schedule>();
+    gs.ecs.register::<SchoolSkills>();
     gs.ecs.register::<SerializationHelper>();
     gs.ecs.register::<SimpleMarker<SerializeMe>>();
     gs.ecs.register::<SingleActivation>();
@@ -88,22 +110,36 @@ fn main() -> rltk::BError {
     gs.ecs.register::<SpawnParticleLine>();
     gs.ecs.register::<SpecialAbilities>();
     gs.ecs.register::<SpellTemplate>();
+    gs.ecs.register::<Stackable>();
     gs.ecs.register::<StatusEffect>();
+    gs.ecs.register::<StatusHooks>();
+    gs.ecs.register::<StatusImmunities>();
+    gs.ecs.register::<Stunned>();
+    gs.ecs.register::<Summoned>();
+    gs.ecs.register::<SummonsCreature>();
+    gs.ecs.register::<SwapPlaces>();
     gs.ecs.register::<Target>();
     gs.ecs.register::<TeachesSpell>();
     gs.ecs.register::<TeleportTo>();
+    gs.ecs.register::<Throwable>();
     gs.ecs.register::<TileSize>();
     gs.ecs.register::<TownPortal>();
+    gs.ecs.register::<Trap>();
+    gs.ecs.register::<TrueForm>();
+    gs.ecs.register::<UsesItems>();
     gs.ecs.register::<Vendor>();
     gs.ecs.register::<Viewshed>();
     gs.ecs.register::<WantsToApproach>();
     gs.ecs.register::<WantsToCastSpell>();
     gs.ecs.register::<WantsToDropItem>();
     gs.ecs.register::<WantsToFlee>();
+    gs.ecs.register::<WantsToManeuver>();
     gs.ecs.register::<WantsToMelee>();
     gs.ecs.register::<WantsToPickupItem>();
     gs.ecs.register::<WantsToRemoveItem>();
     gs.ecs.register::<WantsToShoot>();
+    gs.ecs.register::<WantsToSkirmish>();
+    gs.ecs.register::<WantsToThrow>();
     gs.ecs.register::<WantsToUseItem>();
     gs.ecs.register::<Weapon>();
     gs.ecs.register::<Wearable>();
@@ -1,4 +1,8 @@
 use super::{map_builders::level_builder, Map, OtherLevelPosition, Position, TileType, Viewshed};
+use crate::{
+    raws::{RawMaster, Reaction},
+    tile_walkable, Ally, AllyCommand, DamageType,
+};
 use rltk::Point;
 use serde::{Deserialize, Serialize};
 use specs::prelude::*;
@@ -8,10 +12,25 @@ use std::collections::{HashMap, HashSet};
 pub struct MasterDungeonMap {
     maps: HashMap<i32, Map>,
     pub identified_items: HashSet<String>,
+    pub known_resistances: HashSet<(String, DamageType)>,
+    pub spawned_uniques: HashSet<String>,
     pub scroll_mappings: HashMap<String, String>,
     pub potion_mappings: HashMap<String, String>,
+    pub reputation: HashMap<String, i32>,
+    pub turn: i32,
+    left_level: HashMap<i32, i32>,
 }
 
+// The clock starts at eight in the morning and runs twenty turns to the hour
+const TURNS_PER_HOUR: i32 = 20;
+const STARTING_HOUR: i32 = 8;
+
+// Standing with a faction runs from -100 (sworn enemy) to 100 (honored friend)
+const MAX_REPUTATION: i32 = 100;
+const HOSTILE_REPUTATION: i32 = -50;
+const WARY_REPUTATION: i32 = -10;
+const FRIENDLY_REPUTATION: i32 = 50;
+
 impl MasterDungeonMap {
     pub fn new() -> MasterDungeonMap {
         let mut dm = MasterDungeonMap {
@@ -31,6 +50,8 @@ impl MasterDungeonMap {
                 .insert(potion_tag.to_string(), masked_name);
         }
 
+        dm.reputation = crate::raws::get_starting_reputation();
+
         dm
     }
 
@@ -45,6 +66,94 @@ impl MasterDungeonMap {
             None
         }
     }
+
+    pub fn hour(&self) -> i32 {
+        (self.turn / TURNS_PER_HOUR + STARTING_HOUR) % 24
+    }
+
+    pub fn day(&self) -> i32 {
+        (self.turn / TURNS_PER_HOUR + STARTING_HOUR) / 24 + 1
+    }
+
+    pub fn is_night(&self) -> bool {
+        let hour = self.hour();
+        !(6..21).contains(&hour)
+    }
+
+    /// Remembers when the player left a level, so it can catch up on their return
+    pub fn leave_level(&mut self, depth: i32) {
+        self.left_level.insert(depth, self.turn);
+    }
+
+    /// How many turns have passed since the player was last on a level
+    pub fn turns_away(&self, depth: i32) -> i32 {
+        self.left_level
+            .get(&depth)
+            .map_or(0, |left| self.turn - left)
+    }
+
+    /// The player's standing with a faction, if that faction keeps track of it
+    pub fn reputation(&self, faction: &str) -> Option<i32> {
+        self.reputation.get(faction).copied()
+    }
+
+    pub fn adjust_reputation(&mut self, faction: &str, delta: i32) {
+        if let Some(standing) = self.reputation.get_mut(faction) {
+            let old_reaction = reputation_reaction(*standing);
+            *standing = (*standing + delta).clamp(-MAX_REPUTATION, MAX_REPUTATION);
+            let new_reaction = reputation_reaction(*standing);
+
+            if old_reaction != new_reaction {
+                let (color, verdict) = match new_reaction {
+                    Some(Reaction::Attack) => (rltk::RED, "now consider you an enemy"),
+                    Some(Reaction::Flee) => (rltk::ORANGE, "are wary of you"),
+                    Some(Reaction::Ignore) => (rltk::GREEN, "count you as a friend"),
+                    None => (rltk::YELLOW, "no longer feel strongly about you"),
+                };
+                crate::gamelog::Logger::new()
+                    .npc_name(faction)
+                    .color(color)
+                    .append(verdict)
+                    .log();
+            }
+        }
+    }
+
+    /// Faction reactions from the raws, adjusted by how the faction feels about the player
+    pub fn faction_reaction(
+        &self,
+        my_faction: &str,
+        their_faction: &str,
+        raws: &RawMaster,
+    ) -> Reaction {
+        let base = crate::raws::faction_reaction(my_faction, their_faction, raws);
+        if their_faction != "Player" {
+            return base;
+        }
+        match self.reputation(my_faction).and_then(reputation_reaction) {
+            Some(Reaction::Flee) if base == Reaction::Attack => base,
+            Some(reaction) => reaction,
+            None => base,
+        }
+    }
+
+    /// Multiplier applied to a vendor's prices based on the player's standing with them
+    pub fn price_modifier(&self, faction: &str) -> f32 {
+        let standing = self.reputation(faction).unwrap_or(0);
+        1.0 - standing as f32 / (MAX_REPUTATION as f32 * 5.0)
+    }
+}
+
+fn reputation_reaction(standing: i32) -> Option<Reaction> {
+    if standing <= HOSTILE_REPUTATION {
+        Some(Reaction::Attack)
+    } else if standing <= WARY_REPUTATION {
+        Some(Reaction::Flee)
+    } else if standing >= FRIENDLY_REPUTATION {
+        Some(Reaction::Ignore)
+    } else {
+        None
+    }
 }
 
 pub fn level_transition(ecs: &mut World, new_depth: i32, offset: i32) -> Option<Vec<Map>> {
@@ -122,6 +231,7 @@ fn transition_to_new_map(ecs: &mut World, new_depth: i32) -> Vec<Map> {
 
     // Spawn bad guys
     builder.spawn_entities(ecs);
+    super::assign_homes(ecs, &builder.build_data.homes);
 
     // Place the player and update resources
     let (player_x, player_y) = (player_start.x, player_start.y);
@@ -154,11 +264,17 @@ pub fn freeze_level_entities(ecs: &mut World) {
     let mut other_level_positions = ecs.write_storage::<OtherLevelPosition>();
     let player_entity = ecs.fetch::<Entity>();
     let map_depth = ecs.fetch::<Map>().depth;
+    let allies = ecs.read_storage::<Ally>();
+    ecs.fetch_mut::<MasterDungeonMap>().leave_level(map_depth);
 
     // Find positions and make OtherLevelPosition
     let mut pos_to_delete: Vec<Entity> = Vec::new();
     for (entity, pos) in (&entities, &positions).join() {
-        if entity != *player_entity {
+        // Allies that aren't told to wait come along with the player
+        let following = allies
+            .get(entity)
+            .is_some_and(|ally| ally.command != AllyCommand::Wait);
+        if entity != *player_entity && !following {
             other_level_positions
                 .insert(
                     entity,
@@ -204,6 +320,62 @@ pub fn thaw_level_entities(ecs: &mut World) {
     }
 }
 
+/// Places following allies on open tiles around the player after a level change
+pub fn bring_allies_along(ecs: &mut World) {
+    let entities = ecs.entities();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut viewsheds = ecs.write_storage::<Viewshed>();
+    let allies = ecs.read_storage::<Ally>();
+    let player_entity = ecs.fetch::<Entity>();
+    let map = ecs.fetch::<Map>();
+
+    let player_pos = if let Some(pos) = positions.get(*player_entity) {
+        Point::new(pos.x, pos.y)
+    } else {
+        return;
+    };
+
+    let followers: Vec<Entity> = (&entities, &allies, &positions)
+        .join()
+        .filter(|(_, ally, _)| ally.command != AllyCommand::Wait)
+        .map(|(entity, _, _)| entity)
+        .collect();
+
+    let mut occupied: HashSet<Point> = (&entities, &positions)
+        .join()
+        .filter(|(entity, _)| !followers.contains(entity))
+        .map(|(_, pos)| Point::new(pos.x, pos.y))
+        .collect();
+
+    for follower in followers.iter() {
+        // Fall back to sharing the player's tile if the area is packed
+        let mut spot = player_pos;
+        'search: for radius in 1..=8 {
+            for y in player_pos.y - radius..=player_pos.y + radius {
+                for x in player_pos.x - radius..=player_pos.x + radius {
+                    if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+                        continue;
+                    }
+                    let pt = Point::new(x, y);
+                    if tile_walkable(map.tiles[map.xy_idx(x, y)]) && !occupied.contains(&pt) {
+                        spot = pt;
+                        break 'search;
+                    }
+                }
+            }
+        }
+
+        occupied.insert(spot);
+        if let Some(pos) = positions.get_mut(*follower) {
+            pos.x = spot.x;
+            pos.y = spot.y;
+        }
+        if let Some(vs) = viewsheds.get_mut(*follower) {
+            vs.dirty = true;
+        }
+    }
+}
+
 fn make_scroll_name() -> String {
     let length = 4 + crate::tutorial::rng::roll_dice(1, 4);
     let mut name = "Scroll of ".to_string();
@@ -0,0 +1,121 @@
+use super::{tile_opaque, tile_walkable, Map, TileType};
+use rltk::{FontCharType, RGB};
+use serde::{Deserialize, Serialize};
+
+// How long fire keeps going once it has caught on something that burns
+const GRASS_BURN_TURNS: i32 = 4;
+const WOOD_BURN_TURNS: i32 = 6;
+const OIL_BURN_TURNS: i32 = 8;
+const WEB_BURN_TURNS: i32 = 1;
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum HazardKind {
+    Fire,
+    Gas,
+    Web,
+    Oil,
+}
+
+impl HazardKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HazardKind::Fire => "Fire",
+            HazardKind::Gas => "Poison Gas",
+            HazardKind::Web => "Web",
+            HazardKind::Oil => "Oil",
+        }
+    }
+
+    pub fn glyph(&self) -> FontCharType {
+        match self {
+            HazardKind::Fire => rltk::to_cp437('^'),
+            HazardKind::Gas => rltk::to_cp437('░'),
+            HazardKind::Web => rltk::to_cp437('#'),
+            HazardKind::Oil => rltk::to_cp437('~'),
+        }
+    }
+
+    pub fn colors(&self) -> (RGB, RGB) {
+        match self {
+            HazardKind::Fire => (RGB::named(rltk::YELLOW), RGB::named(rltk::ORANGE_RED)),
+            HazardKind::Gas => (RGB::named(rltk::CHARTREUSE), RGB::from_f32(0., 0.25, 0.)),
+            HazardKind::Web => (RGB::named(rltk::WHITE), RGB::from_f32(0.2, 0.2, 0.2)),
+            HazardKind::Oil => (RGB::named(rltk::DARK_GRAY), RGB::from_f32(0.15, 0.1, 0.05)),
+        }
+    }
+}
+
+/// Something lingering on a tile, counting down once per turn
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    pub turns: i32,
+}
+
+/// How long a tile keeps burning once lit, or None if fire can't take hold there
+pub fn fuel_turns(map: &Map, idx: usize) -> Option<i32> {
+    match map.hazards.get(&idx).map(|h| h.kind) {
+        Some(HazardKind::Oil) => return Some(OIL_BURN_TURNS),
+        Some(HazardKind::Web) => return Some(WEB_BURN_TURNS),
+        _ => {}
+    }
+    match map.tiles[idx] {
+        TileType::Grass => Some(GRASS_BURN_TURNS),
+        // Bridges are left out: burning one away would leave floor hanging over the water
+        TileType::WoodFloor => Some(WOOD_BURN_TURNS),
+        _ => None,
+    }
+}
+
+impl Map {
+    /// Lays a hazard on a tile if the terrain can hold it. Returns true if it took.
+    pub fn add_hazard(&mut self, idx: usize, kind: HazardKind, turns: i32) -> bool {
+        let tile = self.tiles[idx];
+        let allowed = match kind {
+            HazardKind::Gas => !tile_opaque(tile),
+            _ => tile_walkable(tile) && tile != TileType::ShallowWater,
+        };
+        if !allowed || turns < 1 {
+            return false;
+        }
+
+        // Fire feeds on whatever was already there, so the fuel sets how long it lasts
+        let turns = if kind == HazardKind::Fire {
+            i32::max(turns, fuel_turns(self, idx).unwrap_or(0))
+        } else {
+            turns
+        };
+        match self.hazards.get_mut(&idx) {
+            Some(existing) if existing.kind == kind => {
+                existing.turns = i32::max(existing.turns, turns)
+            }
+            // Nothing else survives a fire, and nothing settles on one
+            Some(existing) if existing.kind == HazardKind::Fire => return false,
+            _ => {
+                self.hazards.insert(idx, Hazard { kind, turns });
+            }
+        }
+        true
+    }
+
+    /// Called when fire on a tile burns out: whatever fed it is gone
+    pub fn burn_out(&mut self, idx: usize) {
+        self.hazards.remove(&idx);
+        if matches!(self.tiles[idx], TileType::Grass | TileType::WoodFloor) {
+            self.tiles[idx] = TileType::Floor;
+        }
+    }
+
+    /// Ages hazards on a level nobody was watching; nothing spreads while the player is away
+    pub fn age_hazards(&mut self, elapsed: i32) {
+        let mut burnt = Vec::new();
+        self.hazards.retain(|idx, hazard| {
+            hazard.turns -= elapsed;
+            if hazard.turns < 1 && hazard.kind == HazardKind::Fire {
+                burnt.push(*idx);
+            }
+            hazard.turns > 0
+        });
+        burnt.into_iter().for_each(|idx| self.burn_out(idx));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::*;
 use rltk::{Algorithm2D, BaseMap, FontCharType, Point, RGB};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub mod camera;
 pub use camera::*;
@@ -15,6 +15,12 @@ pub use tiletype::*;
 pub mod dungeon;
 pub use dungeon::*;
 
+mod simulation;
+pub use simulation::*;
+
+mod hazards;
+pub use hazards::*;
+
 pub struct RenderTile(pub FontCharType, pub RGB, pub RGB);
 
 // ------------------------------------------------------------
@@ -29,6 +35,7 @@ pub struct Map {
     pub visible_tiles: Vec<bool>,
     pub depth: i32,
     pub bloodstains: HashSet<usize>,
+    pub hazards: HashMap<usize, Hazard>,
     pub view_blocked: HashSet<usize>,
     pub name: String,
     pub outdoors: bool,
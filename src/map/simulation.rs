@@ -0,0 +1,229 @@
+use super::{Map, MasterDungeonMap};
+use crate::{
+    tile_walkable, Ally, BlocksTile, MoveMode, Movement, Pools, Position, Schedule, Viewshed,
+};
+use rltk::{DistanceAlg, Point};
+use specs::prelude::*;
+use std::collections::HashSet;
+
+// Frozen monsters recover one hit point (and one mana) every this many turns
+const TURNS_PER_HEAL: i32 = 10;
+// Wanderers cover about one tile every this many turns, up to MAX_WANDER
+const TURNS_PER_WANDER_STEP: i32 = 5;
+const MAX_WANDER: i32 = 20;
+// Someone new moves in every this many turns, up to MAX_ARRIVALS per visit
+const TURNS_PER_ARRIVAL: i32 = 300;
+const MAX_ARRIVALS: i32 = 3;
+// Nothing is moved or spawned this close to the player
+const SAFE_DISTANCE: f32 = 8.0;
+
+/// Coarsely catches a level up on everything that happened while the player was away.
+/// Call this after the level's entities have been thawed.
+pub fn simulate_frozen_level(ecs: &mut World) {
+    let depth = ecs.fetch::<Map>().depth;
+    let elapsed = ecs.fetch::<MasterDungeonMap>().turns_away(depth);
+    if elapsed < 1 {
+        return;
+    }
+
+    ecs.fetch_mut::<Map>().age_hazards(elapsed);
+    heal_residents(ecs, elapsed);
+    relocate_wanderers(ecs, elapsed);
+    follow_schedules(ecs);
+    if depth > 1 {
+        spawn_arrivals(ecs, depth, elapsed);
+    }
+}
+
+/// Hands out homes to scheduled townsfolk, in order of spawning
+pub fn assign_homes(ecs: &mut World, homes: &[usize]) {
+    if homes.is_empty() {
+        return;
+    }
+    let map = ecs.fetch::<Map>();
+    let positions = ecs.read_storage::<Position>();
+    let mut schedules = ecs.write_storage::<Schedule>();
+    for (i, (schedule, _pos)) in (&mut schedules, &positions).join().enumerate() {
+        let home = homes[i % homes.len()];
+        schedule.home = (home as i32 % map.width, home as i32 / map.width);
+    }
+}
+
+fn heal_residents(ecs: &mut World, elapsed: i32) {
+    let player_entity = *ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let positions = ecs.read_storage::<Position>();
+    let mut pools = ecs.write_storage::<Pools>();
+
+    let recovery = elapsed / TURNS_PER_HEAL;
+    for (entity, _pos, stats) in (&entities, &positions, &mut pools).join() {
+        if entity == player_entity {
+            continue;
+        }
+        stats.hit_points.current =
+            i32::min(stats.hit_points.max, stats.hit_points.current + recovery);
+        stats.mana.current = i32::min(stats.mana.max, stats.mana.current + recovery);
+    }
+}
+
+fn relocate_wanderers(ecs: &mut World, elapsed: i32) {
+    let entities = ecs.entities();
+    let map = ecs.fetch::<Map>();
+    let player_pos = *ecs.fetch::<Point>();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut move_modes = ecs.write_storage::<MoveMode>();
+    let mut viewsheds = ecs.write_storage::<Viewshed>();
+    let blockers = ecs.read_storage::<BlocksTile>();
+    let allies = ecs.read_storage::<Ally>();
+
+    let wander = i32::min(MAX_WANDER, elapsed / TURNS_PER_WANDER_STEP);
+    if wander < 1 {
+        return;
+    }
+
+    let mut occupied: HashSet<usize> = (&positions, &blockers)
+        .join()
+        .map(|(pos, _)| map.xy_idx(pos.x, pos.y))
+        .collect();
+
+    for (entity, pos, mode) in (&entities, &mut positions, &mut move_modes).join() {
+        if allies.get(entity).is_some() {
+            continue;
+        }
+        match mode.mode {
+            Movement::Static => continue,
+            Movement::Random => {}
+            Movement::RandomWaypoint { .. } => {
+                mode.mode = Movement::RandomWaypoint { path: None };
+            }
+        }
+
+        // A handful of tries to find somewhere open within wandering distance
+        for _ in 0..10 {
+            let x = pos.x + crate::tutorial::rng::roll_dice(1, wander * 2 + 1) - wander - 1;
+            let y = pos.y + crate::tutorial::rng::roll_dice(1, wander * 2 + 1) - wander - 1;
+            if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+                continue;
+            }
+            let idx = map.xy_idx(x, y);
+            if !tile_walkable(map.tiles[idx])
+                || occupied.contains(&idx)
+                || DistanceAlg::Pythagoras.distance2d(player_pos, Point::new(x, y)) < SAFE_DISTANCE
+            {
+                continue;
+            }
+            if blockers.get(entity).is_some() {
+                occupied.remove(&map.xy_idx(pos.x, pos.y));
+                occupied.insert(idx);
+            }
+            pos.x = x;
+            pos.y = y;
+            if let Some(vs) = viewsheds.get_mut(entity) {
+                vs.dirty = true;
+            }
+            break;
+        }
+    }
+}
+
+/// Puts scheduled townsfolk wherever the time of day says they should be, or as close to it
+/// as they can get without standing on someone else
+fn follow_schedules(ecs: &mut World) {
+    let entities = ecs.entities();
+    let night = ecs.fetch::<MasterDungeonMap>().is_night();
+    let map = ecs.fetch::<Map>();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut viewsheds = ecs.write_storage::<Viewshed>();
+    let schedules = ecs.read_storage::<Schedule>();
+    let blockers = ecs.read_storage::<BlocksTile>();
+
+    let mut occupied: HashSet<usize> = (&positions, &blockers)
+        .join()
+        .map(|(pos, _)| map.xy_idx(pos.x, pos.y))
+        .collect();
+
+    for (entity, pos, schedule) in (&entities, &mut positions, &schedules).join() {
+        let (x, y) = if night { schedule.home } else { schedule.work };
+        let current = map.xy_idx(pos.x, pos.y);
+        if current == map.xy_idx(x, y) {
+            continue;
+        }
+        let blocks = blockers.get(entity).is_some();
+        if blocks {
+            occupied.remove(&current);
+        }
+        let Some(idx) = nearest_open_tile(&map, x, y, &occupied) else {
+            if blocks {
+                occupied.insert(current);
+            }
+            continue;
+        };
+        if blocks {
+            occupied.insert(idx);
+        }
+        pos.x = idx as i32 % map.width;
+        pos.y = idx as i32 / map.width;
+        if let Some(vs) = viewsheds.get_mut(entity) {
+            vs.dirty = true;
+        }
+    }
+}
+
+/// The closest walkable, unoccupied tile to (x, y), looking no further than a few steps out
+fn nearest_open_tile(map: &Map, x: i32, y: i32, occupied: &HashSet<usize>) -> Option<usize> {
+    const MAX_RADIUS: i32 = 3;
+    let target = Point::new(x, y);
+    let mut best: Option<(usize, f32)> = None;
+    for ty in y - MAX_RADIUS..=y + MAX_RADIUS {
+        for tx in x - MAX_RADIUS..=x + MAX_RADIUS {
+            if tx < 1 || tx > map.width - 2 || ty < 1 || ty > map.height - 2 {
+                continue;
+            }
+            let idx = map.xy_idx(tx, ty);
+            if !tile_walkable(map.tiles[idx]) || occupied.contains(&idx) {
+                continue;
+            }
+            let distance = DistanceAlg::Pythagoras.distance2d(target, Point::new(tx, ty));
+            if best.is_none_or(|(_, closest)| distance < closest) {
+                best = Some((idx, distance));
+            }
+        }
+    }
+    best.map(|(idx, _)| idx)
+}
+
+fn spawn_arrivals(ecs: &mut World, depth: i32, elapsed: i32) {
+    let arrivals = i32::min(MAX_ARRIVALS, elapsed / TURNS_PER_ARRIVAL);
+    if arrivals < 1 {
+        return;
+    }
+
+    let mut spawns = Vec::new();
+    {
+        let map = ecs.fetch::<Map>();
+        let player_pos = *ecs.fetch::<Point>();
+        let positions = ecs.read_storage::<Position>();
+        let occupied: HashSet<usize> = positions
+            .join()
+            .map(|pos| map.xy_idx(pos.x, pos.y))
+            .collect();
+        let spawn_table =
+            crate::raws::get_spawn_table_for_depth(&crate::raws::RAWS.lock().unwrap(), depth);
+
+        for _ in 0..arrivals {
+            let x = crate::tutorial::rng::roll_dice(1, map.width - 2);
+            let y = crate::tutorial::rng::roll_dice(1, map.height - 2);
+            let idx = map.xy_idx(x, y);
+            if tile_walkable(map.tiles[idx])
+                && !occupied.contains(&idx)
+                && DistanceAlg::Pythagoras.distance2d(player_pos, Point::new(x, y)) >= SAFE_DISTANCE
+            {
+                spawns.push((idx, spawn_table.roll_mob()));
+            }
+        }
+    }
+
+    for (idx, name) in spawns.iter() {
+        crate::spawner::spawn_entity(ecs, &(idx, name));
+    }
+}
@@ -2,7 +2,7 @@ use super::{Map, RenderTile, TileType};
 use rltk::{FontCharType, RGB};
 
 pub fn tile_glyph(idx: usize, map: &Map) -> RenderTile {
-    let RenderTile(glyph, mut fg, mut bg) = match map.depth {
+    let RenderTile(mut glyph, mut fg, mut bg) = match map.depth {
         8 | 9 => get_mushroom_glyph(idx, map),
         7 => {
             let x = idx as i32 % map.width;
@@ -28,6 +28,11 @@ pub fn tile_glyph(idx: usize, map: &Map) -> RenderTile {
     if map.bloodstains.contains(&idx) {
         bg = RGB::from_f32(0.75, 0., 0.);
     }
+    // Hazards come and go, so only what's in sight is drawn
+    if let Some(hazard) = map.hazards.get(&idx).filter(|_| map.visible_tiles[idx]) {
+        glyph = hazard.kind.glyph();
+        (fg, bg) = hazard.kind.colors();
+    }
     if !map.visible_tiles[idx] {
         fg = fg.to_greyscale();
         bg = RGB::from_f32(0., 0., 0.);
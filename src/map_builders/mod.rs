@@ -111,6 +111,7 @@ use door_placement::*;
 // --------------------------------------------------------------------------------
 pub struct BuilderMap {
     pub spawn_list: Vec<(usize, String)>,
+    pub homes: Vec<usize>,
     pub map: Map,
     pub starting_position: Option<Position>,
     pub rooms: Option<Vec<Rect>>,
@@ -146,6 +147,7 @@ impl BuilderChain {
             builders: Vec::new(),
             build_data: BuilderMap {
                 spawn_list: Vec::new(),
+                homes: Vec::new(),
                 map: Map::new(new_depth, width, height, name),
                 starting_position: None,
                 rooms: None,
@@ -164,6 +164,16 @@ impl PrefabBuilder {
                 build_data.map.tiles[idx] = TileType::Floor;
                 build_data.spawn_list.push((idx, "Rations".to_string()));
             }
+            'C' => {
+                build_data.map.tiles[idx] = TileType::Floor;
+                build_data.spawn_list.push((idx, "Chest".to_string()));
+            }
+            'L' => {
+                build_data.map.tiles[idx] = TileType::Floor;
+                build_data
+                    .spawn_list
+                    .push((idx, "Locked Chest".to_string()));
+            }
             '!' => {
                 build_data.map.tiles[idx] = TileType::Floor;
                 build_data
@@ -246,7 +256,12 @@ impl PrefabBuilder {
             return;
         }
 
-        let master_vault_list = vec![TOTALLY_NOT_A_TRAP, SILLY_SMILE, CHECKERBOARD];
+        let master_vault_list = vec![
+            TOTALLY_NOT_A_TRAP,
+            SILLY_SMILE,
+            CHECKERBOARD,
+            TREASURE_CACHE,
+        ];
 
         let possible_vaults: Vec<&PrefabRoom> = master_vault_list
             .iter()
@@ -55,3 +55,19 @@ const CHECKERBOARD_MAP: &str = "
  ^# #
 
 ";
+
+pub const TREASURE_CACHE: PrefabRoom = PrefabRoom {
+    template: TREASURE_CACHE_MAP,
+    width: 5,
+    height: 5,
+    first_depth: 2,
+    last_depth: 100,
+};
+
+const TREASURE_CACHE_MAP: &str = "
+     
+ #C# 
+ ^ ^ 
+  L  
+     
+";
@@ -350,6 +350,7 @@ impl TownBuilder {
         let mut to_place = vec![
             "Barkeep",
             "Shady Salesman",
+            "Mercenary Captain",
             "Patron",
             "Patron",
             "Keg",
@@ -402,6 +403,10 @@ impl TownBuilder {
     }
 
     fn build_hovel(&mut self, building: &Building, build_data: &mut BuilderMap) {
+        // Somewhere for the shopkeepers to sleep
+        let (hx, hy) = (building.0 + (building.2 / 2), building.1 + (building.3 / 2));
+        build_data.homes.push(build_data.map.xy_idx(hx, hy));
+
         let mut to_place = vec!["Peasant", "Bed", "Chair", "Table"];
         self.random_building_spawn(building, build_data, &mut to_place, 0);
     }
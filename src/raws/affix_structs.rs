@@ -0,0 +1,38 @@
+use super::ItemAttributeBonus;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A magical property rolled onto a weapon or piece of armour when it spawns
+#[derive(Deserialize, Debug, Clone)]
+pub struct Affix {
+    pub name: String,
+    pub position: String,
+    pub applies_to: Vec<String>,
+    pub rarity: String,
+    pub weight: i32,
+    pub min_depth: i32,
+    pub max_depth: i32,
+    pub cursed: Option<bool>,
+    pub value: Option<f32>,
+    pub hit_bonus: Option<i32>,
+    pub damage_bonus: Option<i32>,
+    pub armor_class: Option<f32>,
+    pub initiative_bonus: Option<f32>,
+    pub proc_chance: Option<f32>,
+    pub proc_effects: Option<HashMap<String, String>>,
+    pub proc_script: Option<super::Script>,
+    pub crit_effects: Option<HashMap<String, String>>,
+    pub resistances: Option<HashMap<String, i32>>,
+    pub attributes: Option<ItemAttributeBonus>,
+}
+
+/// A hand-named item built from a base item and a fixed set of affixes
+#[derive(Deserialize, Debug, Clone)]
+pub struct UniqueItem {
+    pub name: String,
+    pub base: String,
+    pub affixes: Vec<String>,
+    pub weight: i32,
+    pub min_depth: i32,
+    pub max_depth: i32,
+}
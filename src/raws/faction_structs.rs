@@ -5,6 +5,7 @@ use std::collections::HashMap;
 pub struct FactionInfo {
     pub name: String,
     pub responses: HashMap<String, String>,
+    pub reputation: Option<i32>,
 }
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone)]
@@ -7,6 +7,8 @@ pub struct Item {
     pub renderable: Option<Renderable>,
     pub consumable: Option<Consumable>,
     pub weapon: Option<Weapon>,
+    pub ammo: Option<Ammo>,
+    pub throwable: Option<Throwable>,
     pub wearable: Option<Wearable>,
     pub initiative_penalty: Option<f32>,
     pub weight_lbs: Option<f32>,
@@ -14,15 +16,14 @@ pub struct Item {
     pub vendor_category: Option<String>,
     pub magic: Option<MagicItem>,
     pub attributes: Option<ItemAttributeBonus>,
-    pub template_magic: Option<ItemMagicTemplate>,
+    pub resistances: Option<HashMap<String, i32>>,
+    pub bag: Option<Bag>,
+    pub durability: Option<i32>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct ItemMagicTemplate {
-    pub unidentified_name: String,
-    pub bonus_min: i32,
-    pub bonus_max: i32,
-    pub include_cursed: bool,
+#[derive(Deserialize, Clone, Debug)]
+pub struct Bag {
+    pub capacity_lbs: i32,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -54,6 +55,7 @@ pub struct MagicItem {
 pub struct Consumable {
     pub effects: HashMap<String, String>,
     pub charges: Option<i32>,
+    pub script: Option<super::Script>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -62,9 +64,31 @@ pub struct Weapon {
     pub attribute: String,
     pub base_damage: String,
     pub hit_bonus: i32,
+    pub damage_type: Option<String>,
     pub proc_chance: Option<f32>,
     pub proc_target: Option<String>,
     pub proc_effects: Option<HashMap<String, String>>,
+    pub proc_script: Option<super::Script>,
+    pub crit_effects: Option<HashMap<String, String>>,
+    pub ammo: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Ammo {
+    pub ammo_type: String,
+    pub count: i32,
+    pub hit_bonus: Option<i32>,
+    pub damage_bonus: Option<i32>,
+    pub damage_type: Option<String>,
+    pub recovery_chance: Option<i32>,
+    pub proc_chance: Option<f32>,
+    pub proc_effects: Option<HashMap<String, String>>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Throwable {
+    pub range: i32,
+    pub hit_bonus: i32,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -17,12 +17,20 @@ pub struct Mob {
     pub hp: Option<i32>,
     pub mana: Option<i32>,
     pub equipped: Option<Vec<String>>,
+    pub carrying: Option<Vec<String>>,
+    pub uses_items: Option<bool>,
+    pub schedule: Option<bool>,
+    pub resistances: Option<HashMap<String, i32>>,
+    pub immunities: Option<Vec<String>>,
     pub natural: Option<MobNatural>,
     pub loot_table: Option<String>,
     pub light: Option<MobLight>,
     pub faction: Option<String>,
     pub gold: Option<String>,
     pub vendor: Option<Vec<String>>,
+    pub repairs: Option<bool>,
+    pub mercenaries: Option<Vec<String>>,
+    pub hire_cost: Option<f32>,
     pub abilities: Option<Vec<MobAbility>>,
     pub on_death: Option<Vec<MobAbility>>,
 }
@@ -60,4 +68,5 @@ pub struct NaturalAttack {
     pub name: String,
     pub hit_bonus: i32,
     pub damage: String,
+    pub damage_type: Option<String>,
 }
@@ -22,8 +22,11 @@ use spell_structs::*;
 mod loot_structs;
 use loot_structs::*;
 
-mod weapon_traits;
-use weapon_traits::*;
+mod affix_structs;
+use affix_structs::*;
+
+mod script_structs;
+use script_structs::*;
 
 mod rawmaster;
 pub use rawmaster::*;
@@ -39,7 +42,8 @@ pub struct Raws {
     pub loot_tables: Vec<LootTable>,
     pub faction_table: Vec<FactionInfo>,
     pub spells: Vec<Spell>,
-    pub weapon_traits: Vec<WeaponTrait>,
+    pub affixes: Vec<Affix>,
+    pub uniques: Vec<UniqueItem>,
 }
 
 use lazy_static::lazy_static;
@@ -7,14 +7,27 @@ pub struct Prop {
     pub name: String,
     pub renderable: Option<Renderable>,
     pub hidden: Option<bool>,
+    pub trap_difficulty: Option<i32>,
     pub blocks_tile: Option<bool>,
     pub blocks_visibility: Option<bool>,
     pub door_open: Option<bool>,
     pub entry_trigger: Option<EntryTrigger>,
     pub light: Option<super::mob_structs::MobLight>,
+    pub container: Option<Container>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Container {
+    pub loot_table: Option<String>,
+    pub loot_rolls: Option<i32>,
+    pub locked: Option<bool>,
+    pub trap_chance: Option<i32>,
+    pub trap_difficulty: Option<i32>,
+    pub trap: Option<EntryTrigger>,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct EntryTrigger {
     pub effects: HashMap<String, String>,
+    pub script: Option<super::Script>,
 }
@@ -1,11 +1,30 @@
 use crate::components::*;
-use crate::{mana_at_level, npc_hp, MasterTable, RandomTable};
+use crate::effects::{
+    AreaShape, EffectNode, TargetCondition, DEFAULT_ATTRIBUTE_TURNS,
+    DEFAULT_DAMAGE_OVER_TIME_TURNS, DEFAULT_POLYMORPH_TURNS, DEFAULT_SLOW_TURNS,
+};
+use crate::{mana_at_level, npc_hp, HazardKind, MasterTable, RandomTable, DEFAULT_TRAP_DIFFICULTY};
 use specs::prelude::*;
 use specs::saveload::{MarkedBuilder, SimpleMarker};
 use std::collections::{HashMap, HashSet};
 
 use super::{parse_dice_string, Raws, Reaction};
 
+// Melee weapons without a throwable section can still be thrown, just not very well
+const DEFAULT_THROW_RANGE: i32 = 3;
+const DEFAULT_THROW_HIT_PENALTY: i32 = -2;
+
+// Percent chance a fired arrow or bolt survives to be picked up again
+const DEFAULT_AMMO_RECOVERY: i32 = 50;
+
+// Percent chance that a weapon or armour piece found in the dungeon carries affixes
+const MAGIC_ITEM_BASE_CHANCE: i32 = 10;
+const MAGIC_ITEM_CHANCE_PER_DEPTH: i32 = 3;
+const MAGIC_ITEM_MAX_CHANCE: i32 = 50;
+
+// Affixes with proc effects but no listed chance fire this often
+const DEFAULT_AFFIX_PROC_CHANCE: f32 = 0.25;
+
 pub enum SpawnTableType {
     Item,
     Mob,
@@ -16,6 +35,7 @@ pub enum SpawnType {
     AtPosition { x: i32, y: i32 },
     Equipped { by: Entity },
     Carried { by: Entity },
+    Contained { by: Entity },
 }
 
 #[derive(Default)]
@@ -27,11 +47,8 @@ pub struct RawMaster {
     loot_index: HashMap<String, usize>,
     faction_index: HashMap<String, HashMap<String, Reaction>>,
     spell_index: HashMap<String, usize>,
-}
-
-struct NewMagicItem {
-    name: String,
-    bonus: i32,
+    affix_index: HashMap<String, usize>,
+    unique_bases: HashMap<String, String>,
 }
 
 impl RawMaster {
@@ -41,139 +58,79 @@ impl RawMaster {
         }
     }
 
-    fn append_magic_template(items_to_build: &mut Vec<NewMagicItem>, item: &super::Item) {
-        if let Some(template) = &item.template_magic {
-            if item.weapon.is_some() || item.wearable.is_some() {
-                if template.include_cursed {
-                    items_to_build.push(NewMagicItem {
-                        name: item.name.clone(),
-                        bonus: -1,
-                    });
-                }
-                for bonus in template.bonus_min..=template.bonus_max {
-                    items_to_build.push(NewMagicItem {
-                        name: item.name.clone(),
-                        bonus,
-                    });
-                }
+    /// Builds a copy of a base item with the given affixes folded into its stats and name
+    fn build_affixed_item(&self, base: &str, affixes: &[usize]) -> super::Item {
+        let mut item = self.raws.items[self.item_index[base]].clone();
+        item.vendor_category = None; // Don't sell magic items!
+
+        let mut prefix = None;
+        let mut suffix = None;
+        let mut class = MagicItemClass::Common;
+        let mut cursed = false;
+        for affix in affixes.iter().map(|idx| &self.raws.affixes[*idx]) {
+            if affix.position == "prefix" {
+                prefix = Some(affix.name.as_str());
             } else {
-                rltk::console::log(format!(
-                    "{} is marked as templated, but isn't a weapon or armor.",
-                    item.name
-                ));
+                suffix = Some(affix.name.as_str());
             }
+            class = class.max(string_to_magic_class(&affix.rarity));
+            cursed |= affix.cursed.unwrap_or(false);
+            apply_affix(&mut item, affix);
         }
-    }
 
-    fn build_base_magic_item(&self, nmw: &NewMagicItem) -> super::Item {
-        let base_item_index = self.item_index[&nmw.name];
-        let mut base_item_copy = self.raws.items[base_item_index].clone();
-        base_item_copy.vendor_category = None; // Don't sell magic items!
-
-        base_item_copy.name = if nmw.bonus == -1 {
-            format!("{} -1", nmw.name)
-        } else {
-            format!("{} +{}", nmw.name, nmw.bonus)
-        };
-
-        base_item_copy.magic = Some(super::MagicItem {
-            class: match nmw.bonus {
-                2 | 3 | 4 => "rare".to_string(),
-                5 => "legendary".to_string(),
-                _ => "common".to_string(),
+        item.name = compose_affixed_name(prefix, base, suffix);
+        item.magic = Some(super::MagicItem {
+            class: match class {
+                MagicItemClass::Common => "common".to_string(),
+                MagicItemClass::Rare => "rare".to_string(),
+                MagicItemClass::Legendary => "legendary".to_string(),
             },
-            naming: base_item_copy
-                .template_magic
-                .as_ref()
-                .unwrap()
-                .unidentified_name
-                .clone(),
-            cursed: if nmw.bonus == -1 { Some(true) } else { None },
+            naming: format!("Unidentified {}", base),
+            cursed: if cursed { Some(true) } else { None },
         });
-
-        if let Some(initiative_penalty) = base_item_copy.initiative_penalty.as_mut() {
-            *initiative_penalty -= nmw.bonus as f32;
-        }
-
-        if let Some(base_value) = base_item_copy.base_value.as_mut() {
-            *base_value += (nmw.bonus as f32 + 1.0) * 50.;
-        }
-
-        if let Some(mut weapon) = base_item_copy.weapon.as_mut() {
-            weapon.hit_bonus += nmw.bonus;
-            let (n, die, plus) = parse_dice_string(&weapon.base_damage);
-            let final_bonus = plus + nmw.bonus;
-            match final_bonus.cmp(&0) {
-                std::cmp::Ordering::Less => {
-                    weapon.base_damage = format!("{n}d{die}{final_bonus}");
-                }
-                std::cmp::Ordering::Greater => {
-                    weapon.base_damage = format!("{n}d{die}+{final_bonus}");
-                }
-                _ => {}
-            };
-        }
-
-        if let Some(mut armor) = base_item_copy.wearable.as_mut() {
-            armor.armor_class += nmw.bonus as f32;
-        }
-        base_item_copy
+        item
     }
 
-    fn build_magic_weapon_or_armor(&mut self, items_to_build: &[NewMagicItem]) {
-        for nmw in items_to_build.iter() {
-            let base_item_copy = self.build_base_magic_item(nmw);
+    fn build_uniques(&mut self, used_names: &mut HashSet<String>) {
+        for unique in self.raws.uniques.clone().iter() {
+            if used_names.contains(&unique.name) || !self.item_index.contains_key(&unique.base) {
+                rltk::console::log(format!(
+                    "WARNING - unique item [{}] is a duplicate or has an unknown base",
+                    unique.name
+                ));
+                continue;
+            }
+            let affixes: Vec<usize> = unique
+                .affixes
+                .iter()
+                .filter_map(|name| self.affix_index.get(name).copied())
+                .collect();
+
+            let mut item = self.build_affixed_item(&unique.base, &affixes);
+            item.name = unique.name.clone();
+            if let Some(magic) = item.magic.as_mut() {
+                magic.class = "legendary".to_string();
+            }
 
-            let real_name = base_item_copy.name.clone();
-            self.raws.items.push(base_item_copy);
+            self.raws.items.push(item);
             self.item_index
-                .insert(real_name.clone(), self.raws.items.len() - 1);
+                .insert(unique.name.clone(), self.raws.items.len() - 1);
+            self.unique_bases
+                .insert(unique.name.clone(), unique.base.clone());
+            used_names.insert(unique.name.clone());
 
             self.raws.spawn_table.push(super::SpawnTableEntry {
-                name: real_name.clone(),
-                weight: 10 - i32::abs(nmw.bonus),
-                min_depth: 1 + i32::abs((nmw.bonus - 1) * 3),
-                max_depth: 100,
+                name: unique.name.clone(),
+                weight: unique.weight,
+                min_depth: unique.min_depth,
+                max_depth: unique.max_depth,
                 add_map_depth_to_weight: None,
             });
         }
     }
 
-    fn build_traited_weapons(&mut self, items_to_build: &[NewMagicItem]) {
-        items_to_build
-            .iter()
-            .filter(|i| i.bonus > 0)
-            .for_each(|nmw| {
-                for wt in self.raws.weapon_traits.iter() {
-                    let mut base_item_copy = self.build_base_magic_item(nmw);
-                    if let Some(mut weapon) = base_item_copy.weapon.as_mut() {
-                        base_item_copy.name = format!("{} {}", wt.name, base_item_copy.name);
-                        if let Some(base_value) = base_item_copy.base_value.as_mut() {
-                            *base_value *= 2.0;
-                        }
-                        weapon.proc_chance = Some(0.25);
-                        weapon.proc_effects = Some(wt.effects.clone());
-
-                        let real_name = base_item_copy.name.clone();
-                        self.raws.items.push(base_item_copy);
-                        self.item_index
-                            .insert(real_name.clone(), self.raws.items.len() - 1);
-
-                        self.raws.spawn_table.push(super::SpawnTableEntry {
-                            name: real_name.clone(),
-                            weight: 9 - i32::abs(nmw.bonus),
-                            min_depth: 2 + i32::abs((nmw.bonus - 1) * 3),
-                            max_depth: 100,
-                            add_map_depth_to_weight: None,
-                        });
-                    }
-                }
-            });
-    }
-
     pub fn load(&mut self, raws: Raws) {
         let mut used_names = HashSet::new();
-        let mut items_to_build = Vec::new();
 
         self.raws = raws;
         self.item_index = HashMap::new();
@@ -187,8 +144,6 @@ impl RawMaster {
             } else {
                 self.item_index.insert(item.name.clone(), i);
                 used_names.insert(item.name.clone());
-
-                RawMaster::append_magic_template(&mut items_to_build, item);
             }
         }
 
@@ -248,8 +203,11 @@ impl RawMaster {
             self.spell_index.insert(spell.name.clone(), i);
         }
 
-        self.build_magic_weapon_or_armor(&items_to_build);
-        self.build_traited_weapons(&items_to_build);
+        for (i, affix) in self.raws.affixes.iter().enumerate() {
+            self.affix_index.insert(affix.name.clone(), i);
+        }
+
+        self.build_uniques(&mut used_names);
     }
 }
 
@@ -267,6 +225,26 @@ pub fn faction_reaction(my_faction: &str, their_faction: &str, raws: &RawMaster)
     Reaction::Ignore
 }
 
+/// Factions that have a specific grudge against the given faction
+pub fn faction_rivals(faction: &str, raws: &RawMaster) -> Vec<String> {
+    raws.faction_index
+        .iter()
+        .filter(|(name, reactions)| {
+            name.as_str() != faction && reactions.get(faction) == Some(&Reaction::Attack)
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+pub fn get_starting_reputation() -> HashMap<String, i32> {
+    let raws = &super::RAWS.lock().unwrap();
+    raws.raws
+        .faction_table
+        .iter()
+        .filter_map(|faction| faction.reputation.map(|rep| (faction.name.clone(), rep)))
+        .collect()
+}
+
 pub fn get_scroll_tags() -> Vec<String> {
     let raws = &super::RAWS.lock().unwrap();
     let mut result = Vec::new();
@@ -310,13 +288,43 @@ pub fn get_item_drop(raws: &RawMaster, table: &str) -> Option<String> {
     None
 }
 
+/// How many of an item a single spawn produces; arrows and bolts come in bundles
+fn spawn_quantity(item: &super::Item) -> i32 {
+    item.ammo.as_ref().map_or(1, |ammo| ammo.count)
+}
+
+pub fn get_item_weight(raws: &RawMaster, key: &str) -> f32 {
+    raws.item_index
+        .get(key)
+        .map(|idx| &raws.raws.items[*idx])
+        .and_then(|item| Some(item.weight_lbs? * spawn_quantity(item) as f32))
+        .unwrap_or(0.0)
+}
+
 pub fn get_vendor_items(categories: &[String], raws: &RawMaster) -> Vec<(String, f32)> {
     let mut result = Vec::new();
 
     for item in raws.raws.items.iter() {
         if let Some(cat) = &item.vendor_category {
             if categories.contains(cat) && item.base_value.is_some() {
-                result.push((item.name.clone(), item.base_value.unwrap()));
+                result.push((
+                    item.name.clone(),
+                    item.base_value.unwrap() * spawn_quantity(item) as f32,
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+pub fn get_mercenaries(names: &[String], raws: &RawMaster) -> Vec<(String, f32)> {
+    let mut result = Vec::new();
+
+    for name in names.iter() {
+        if let Some(idx) = raws.mob_index.get(name) {
+            if let Some(cost) = raws.raws.mobs[*idx].hire_cost {
+                result.push((name.clone(), cost));
             }
         }
     }
@@ -330,7 +338,178 @@ pub fn is_tag_magic(tag: &str) -> bool {
         let item_template = &raws.raws.items[raws.item_index[tag]];
         item_template.magic.is_some()
     } else {
-        false
+        parse_affixed_name(raws, tag).is_some()
+    }
+}
+
+fn compose_affixed_name(prefix: Option<&str>, base: &str, suffix: Option<&str>) -> String {
+    let mut name = base.to_string();
+    if let Some(prefix) = prefix {
+        name = format!("{} {}", prefix, name);
+    }
+    if let Some(suffix) = suffix {
+        name = format!("{} {}", name, suffix);
+    }
+    name
+}
+
+/// Works out which base item and affixes a composed name like "Venomous Longsword +2" came from
+fn parse_affixed_name(raws: &RawMaster, key: &str) -> Option<(String, Vec<usize>)> {
+    let prefixes = raws
+        .raws
+        .affixes
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| a.position == "prefix" && key.starts_with(&format!("{} ", a.name)))
+        .map(|(i, a)| (Some(i), a.name.len() + 1))
+        .chain(std::iter::once((None, 0)));
+
+    for (prefix, prefix_len) in prefixes {
+        let rest = &key[prefix_len..];
+        let suffixes = raws
+            .raws
+            .affixes
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.position == "suffix" && rest.ends_with(&format!(" {}", a.name)))
+            .map(|(i, a)| (Some(i), a.name.len() + 1))
+            .chain(std::iter::once((None, 0)));
+
+        for (suffix, suffix_len) in suffixes {
+            if prefix.is_none() && suffix.is_none() {
+                continue;
+            }
+            let base = &rest[..rest.len() - suffix_len];
+            if let Some(idx) = raws.item_index.get(base) {
+                let template = &raws.raws.items[*idx];
+                let affixes: Vec<usize> = prefix.into_iter().chain(suffix).collect();
+                let fits = affixes
+                    .iter()
+                    .all(|a| affix_fits(&raws.raws.affixes[*a], template));
+                if fits && template.magic.is_none() {
+                    return Some((base.to_string(), affixes));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn affix_fits(affix: &super::Affix, item: &super::Item) -> bool {
+    affix.applies_to.iter().any(|kind| match kind.as_str() {
+        "weapon" => item.weapon.is_some(),
+        "armor" => item.wearable.is_some(),
+        _ => false,
+    })
+}
+
+/// Maybe rolls a prefix, a suffix or both for a weapon or armour piece found at this depth
+fn roll_affixes(raws: &RawMaster, item: &super::Item, depth: i32) -> Vec<usize> {
+    let chance = i32::min(
+        MAGIC_ITEM_MAX_CHANCE,
+        MAGIC_ITEM_BASE_CHANCE + depth * MAGIC_ITEM_CHANCE_PER_DEPTH,
+    );
+    if item.magic.is_some()
+        || (item.weapon.is_none() && item.wearable.is_none())
+        || crate::tutorial::rng::roll_dice(1, 100) > chance
+    {
+        return Vec::new();
+    }
+
+    let positions: &[&str] = match crate::tutorial::rng::roll_dice(1, 3) {
+        1 => &["prefix"],
+        2 => &["suffix"],
+        _ => &["prefix", "suffix"],
+    };
+
+    let mut result = Vec::new();
+    for position in positions.iter() {
+        let mut table = RandomTable::new();
+        for (i, affix) in raws.raws.affixes.iter().enumerate() {
+            if affix.position == *position
+                && depth >= affix.min_depth
+                && depth <= affix.max_depth
+                && affix_fits(affix, item)
+            {
+                table.add(i, affix.weight);
+            }
+        }
+        if let Ok(idx) = table.roll().parse::<usize>() {
+            result.push(idx);
+        }
+    }
+    result
+}
+
+fn apply_affix(item: &mut super::Item, affix: &super::Affix) {
+    if let Some(base_value) = item.base_value.as_mut() {
+        *base_value += affix.value.unwrap_or(0.0);
+    }
+    if let (Some(initiative_penalty), Some(bonus)) =
+        (item.initiative_penalty.as_mut(), affix.initiative_bonus)
+    {
+        *initiative_penalty -= bonus;
+    }
+
+    if let Some(weapon) = item.weapon.as_mut() {
+        weapon.hit_bonus += affix.hit_bonus.unwrap_or(0);
+        if let Some(damage_bonus) = affix.damage_bonus {
+            let (n, die, plus) = parse_dice_string(&weapon.base_damage);
+            let final_bonus = plus + damage_bonus;
+            weapon.base_damage = match final_bonus.cmp(&0) {
+                std::cmp::Ordering::Less => format!("{n}d{die}{final_bonus}"),
+                std::cmp::Ordering::Greater => format!("{n}d{die}+{final_bonus}"),
+                _ => format!("{n}d{die}"),
+            };
+        }
+        if let Some(effects) = &affix.proc_effects {
+            weapon.proc_chance = Some(affix.proc_chance.unwrap_or(DEFAULT_AFFIX_PROC_CHANCE));
+            let mut merged = weapon.proc_effects.clone().unwrap_or_default();
+            merged.extend(effects.clone());
+            weapon.proc_effects = Some(merged);
+        }
+        if let Some(script) = &affix.proc_script {
+            weapon.proc_chance = Some(affix.proc_chance.unwrap_or(DEFAULT_AFFIX_PROC_CHANCE));
+            weapon.proc_script = Some(match weapon.proc_script.take() {
+                Some(existing) => super::Script::Sequence {
+                    effects: vec![existing, script.clone()],
+                },
+                None => script.clone(),
+            });
+        }
+        if let Some(crit_effects) = &affix.crit_effects {
+            let mut merged = weapon.crit_effects.clone().unwrap_or_default();
+            merged.extend(crit_effects.clone());
+            weapon.crit_effects = Some(merged);
+        }
+    }
+
+    if let Some(armor) = item.wearable.as_mut() {
+        armor.armor_class += affix.armor_class.unwrap_or(0.0);
+    }
+
+    if let Some(resistances) = &affix.resistances {
+        let mut merged = item.resistances.clone().unwrap_or_default();
+        for (damage_type, amount) in resistances.iter() {
+            *merged.entry(damage_type.clone()).or_insert(0) += amount;
+        }
+        item.resistances = Some(merged);
+    }
+
+    if let Some(attributes) = &affix.attributes {
+        let add = |a: Option<i32>, b: Option<i32>| match (a, b) {
+            (None, None) => None,
+            _ => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+        };
+        item.attributes = Some(match &item.attributes {
+            Some(existing) => super::ItemAttributeBonus {
+                might: add(existing.might, attributes.might),
+                fitness: add(existing.fitness, attributes.fitness),
+                quickness: add(existing.quickness, attributes.quickness),
+                intelligence: add(existing.intelligence, attributes.intelligence),
+            },
+            None => attributes.clone(),
+        });
     }
 }
 
@@ -342,6 +521,9 @@ pub fn spawn_named_entity(
 ) -> Option<Entity> {
     if raws.item_index.contains_key(key) {
         spawn_named_item(raws, ecs, key, pos)
+    } else if let Some((base, affixes)) = parse_affixed_name(raws, key) {
+        let item = raws.build_affixed_item(&base, &affixes);
+        Some(spawn_item_from_template(raws, ecs, &base, &item, pos))
     } else if raws.mob_index.contains_key(key) {
         spawn_named_mob(raws, ecs, key, pos)
     } else if raws.prop_index.contains_key(key) {
@@ -369,6 +551,224 @@ fn parse_particle(n: &str) -> SpawnParticleBurst {
     }
 }
 
+fn compile_script(script: &super::Script) -> EffectNode {
+    use super::Script;
+    let boxed = |script: &Script| Box::new(compile_script(script));
+    match script {
+        Script::Sequence { effects } => {
+            EffectNode::Sequence(effects.iter().map(compile_script).collect())
+        }
+        Script::Chance { percent, effect } => EffectNode::Chance {
+            percent: *percent,
+            effect: boxed(effect),
+        },
+        Script::IfTarget {
+            condition,
+            then,
+            otherwise,
+        } => EffectNode::IfTarget {
+            condition: compile_condition(condition),
+            then: boxed(then),
+            otherwise: otherwise.as_deref().map(boxed),
+        },
+        Script::Area {
+            shape,
+            radius,
+            effect,
+        } => EffectNode::Area {
+            shape: match shape.as_str() {
+                "square" => AreaShape::Square { radius: *radius },
+                "circle" => AreaShape::Circle { radius: *radius },
+                _ => {
+                    rltk::console::log(format!("Warning: unknown area shape [{}]", shape));
+                    AreaShape::Circle { radius: *radius }
+                }
+            },
+            effect: boxed(effect),
+        },
+        Script::OnKill { effect } => EffectNode::OnKill(boxed(effect)),
+        Script::Damage {
+            amount,
+            damage_type,
+        } => {
+            let (n_dice, die_type, bonus) = parse_dice_string(amount);
+            EffectNode::Damage {
+                n_dice,
+                die_type,
+                bonus,
+                damage_type: damage_type
+                    .as_deref()
+                    .map_or(DamageType::Arcane, string_to_damage_type),
+            }
+        }
+        Script::Healing { amount } => EffectNode::Healing { amount: *amount },
+        Script::Mana { amount } => EffectNode::Mana { amount: *amount },
+        Script::Confusion { turns } => EffectNode::Confusion { turns: *turns },
+        Script::Slow {
+            initiative_penalty,
+            turns,
+        } => EffectNode::Slow {
+            initiative_penalty: *initiative_penalty,
+            turns: turns.unwrap_or(DEFAULT_SLOW_TURNS),
+        },
+        Script::DamageOverTime {
+            damage,
+            damage_type,
+            turns,
+        } => EffectNode::DamageOverTime {
+            damage: *damage,
+            damage_type: damage_type
+                .as_deref()
+                .map_or(DamageType::Poison, string_to_damage_type),
+            turns: turns.unwrap_or(DEFAULT_DAMAGE_OVER_TIME_TURNS),
+        },
+        Script::Attributes {
+            name,
+            might,
+            fitness,
+            quickness,
+            intelligence,
+            turns,
+        } => EffectNode::Attributes {
+            name: name.clone(),
+            bonus: AttributeBonus {
+                might: *might,
+                fitness: *fitness,
+                quickness: *quickness,
+                intelligence: *intelligence,
+            },
+            turns: turns.unwrap_or(DEFAULT_ATTRIBUTE_TURNS),
+        },
+        Script::Stun { turns } => EffectNode::Stun { turns: *turns },
+        Script::Knockback { distance } => EffectNode::Knockback {
+            distance: *distance,
+        },
+        Script::Pull { distance } => EffectNode::Pull {
+            distance: *distance,
+        },
+        Script::Blink => EffectNode::Blink,
+        Script::RandomTeleport { range } => EffectNode::RandomTeleport { range: *range },
+        Script::SwapPlaces => EffectNode::SwapPlaces,
+        Script::Particle {
+            glyph,
+            color,
+            lifetime_ms,
+        } => EffectNode::Particle {
+            glyph: rltk::to_cp437(glyph.chars().next().unwrap()),
+            fg: rltk::RGB::from_hex(color).expect("Bad RGB"),
+            lifespan: *lifetime_ms,
+        },
+        Script::Status {
+            status,
+            turns,
+            on_apply,
+            on_tick,
+            on_expire,
+        } => EffectNode::Status {
+            kind: string_to_status_kind(status),
+            turns: *turns,
+            on_apply: on_apply.as_deref().map(boxed),
+            on_tick: on_tick.as_deref().map(boxed),
+            on_expire: on_expire.as_deref().map(boxed),
+        },
+        Script::Hazard { hazard, turns } => EffectNode::Hazard {
+            kind: string_to_hazard_kind(hazard),
+            turns: *turns,
+        },
+        Script::Summon {
+            mob,
+            count,
+            duration,
+            faction,
+        } => EffectNode::Summon {
+            mob: mob.clone(),
+            count: count.unwrap_or(1),
+            duration: duration.unwrap_or(0),
+            faction: faction.clone().unwrap_or_else(|| "caster".to_string()),
+        },
+        Script::Polymorph { form, turns } => EffectNode::Polymorph {
+            form: form.clone(),
+            turns: turns.unwrap_or(DEFAULT_POLYMORPH_TURNS),
+        },
+    }
+}
+
+fn compile_condition(condition: &super::ScriptCondition) -> TargetCondition {
+    use super::ScriptCondition;
+    match condition {
+        ScriptCondition::Faction { name } => TargetCondition::Faction(name.clone()),
+        ScriptCondition::HealthBelow { percent } => TargetCondition::HealthBelow(*percent),
+        ScriptCondition::HealthAbove { percent } => TargetCondition::HealthAbove(*percent),
+        ScriptCondition::IsPlayer => TargetCondition::IsPlayer,
+        ScriptCondition::Dead => TargetCondition::Dead,
+        ScriptCondition::Not { condition } => {
+            TargetCondition::Not(Box::new(compile_condition(condition)))
+        }
+    }
+}
+
+/// Reads an area shape such as "cone;90"; `size` is the radius or length from `area_of_effect`
+fn parse_aoe_shape(shape: Option<&str>, size: i32) -> AoeShape {
+    let tokens: Vec<&str> = shape.unwrap_or("circle").split(';').collect();
+    match tokens[0] {
+        "circle" => AoeShape::Circle { radius: size },
+        "cone" => AoeShape::Cone {
+            length: size,
+            angle: tokens
+                .get(1)
+                .map_or(90, |angle| angle.parse::<i32>().unwrap()),
+        },
+        "line" => AoeShape::Line { length: size },
+        "wall" => AoeShape::Wall { length: size },
+        "ring" => AoeShape::Ring { radius: size },
+        "blast" => AoeShape::Blast { radius: size },
+        _ => {
+            rltk::console::log(format!("Warning: unknown area shape [{}]", tokens[0]));
+            AoeShape::Circle { radius: size }
+        }
+    }
+}
+
+fn parse_hazard(hazard: &str) -> LeavesHazard {
+    let tokens: Vec<&str> = hazard.split(';').collect();
+    LeavesHazard {
+        kind: string_to_hazard_kind(tokens[0]),
+        turns: tokens
+            .get(1)
+            .map_or(5, |turns| turns.parse::<i32>().unwrap()),
+    }
+}
+
+/// "mob;count;duration;faction", where a duration of 0 lasts until killed and the
+/// faction "caster" sides with whoever did the summoning
+fn parse_summon(summon: &str) -> SummonsCreature {
+    let tokens: Vec<&str> = summon.split(';').collect();
+    SummonsCreature {
+        mob: tokens[0].to_string(),
+        count: tokens
+            .get(1)
+            .map_or(1, |count| count.parse::<i32>().unwrap()),
+        duration: tokens
+            .get(2)
+            .map_or(0, |duration| duration.parse::<i32>().unwrap()),
+        faction: tokens
+            .get(3)
+            .map_or("caster", |faction| faction)
+            .to_string(),
+    }
+}
+
+/// "form;turns", where the form "random" picks a beast when the effect lands
+fn parse_polymorph(polymorph: &str) -> Polymorphs {
+    let tokens: Vec<&str> = polymorph.split(';').collect();
+    Polymorphs {
+        form: tokens[0].to_string(),
+        turns: tokens.get(1).map_or(DEFAULT_POLYMORPH_TURNS, |turns| {
+            turns.parse::<i32>().unwrap()
+        }),
+    }
+}
+
 macro_rules! apply_effects {
     ( $effects:expr, $eb:expr ) => {
         for effect in $effects.iter() {
@@ -397,13 +797,21 @@ macro_rules! apply_effects {
                 "damage" => {
                     $eb = $eb.with(InflictsDamage {
                         damage: effect.1.parse::<i32>().unwrap(),
+                        damage_type: $effects
+                            .get("damage_type")
+                            .map_or(DamageType::Arcane, |dt| string_to_damage_type(dt)),
                     });
                 }
+                "damage_type" => {}
                 "area_of_effect" => {
                     $eb = $eb.with(AreaOfEffect {
-                        radius: effect.1.parse::<i32>().unwrap(),
+                        shape: parse_aoe_shape(
+                            $effects.get("aoe_shape").map(|s| s.as_str()),
+                            effect.1.parse::<i32>().unwrap(),
+                        ),
                     });
                 }
+                "aoe_shape" => {}
                 "confusion" => {
                     $eb = $eb.with(Confusion {});
                     $eb = $eb.with(Duration {
@@ -424,9 +832,37 @@ macro_rules! apply_effects {
                         initiative_penalty: effect.1.parse::<f32>().unwrap(),
                     })
                 }
+                "hazard" => $eb = $eb.with(parse_hazard(&effect.1)),
+                "summon" => $eb = $eb.with(parse_summon(&effect.1)),
+                "polymorph" => $eb = $eb.with(parse_polymorph(&effect.1)),
+                "deploy_trap" => {
+                    $eb = $eb.with(DeploysTrap {
+                        prop: effect.1.to_string(),
+                    })
+                }
+                "blink" => $eb = $eb.with(Blink {}),
+                "random_teleport" => {
+                    $eb = $eb.with(RandomTeleport {
+                        range: effect.1.parse::<i32>().unwrap(),
+                    })
+                }
+                "swap_places" => $eb = $eb.with(SwapPlaces {}),
+                "knockback" => {
+                    $eb = $eb.with(Knockback {
+                        distance: effect.1.parse::<i32>().unwrap(),
+                    })
+                }
+                "pull" => {
+                    $eb = $eb.with(Pull {
+                        distance: effect.1.parse::<i32>().unwrap(),
+                    })
+                }
                 "damage_over_time" => {
                     $eb = $eb.with(DamageOverTime {
                         damage: effect.1.parse::<i32>().unwrap(),
+                        damage_type: $effects
+                            .get("damage_type")
+                            .map_or(DamageType::Poison, |dt| string_to_damage_type(dt)),
                     })
                 }
                 _ => rltk::console::log(format!(
@@ -493,11 +929,21 @@ fn spawn_named_spell(raws: &RawMaster, ecs: &mut World, key: &str) -> Option<Ent
         let mut eb = ecs.create_entity().marked::<SimpleMarker<SerializeMe>>();
         eb = eb.with(SpellTemplate {
             mana_cost: spell_template.mana_cost,
+            school: spell_template
+                .school
+                .as_deref()
+                .map_or(SpellSchool::Evocation, string_to_spell_school),
+            description: spell_template.description.clone().unwrap_or_default(),
         });
         eb = eb.with(Name {
             name: spell_template.name.clone(),
         });
         apply_effects!(spell_template.effects, eb);
+        if let Some(script) = &spell_template.script {
+            eb = eb.with(EffectScript {
+                root: compile_script(script),
+            });
+        }
 
         return Some(eb.build());
     }
@@ -512,128 +958,245 @@ fn spawn_named_item(
     pos: SpawnType,
 ) -> Option<Entity> {
     if raws.item_index.contains_key(key) {
+        let mut key = key;
+
+        // A unique only ever turns up once; after that its base item spawns in its place
+        if let Some(base) = raws.unique_bases.get(key) {
+            let mut dm = ecs.fetch_mut::<crate::map::MasterDungeonMap>();
+            if dm.spawned_uniques.contains(key) {
+                key = base;
+            } else {
+                dm.spawned_uniques.insert(key.to_string());
+            }
+        }
         let item_template = &raws.raws.items[raws.item_index[key]];
-        let dm = ecs.fetch::<crate::map::MasterDungeonMap>();
-        let scroll_names = dm.scroll_mappings.clone();
-        let potion_names = dm.potion_mappings.clone();
-        let identified = dm.identified_items.clone();
-        std::mem::drop(dm);
 
-        let mut eb = ecs.create_entity().marked::<SimpleMarker<SerializeMe>>();
-        eb = spawn_position(pos, eb, key, raws);
+        // Weapons and armour lying around the dungeon or stashed in chests may be magical
+        if matches!(
+            pos,
+            SpawnType::AtPosition { .. } | SpawnType::Contained { .. }
+        ) {
+            let depth = ecs.fetch::<crate::map::Map>().depth;
+            let affixes = roll_affixes(raws, item_template, depth);
+            if !affixes.is_empty() {
+                let item = raws.build_affixed_item(key, &affixes);
+                return Some(spawn_item_from_template(raws, ecs, key, &item, pos));
+            }
+        }
 
-        if let Some(renderable) = &item_template.renderable {
-            eb = eb.with(get_renderable_component(renderable));
+        return Some(spawn_item_from_template(raws, ecs, key, item_template, pos));
+    }
+    None
+}
+
+/// Builds an item entity from a raw template; `key` is the name of the raw it is based on
+fn spawn_item_from_template(
+    raws: &RawMaster,
+    ecs: &mut World,
+    key: &str,
+    item_template: &super::Item,
+    pos: SpawnType,
+) -> Entity {
+    let dm = ecs.fetch::<crate::map::MasterDungeonMap>();
+    let scroll_names = dm.scroll_mappings.clone();
+    let potion_names = dm.potion_mappings.clone();
+    let identified = dm.identified_items.clone();
+    std::mem::drop(dm);
+
+    let mut eb = ecs.create_entity().marked::<SimpleMarker<SerializeMe>>();
+    eb = spawn_position(pos, eb, key, raws);
+
+    if let Some(renderable) = &item_template.renderable {
+        eb = eb.with(get_renderable_component(renderable));
+    }
+
+    if let Some(weapon) = &item_template.weapon {
+        eb = eb.with(Equippable {
+            slot: EquipmentSlot::Melee,
+        });
+        let (n_dice, die_type, bonus) = parse_dice_string(&weapon.base_damage);
+        let wpn = Weapon {
+            range: if weapon.range == "melee" {
+                None
+            } else {
+                Some(weapon.range.parse::<i32>().expect("Not a number"))
+            },
+            attribute: match weapon.attribute.as_str() {
+                "Quickness" => WeaponAttribute::Quickness,
+                _ => WeaponAttribute::Might,
+            },
+            damage_n_dice: n_dice,
+            damage_die_type: die_type,
+            damage_bonus: bonus,
+            damage_type: match &weapon.damage_type {
+                Some(dt) => string_to_damage_type(dt),
+                None if weapon.range == "melee" => DamageType::Slashing,
+                None => DamageType::Piercing,
+            },
+            hit_bonus: weapon.hit_bonus,
+            proc_chance: weapon.proc_chance,
+            proc_target: weapon.proc_target.clone(),
+            crit_effects: weapon
+                .crit_effects
+                .as_ref()
+                .map(parse_crit_effects)
+                .unwrap_or_default(),
+            ammo: weapon.ammo.as_deref().map(string_to_ammo_type),
+        };
+        eb = eb.with(wpn);
+        if let Some(proc_effects) = &weapon.proc_effects {
+            apply_effects!(proc_effects, eb);
+        }
+        if let Some(script) = &weapon.proc_script {
+            eb = eb.with(EffectScript {
+                root: compile_script(script),
+            });
         }
 
-        if let Some(weapon) = &item_template.weapon {
-            eb = eb.with(Equippable {
-                slot: EquipmentSlot::Melee,
+        // Anything you can swing, you can throw - some things just fly better than others
+        if let Some(throwable) = &item_template.throwable {
+            eb = eb.with(Throwable {
+                range: throwable.range,
+                hit_bonus: throwable.hit_bonus,
+            });
+        } else if weapon.range == "melee" {
+            eb = eb.with(Throwable {
+                range: DEFAULT_THROW_RANGE,
+                hit_bonus: DEFAULT_THROW_HIT_PENALTY,
             });
-            let (n_dice, die_type, bonus) = parse_dice_string(&weapon.base_damage);
-            let wpn = Weapon {
-                range: if weapon.range == "melee" {
-                    None
-                } else {
-                    Some(weapon.range.parse::<i32>().expect("Not a number"))
-                },
-                attribute: match weapon.attribute.as_str() {
-                    "Quickness" => WeaponAttribute::Quickness,
-                    _ => WeaponAttribute::Might,
-                },
-                damage_n_dice: n_dice,
-                damage_die_type: die_type,
-                damage_bonus: bonus,
-                hit_bonus: weapon.hit_bonus,
-                proc_chance: weapon.proc_chance,
-                proc_target: weapon.proc_target.clone(),
-            };
-            eb = eb.with(wpn);
-            if let Some(proc_effects) = &weapon.proc_effects {
-                apply_effects!(proc_effects, eb);
-            }
         }
+    }
 
-        if let Some(magic) = &item_template.magic {
-            let class = match magic.class.as_str() {
-                "rare" => MagicItemClass::Rare,
-                "legendary" => MagicItemClass::Legendary,
-                _ => MagicItemClass::Common,
-            };
-            eb = eb.with(MagicItem { class });
-
-            if !identified.contains(&item_template.name) {
-                match magic.naming.as_str() {
-                    "scroll" => {
-                        eb = eb.with(ObfuscatedName {
-                            name: scroll_names[&item_template.name].clone(),
-                        });
-                    }
-                    "potion" => {
-                        eb = eb.with(ObfuscatedName {
-                            name: potion_names[&item_template.name].clone(),
-                        });
-                    }
-                    _ => {
-                        eb = eb.with(ObfuscatedName {
-                            name: magic.naming.clone(),
-                        });
-                    }
-                }
-            }
+    if let Some(ammo) = &item_template.ammo {
+        eb = eb.with(Equippable {
+            slot: EquipmentSlot::Ammo,
+        });
+        eb = eb.with(Stackable {
+            quantity: ammo.count,
+        });
+        eb = eb.with(Ammunition {
+            ammo_type: string_to_ammo_type(&ammo.ammo_type),
+            hit_bonus: ammo.hit_bonus.unwrap_or(0),
+            damage_bonus: ammo.damage_bonus.unwrap_or(0),
+            damage_type: ammo.damage_type.as_deref().map(string_to_damage_type),
+            recovery_chance: ammo.recovery_chance.unwrap_or(DEFAULT_AMMO_RECOVERY),
+            proc_chance: ammo.proc_chance,
+        });
+        if let Some(proc_effects) = &ammo.proc_effects {
+            apply_effects!(proc_effects, eb);
+        }
+    }
 
-            if let Some(cursed) = magic.cursed {
-                if cursed {
-                    eb = eb.with(CursedItem {});
+    if let Some(magic) = &item_template.magic {
+        eb = eb.with(MagicItem {
+            class: string_to_magic_class(&magic.class),
+        });
+
+        if !identified.contains(&item_template.name) {
+            match magic.naming.as_str() {
+                "scroll" => {
+                    eb = eb.with(ObfuscatedName {
+                        name: scroll_names[&item_template.name].clone(),
+                    });
+                }
+                "potion" => {
+                    eb = eb.with(ObfuscatedName {
+                        name: potion_names[&item_template.name].clone(),
+                    });
+                }
+                _ => {
+                    eb = eb.with(ObfuscatedName {
+                        name: magic.naming.clone(),
+                    });
                 }
             }
         }
 
-        if let Some(wearable) = &item_template.wearable {
-            let slot = string_to_slot(&wearable.slot);
-            eb = eb.with(Equippable { slot });
-            eb = eb.with(Wearable {
-                slot,
-                armor_class: wearable.armor_class,
-            });
+        if let Some(cursed) = magic.cursed {
+            if cursed {
+                eb = eb.with(CursedItem {});
+            }
         }
+    }
 
-        if let Some(ab) = &item_template.attributes {
-            eb = eb.with(AttributeBonus {
-                might: ab.might,
-                fitness: ab.fitness,
-                quickness: ab.quickness,
-                intelligence: ab.intelligence,
-            });
-        }
+    if let Some(wearable) = &item_template.wearable {
+        let slot = string_to_slot(&wearable.slot);
+        eb = eb.with(Equippable { slot });
+        eb = eb.with(Wearable {
+            slot,
+            armor_class: wearable.armor_class,
+        });
+    }
 
-        eb = eb.with(Name {
-            name: item_template.name.clone(),
+    if let Some(resistances) = &item_template.resistances {
+        eb = eb.with(parse_resistances(resistances));
+    }
+
+    if let Some(bag) = &item_template.bag {
+        eb = eb.with(Bag {
+            capacity_lbs: bag.capacity_lbs,
         });
+    }
 
-        eb = eb.with(crate::components::Item {
-            initiative_penalty: item_template.initiative_penalty.unwrap_or(0.0),
-            weight_lbs: item_template.weight_lbs.unwrap_or(0.0),
-            base_value: item_template.base_value.unwrap_or(0.0),
+    if let Some(durability) = item_template.durability {
+        eb = eb.with(Durability {
+            current: durability,
+            max: durability,
         });
+    }
 
-        if let Some(consumable) = &item_template.consumable {
-            let max_charges = consumable.charges.unwrap_or(0);
-            eb = eb.with(Consumable {
-                max_charges,
-                charges: i32::max(1, max_charges),
+    if let Some(ab) = &item_template.attributes {
+        eb = eb.with(AttributeBonus {
+            might: ab.might,
+            fitness: ab.fitness,
+            quickness: ab.quickness,
+            intelligence: ab.intelligence,
+        });
+    }
+
+    eb = eb.with(Name {
+        name: item_template.name.clone(),
+    });
+
+    eb = eb.with(crate::components::Item {
+        initiative_penalty: item_template.initiative_penalty.unwrap_or(0.0),
+        weight_lbs: item_template.weight_lbs.unwrap_or(0.0),
+        base_value: item_template.base_value.unwrap_or(0.0),
+    });
+
+    if let Some(consumable) = &item_template.consumable {
+        let max_charges = consumable.charges.unwrap_or(0);
+        eb = eb.with(Consumable {
+            max_charges,
+            charges: i32::max(1, max_charges),
+        });
+        if max_charges == 0 {
+            eb = eb.with(Stackable { quantity: 1 });
+        }
+        apply_effects!(consumable.effects, eb);
+        if let Some(script) = &consumable.script {
+            eb = eb.with(EffectScript {
+                root: compile_script(script),
             });
-            apply_effects!(consumable.effects, eb);
         }
-
-        return Some(eb.build());
     }
-    None
+
+    eb.build()
 }
 
-fn spawn_named_mob(raws: &RawMaster, ecs: &mut World, key: &str, pos: SpawnType) -> Option<Entity> {
+pub fn spawn_named_mob(
+    raws: &RawMaster,
+    ecs: &mut World,
+    key: &str,
+    pos: SpawnType,
+) -> Option<Entity> {
     if raws.mob_index.contains_key(key) {
         let mob_template = &raws.raws.mobs[raws.mob_index[key]];
+        let spawn_point = if let SpawnType::AtPosition { x, y } = pos {
+            Some((x, y))
+        } else {
+            None
+        };
         let mut eb = ecs.create_entity().marked::<SimpleMarker<SerializeMe>>();
         eb = spawn_position(pos, eb, key, raws);
 
@@ -707,40 +1270,51 @@ fn spawn_named_mob(raws: &RawMaster, ecs: &mut World, key: &str, pos: SpawnType)
         // why is the mob defaulted to 2?
         eb = eb.with(Initiative { current: 2 });
 
-        let mut attr = Attributes {
-            ..Default::default()
-        };
+        eb = eb.with(mob_attributes(mob_template));
 
-        let mut mob_fitness = 11;
-        let mut mob_int = 11;
-        if let Some(might) = mob_template.attributes.might {
-            attr.might = Attribute::new_base(might);
+        if let Some(vendor) = &mob_template.vendor {
+            eb = eb.with(Vendor {
+                categories: vendor.clone(),
+            });
         }
 
-        if let Some(fitness) = mob_template.attributes.fitness {
-            attr.fitness = Attribute::new_base(fitness);
-            mob_fitness = fitness;
+        if mob_template.repairs.unwrap_or(false) {
+            eb = eb.with(RepairService {});
         }
 
-        if let Some(quickness) = mob_template.attributes.quickness {
-            attr.quickness = Attribute::new_base(quickness);
+        if let Some(mercenaries) = &mob_template.mercenaries {
+            eb = eb.with(Recruiter {
+                mercenaries: mercenaries.clone(),
+            });
         }
 
-        if let Some(intelligence) = mob_template.attributes.intelligence {
-            attr.intelligence = Attribute::new_base(intelligence);
-            mob_int = intelligence;
+        if let Some(resistances) = &mob_template.resistances {
+            eb = eb.with(parse_resistances(resistances));
         }
-        eb = eb.with(attr);
 
-        if let Some(vendor) = &mob_template.vendor {
-            eb = eb.with(Vendor {
-                categories: vendor.clone(),
+        if let Some(immunities) = &mob_template.immunities {
+            eb = eb.with(StatusImmunities {
+                kinds: immunities
+                    .iter()
+                    .map(|status| string_to_status_kind(status))
+                    .collect(),
             });
         }
 
+        if mob_template.uses_items.unwrap_or(false) {
+            eb = eb.with(UsesItems {});
+        }
+
+        // Scheduled townsfolk work where they were spawned; the town hands out homes later
+        if let (true, Some(spawn_point)) = (mob_template.schedule.unwrap_or(false), spawn_point) {
+            eb = eb.with(Schedule {
+                work: spawn_point,
+                home: spawn_point,
+            });
+        }
+
+        let (mob_hp, mob_mana) = mob_pools(mob_template);
         let mob_level = mob_template.level.unwrap_or(1);
-        let mob_hp = npc_hp(mob_fitness, mob_level);
-        let mob_mana = mana_at_level(mob_int, mob_level);
 
         let pools = Pools {
             level: mob_level,
@@ -754,6 +1328,8 @@ fn spawn_named_mob(raws: &RawMaster, ecs: &mut World, key: &str, pos: SpawnType)
                 max: mob_mana,
             },
             total_weight: 0.0,
+            carry_capacity_lbs: 0,
+            burden: BurdenLevel::Unburdened,
             total_initiative_penalty: 0.0,
             gold: if let Some(gold) = &mob_template.gold {
                 let (n, d, b) = parse_dice_string(gold);
@@ -778,6 +1354,7 @@ fn spawn_named_mob(raws: &RawMaster, ecs: &mut World, key: &str, pos: SpawnType)
         skills.skills.insert(Skill::Melee, 1);
         skills.skills.insert(Skill::Defense, 1);
         skills.skills.insert(Skill::Magic, 1);
+        skills.skills.insert(Skill::Perception, 1);
         if let Some(mobskills) = &mob_template.skills {
             for sk in mobskills.iter() {
                 match sk.0.as_str() {
@@ -790,6 +1367,9 @@ fn spawn_named_mob(raws: &RawMaster, ecs: &mut World, key: &str, pos: SpawnType)
                     "Magic" => {
                         skills.skills.insert(Skill::Magic, *sk.1);
                     }
+                    "Perception" => {
+                        skills.skills.insert(Skill::Perception, *sk.1);
+                    }
                     _ => {
                         rltk::console::log(format!("Unknown skill referneced: [{}]", sk.0));
                     }
@@ -836,25 +1416,7 @@ fn spawn_named_mob(raws: &RawMaster, ecs: &mut World, key: &str, pos: SpawnType)
         });
 
         if let Some(na) = &mob_template.natural {
-            let mut nature = NaturalAttackDefense {
-                armor_class: na.armor_class,
-                attacks: Vec::new(),
-            };
-            if let Some(attacks) = &na.attacks {
-                for nattack in attacks.iter() {
-                    let (damage_n_dice, damage_die_type, damage_bonus) =
-                        parse_dice_string(&nattack.damage);
-                    let attack = NaturalAttack {
-                        name: nattack.name.clone(),
-                        hit_bonus: nattack.hit_bonus,
-                        damage_n_dice,
-                        damage_die_type,
-                        damage_bonus,
-                    };
-                    nature.attacks.push(attack);
-                }
-            }
-            eb = eb.with(nature);
+            eb = eb.with(mob_natural(na));
         }
 
         let new_mob = eb.build();
@@ -866,6 +1428,17 @@ fn spawn_named_mob(raws: &RawMaster, ecs: &mut World, key: &str, pos: SpawnType)
             }
         }
 
+        // Do they have anything in their pack?
+        if let Some(carrying) = &mob_template.carrying {
+            for tag in carrying.iter() {
+                if let Some(item) =
+                    spawn_named_entity(raws, ecs, tag, SpawnType::Carried { by: new_mob })
+                {
+                    crate::merge_into_stack(ecs, item);
+                }
+            }
+        }
+
         return Some(new_mob);
     }
     None
@@ -906,6 +1479,10 @@ fn spawn_named_prop(
             eb = eb.with(Hidden {});
         }
 
+        if let Some(difficulty) = prop_template.trap_difficulty {
+            eb = eb.with(Trap { difficulty });
+        }
+
         if Some(true) == prop_template.blocks_tile {
             eb = eb.with(BlocksTile {});
         }
@@ -921,13 +1498,69 @@ fn spawn_named_prop(
         if let Some(entry_trigger) = &prop_template.entry_trigger {
             eb = eb.with(EntryTrigger {});
             apply_effects!(entry_trigger.effects, eb);
+            if let Some(script) = &entry_trigger.script {
+                eb = eb.with(EffectScript {
+                    root: compile_script(script),
+                });
+            }
         }
 
-        return Some(eb.build());
+        let prop = eb.build();
+        if let Some(container) = &prop_template.container {
+            fill_container(raws, ecs, prop, container);
+        }
+
+        return Some(prop);
     }
     None
 }
 
+fn fill_container(
+    raws: &RawMaster,
+    ecs: &mut World,
+    prop: Entity,
+    container: &super::props_structs::Container,
+) {
+    ecs.write_storage::<Container>()
+        .insert(
+            prop,
+            Container {
+                locked: container.locked.unwrap_or(false),
+            },
+        )
+        .expect("Unable to insert container");
+
+    // A trap lives in its own entity, off the map, until the container is opened
+    if let Some(trap) = &container.trap {
+        if crate::tutorial::rng::roll_dice(1, 100) <= container.trap_chance.unwrap_or(100) {
+            let mut eb = ecs
+                .create_entity()
+                .marked::<SimpleMarker<SerializeMe>>()
+                .with(ContainerTrap { container: prop })
+                .with(SingleActivation {})
+                .with(Hidden {})
+                .with(Trap {
+                    difficulty: container.trap_difficulty.unwrap_or(DEFAULT_TRAP_DIFFICULTY),
+                });
+            apply_effects!(trap.effects, eb);
+            if let Some(script) = &trap.script {
+                eb = eb.with(EffectScript {
+                    root: compile_script(script),
+                });
+            }
+            eb.build();
+        }
+    }
+
+    if let Some(table) = &container.loot_table {
+        for _ in 0..container.loot_rolls.unwrap_or(1) {
+            if let Some(tag) = get_item_drop(raws, table) {
+                spawn_named_entity(raws, ecs, &tag, SpawnType::Contained { by: prop });
+            }
+        }
+    }
+}
+
 fn spawn_position<'a>(
     pos: SpawnType,
     new_entity: EntityBuilder<'a>,
@@ -940,6 +1573,7 @@ fn spawn_position<'a>(
     match pos {
         SpawnType::AtPosition { x, y } => eb = eb.with(Position { x, y }),
         SpawnType::Carried { by } => eb = eb.with(InBackpack { owner: by }),
+        SpawnType::Contained { by } => eb = eb.with(InContainer { container: by }),
         SpawnType::Equipped { by } => {
             let slot = find_slot_for_equippable_item(tag, raws);
             eb = eb.with(Equipped { owner: by, slot })
@@ -949,6 +1583,111 @@ fn spawn_position<'a>(
     eb
 }
 
+fn mob_attributes(mob_template: &super::Mob) -> Attributes {
+    let mut attr = Attributes {
+        ..Default::default()
+    };
+    if let Some(might) = mob_template.attributes.might {
+        attr.might = Attribute::new_base(might);
+    }
+    if let Some(fitness) = mob_template.attributes.fitness {
+        attr.fitness = Attribute::new_base(fitness);
+    }
+    if let Some(quickness) = mob_template.attributes.quickness {
+        attr.quickness = Attribute::new_base(quickness);
+    }
+    if let Some(intelligence) = mob_template.attributes.intelligence {
+        attr.intelligence = Attribute::new_base(intelligence);
+    }
+    attr
+}
+
+/// Maximum hit points and mana for a mob at its listed level
+fn mob_pools(mob_template: &super::Mob) -> (i32, i32) {
+    let mob_level = mob_template.level.unwrap_or(1);
+    (
+        npc_hp(mob_template.attributes.fitness.unwrap_or(11), mob_level),
+        mana_at_level(
+            mob_template.attributes.intelligence.unwrap_or(11),
+            mob_level,
+        ),
+    )
+}
+
+fn mob_natural(na: &super::MobNatural) -> NaturalAttackDefense {
+    let mut nature = NaturalAttackDefense {
+        armor_class: na.armor_class,
+        attacks: Vec::new(),
+    };
+    if let Some(attacks) = &na.attacks {
+        for nattack in attacks.iter() {
+            let (damage_n_dice, damage_die_type, damage_bonus) = parse_dice_string(&nattack.damage);
+            let attack = NaturalAttack {
+                name: nattack.name.clone(),
+                hit_bonus: nattack.hit_bonus,
+                damage_n_dice,
+                damage_die_type,
+                damage_bonus,
+                damage_type: nattack
+                    .damage_type
+                    .as_ref()
+                    .map_or(DamageType::Blunt, |dt| string_to_damage_type(dt)),
+            };
+            nature.attacks.push(attack);
+        }
+    }
+    nature
+}
+
+/// The shape a creature takes when polymorphed into this mob
+pub fn mob_form(raws: &RawMaster, key: &str) -> Option<CreatureForm> {
+    let mob_template = &raws.raws.mobs[*raws.mob_index.get(key)?];
+    let renderable = get_renderable_component(mob_template.renderable.as_ref()?);
+    let (hp, mana) = mob_pools(mob_template);
+    Some(CreatureForm {
+        glyph: renderable.glyph,
+        fg: renderable.fg,
+        natural: mob_template.natural.as_ref().map(mob_natural),
+        attributes: mob_attributes(mob_template),
+        hit_points: Pool {
+            current: hp,
+            max: hp,
+        },
+        mana: Pool {
+            current: mana,
+            max: mana,
+        },
+        faction: Some(
+            mob_template
+                .faction
+                .clone()
+                .unwrap_or_else(|| "Mindless".to_string()),
+        ),
+    })
+}
+
+/// Picks a form for a random polymorph: any single-tile mob that fights with its own teeth and claws
+pub fn random_beast_form(raws: &RawMaster) -> Option<String> {
+    let beasts: Vec<&String> = raws
+        .raws
+        .mobs
+        .iter()
+        .filter(|mob| {
+            mob.natural.as_ref().is_some_and(|na| na.attacks.is_some())
+                && mob
+                    .renderable
+                    .as_ref()
+                    .is_some_and(|r| r.x_size.unwrap_or(1) == 1 && r.y_size.unwrap_or(1) == 1)
+        })
+        .map(|mob| &mob.name)
+        .collect();
+    if beasts.is_empty() {
+        return None;
+    }
+    let roll = crate::tutorial::rng::range(0, beasts.len() as i32);
+    Some(beasts[roll as usize].clone())
+}
+
 fn get_renderable_component(
     renderable: &super::item_structs::Renderable,
 ) -> crate::components::Renderable {
@@ -993,6 +1732,10 @@ fn find_slot_for_equippable_item(tag: &str, raws: &RawMaster) -> EquipmentSlot {
         return EquipmentSlot::Melee;
     }
 
+    if item.ammo.is_some() {
+        return EquipmentSlot::Ammo;
+    }
+
     if let Some(wearable) = &item.wearable {
         return string_to_slot(&wearable.slot);
     }
@@ -1000,6 +1743,115 @@ fn find_slot_for_equippable_item(tag: &str, raws: &RawMaster) -> EquipmentSlot {
     panic!("Trying to equip {}, but it has no slot tag", tag);
 }
 
+fn string_to_magic_class(class: &str) -> MagicItemClass {
+    match class {
+        "rare" => MagicItemClass::Rare,
+        "legendary" => MagicItemClass::Legendary,
+        _ => MagicItemClass::Common,
+    }
+}
+
+fn string_to_spell_school(school: &str) -> SpellSchool {
+    match school.to_lowercase().as_str() {
+        "evocation" => SpellSchool::Evocation,
+        "conjuration" => SpellSchool::Conjuration,
+        "enchantment" => SpellSchool::Enchantment,
+        "necromancy" => SpellSchool::Necromancy,
+        _ => {
+            rltk::console::log(format!("Warning: unknown spell school [{}]", school));
+            SpellSchool::Evocation
+        }
+    }
+}
+
+fn string_to_status_kind(status: &str) -> StatusKind {
+    match status.to_lowercase().as_str() {
+        "poisoned" => StatusKind::Poisoned,
+        "bleeding" => StatusKind::Bleeding,
+        "burning" => StatusKind::Burning,
+        "chilled" => StatusKind::Chilled,
+        "withering" => StatusKind::Withering,
+        "stunned" => StatusKind::Stunned,
+        "confused" => StatusKind::Confused,
+        "slowed" => StatusKind::Slowed,
+        "hasted" => StatusKind::Hasted,
+        "regenerating" => StatusKind::Regenerating,
+        "invisible" => StatusKind::Invisible,
+        "polymorphed" => StatusKind::Polymorphed,
+        _ => {
+            rltk::console::log(format!("Warning: unknown status [{}]", status));
+            StatusKind::Attribute
+        }
+    }
+}
+
+fn string_to_hazard_kind(hazard: &str) -> HazardKind {
+    match hazard.to_lowercase().as_str() {
+        "fire" => HazardKind::Fire,
+        "gas" => HazardKind::Gas,
+        "web" => HazardKind::Web,
+        "oil" => HazardKind::Oil,
+        _ => {
+            rltk::console::log(format!("Warning: unknown hazard [{}]", hazard));
+            HazardKind::Gas
+        }
+    }
+}
+
+fn string_to_damage_type(damage_type: &str) -> DamageType {
+    match damage_type.to_lowercase().as_str() {
+        "slashing" => DamageType::Slashing,
+        "piercing" => DamageType::Piercing,
+        "blunt" => DamageType::Blunt,
+        "fire" => DamageType::Fire,
+        "cold" => DamageType::Cold,
+        "poison" => DamageType::Poison,
+        "arcane" => DamageType::Arcane,
+        _ => {
+            rltk::console::log(format!("Warning: unknown damage type [{}]", damage_type));
+            DamageType::Blunt
+        }
+    }
+}
+
+fn parse_resistances(resistances: &HashMap<String, i32>) -> Resistances {
+    Resistances {
+        resistances: resistances
+            .iter()
+            .map(|(damage_type, percent)| (string_to_damage_type(damage_type), *percent))
+            .collect(),
+    }
+}
+
+fn string_to_ammo_type(ammo_type: &str) -> AmmoType {
+    match ammo_type.to_lowercase().as_str() {
+        "bolt" => AmmoType::Bolt,
+        "arrow" => AmmoType::Arrow,
+        _ => {
+            rltk::console::log(format!("Warning: unknown ammo type [{}]", ammo_type));
+            AmmoType::Arrow
+        }
+    }
+}
+
+fn parse_crit_effects(crit_effects: &HashMap<String, String>) -> Vec<CritEffect> {
+    crit_effects
+        .iter()
+        .filter_map(|(name, value)| {
+            let amount = value.parse::<i32>().expect("Not a number");
+            match name.as_str() {
+                "bleed" => Some(CritEffect::Bleed { damage: amount }),
+                "stun" => Some(CritEffect::Stun { turns: amount }),
+                "knockback" => Some(CritEffect::Knockback { distance: amount }),
+                _ => {
+                    rltk::console::log(format!("Warning: crit effect {} not implemented", name));
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 fn string_to_slot(slot: &str) -> EquipmentSlot {
     match slot {
         "Shield" => EquipmentSlot::Shield,
@@ -1008,6 +1860,7 @@ fn string_to_slot(slot: &str) -> EquipmentSlot {
         "Legs" => EquipmentSlot::Legs,
         "Feet" => EquipmentSlot::Feet,
         "Hands" => EquipmentSlot::Hands,
+        "Ammo" => EquipmentSlot::Ammo,
         "Melee" => EquipmentSlot::Melee,
         _ => {
             rltk::console::log(format!("Warning: unknown equipment slot type [{}]", slot));
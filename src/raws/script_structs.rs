@@ -0,0 +1,108 @@
+use serde::Deserialize;
+
+/// A typed effect description, for anything the flat `effects` map can't express
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Script {
+    Sequence {
+        effects: Vec<Script>,
+    },
+    Chance {
+        percent: i32,
+        effect: Box<Script>,
+    },
+    IfTarget {
+        condition: ScriptCondition,
+        then: Box<Script>,
+        otherwise: Option<Box<Script>>,
+    },
+    Area {
+        shape: String,
+        radius: i32,
+        effect: Box<Script>,
+    },
+    OnKill {
+        effect: Box<Script>,
+    },
+    Damage {
+        amount: String,
+        damage_type: Option<String>,
+    },
+    Healing {
+        amount: i32,
+    },
+    Mana {
+        amount: i32,
+    },
+    Confusion {
+        turns: i32,
+    },
+    Slow {
+        initiative_penalty: f32,
+        turns: Option<i32>,
+    },
+    DamageOverTime {
+        damage: i32,
+        damage_type: Option<String>,
+        turns: Option<i32>,
+    },
+    Attributes {
+        name: String,
+        might: Option<i32>,
+        fitness: Option<i32>,
+        quickness: Option<i32>,
+        intelligence: Option<i32>,
+        turns: Option<i32>,
+    },
+    Stun {
+        turns: i32,
+    },
+    Knockback {
+        distance: i32,
+    },
+    Pull {
+        distance: i32,
+    },
+    Blink,
+    RandomTeleport {
+        range: i32,
+    },
+    SwapPlaces,
+    Particle {
+        glyph: String,
+        color: String,
+        lifetime_ms: f32,
+    },
+    Status {
+        status: String,
+        turns: i32,
+        on_apply: Option<Box<Script>>,
+        on_tick: Option<Box<Script>>,
+        on_expire: Option<Box<Script>>,
+    },
+    Hazard {
+        hazard: String,
+        turns: i32,
+    },
+    Summon {
+        mob: String,
+        count: Option<i32>,
+        duration: Option<i32>,
+        faction: Option<String>,
+    },
+    Polymorph {
+        form: String,
+        turns: Option<i32>,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScriptCondition {
+    Faction { name: String },
+    HealthBelow { percent: i32 },
+    HealthAbove { percent: i32 },
+    IsPlayer,
+    Dead,
+    Not { condition: Box<ScriptCondition> },
+}
@@ -5,5 +5,8 @@ use std::collections::HashMap;
 pub struct Spell {
     pub name: String,
     pub mana_cost: i32,
+    pub school: Option<String>,
+    pub description: Option<String>,
     pub effects: HashMap<String, String>,
+    pub script: Option<super::Script>,
 }
@@ -0,0 +1,894 @@
+use crate::{
+    effects::*, skill_bonus, Ammunition, Attributes, CritEffect, DamageType, Durability,
+    EquipmentSlot, Equipped, HungerClock, HungerState, ItemCondition, Maneuver,
+    NaturalAttackDefense, Resistances, Skill, Skills, StatusEffect, Weapon, WeaponAttribute,
+    Wearable,
+};
+use rltk::RGB;
+use specs::prelude::*;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum AttackSource {
+    Melee,
+    Ranged,
+    Thrown,
+    Spell,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum HitOutcome {
+    Hit,
+    CriticalHit,
+    Miss,
+    Fumble,
+}
+
+/// What happens to the weapon when an attack is badly fumbled
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum FumbleEffect {
+    DropWeapon,
+    DamageWeapon,
+}
+
+/// Where attack resolution gets its dice from. The game uses `GameDice`; anything that
+/// needs repeatable combat math can supply fixed rolls instead.
+pub trait DiceRoller {
+    fn roll_dice(&mut self, n: i32, die_type: i32) -> i32;
+
+    /// Picks one of `len` things, or nothing if there is nothing to pick from
+    fn pick_index(&mut self, len: usize) -> Option<usize> {
+        if len == 0 {
+            None
+        } else {
+            Some((self.roll_dice(1, len as i32) - 1) as usize)
+        }
+    }
+}
+
+pub struct GameDice;
+
+impl DiceRoller for GameDice {
+    fn roll_dice(&mut self, n: i32, die_type: i32) -> i32 {
+        crate::tutorial::rng::roll_dice(n, die_type)
+    }
+}
+
+/// Everything about the attacker that goes into a single attack
+#[derive(Clone)]
+pub struct AttackProfile {
+    pub source: AttackSource,
+    pub weapon: Weapon,
+    pub weapon_entity: Option<Entity>,
+    pub ammo_entity: Option<Entity>,
+    pub ammo_proc_chance: Option<f32>,
+    pub attribute_hit_bonus: i32,
+    pub skill_hit_bonus: i32,
+    pub status_hit_bonus: i32,
+    pub attribute_damage_bonus: i32,
+    pub skill_damage_bonus: i32,
+    pub always_hits: bool,
+}
+
+/// Everything about the defender that goes into their armor class
+#[derive(Clone, Copy, Default)]
+pub struct DefenseProfile {
+    pub base_armor_class: i32,
+    pub quickness_bonus: i32,
+    pub skill_bonus: i32,
+    pub item_bonus: i32,
+}
+
+impl DefenseProfile {
+    pub fn armor_class(&self) -> i32 {
+        self.base_armor_class + self.quickness_bonus + self.skill_bonus + self.item_bonus
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DamageBreakdown {
+    pub dice: i32,
+    pub attribute: i32,
+    pub skill: i32,
+    pub weapon: i32,
+    pub critical: i32,
+}
+
+impl DamageBreakdown {
+    pub fn total(&self) -> i32 {
+        i32::max(
+            0,
+            self.dice + self.attribute + self.skill + self.weapon + self.critical,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AttackResult {
+    pub outcome: HitOutcome,
+    pub natural_roll: i32,
+    pub hit_roll: i32,
+    pub armor_class: i32,
+    pub damage: DamageBreakdown,
+    pub proc_triggered: bool,
+    pub ammo_proc_triggered: bool,
+    pub fumble: Option<FumbleEffect>,
+}
+
+impl AttackResult {
+    pub fn is_hit(&self) -> bool {
+        self.outcome == HitOutcome::Hit || self.outcome == HitOutcome::CriticalHit
+    }
+}
+
+/// The storages attack resolution reads from, borrowed from whichever system is asking
+pub struct CombatData<'s, 'a> {
+    pub entities: &'s Entities<'a>,
+    pub attributes: &'s ReadStorage<'a, Attributes>,
+    pub skills: &'s ReadStorage<'a, Skills>,
+    pub hunger_clock: &'s ReadStorage<'a, HungerClock>,
+    pub equipped: &'s ReadStorage<'a, Equipped>,
+    pub weapons: &'s ReadStorage<'a, Weapon>,
+    pub wearables: &'s ReadStorage<'a, Wearable>,
+    pub natural: &'s ReadStorage<'a, NaturalAttackDefense>,
+    pub resistances: &'s ReadStorage<'a, Resistances>,
+    pub statuses: &'s ReadStorage<'a, StatusEffect>,
+    pub durabilities: &'s ReadStorage<'a, Durability>,
+}
+
+/// Percent chance that a weapon or armour piece loses a point of durability when it is used
+const WEAR_CHANCE: i32 = 25;
+
+/// The shape an item is in; anything without durability never wears out
+pub fn item_condition(item: Entity, durabilities: &ReadStorage<Durability>) -> ItemCondition {
+    durabilities
+        .get(item)
+        .map_or(ItemCondition::Good, |d| d.condition())
+}
+
+fn unarmed() -> Weapon {
+    Weapon {
+        range: None,
+        attribute: WeaponAttribute::Might,
+        hit_bonus: 0,
+        damage_n_dice: 1,
+        damage_die_type: 4,
+        damage_bonus: 0,
+        damage_type: DamageType::Blunt,
+        proc_chance: None,
+        proc_target: None,
+        crit_effects: Vec::new(),
+        ammo: None,
+    }
+}
+
+/// Builds the attack an entity would make with its natural attacks or wielded weapon
+pub fn attack_profile(
+    attacker: Entity,
+    source: AttackSource,
+    data: &CombatData,
+    dice: &mut dyn DiceRoller,
+) -> Option<AttackProfile> {
+    let attributes = data.attributes.get(attacker)?;
+    let skills = data.skills.get(attacker)?;
+
+    let mut weapon = unarmed();
+    if let Some(nat) = data.natural.get(attacker) {
+        if let Some(attack_index) = dice.pick_index(nat.attacks.len()) {
+            weapon.hit_bonus = nat.attacks[attack_index].hit_bonus;
+            weapon.damage_n_dice = nat.attacks[attack_index].damage_n_dice;
+            weapon.damage_die_type = nat.attacks[attack_index].damage_die_type;
+            weapon.damage_bonus = nat.attacks[attack_index].damage_bonus;
+            weapon.damage_type = nat.attacks[attack_index].damage_type;
+        }
+    }
+
+    // Find the wielded weapon
+    let mut weapon_entity = None;
+    for (wielded_entity, wielded, wielded_weapon) in
+        (data.entities, data.equipped, data.weapons).join()
+    {
+        if wielded.owner == attacker && wielded.slot == EquipmentSlot::Melee {
+            weapon = wielded_weapon.clone();
+            weapon_entity = Some(wielded_entity);
+        }
+    }
+
+    Some(weapon_profile(
+        attacker,
+        source,
+        weapon,
+        weapon_entity,
+        attributes,
+        skills,
+        data,
+    ))
+}
+
+/// Throwing a weapon uses its own damage, at whatever penalty it has for flying through the air
+pub fn throw_profile(
+    thrower: Entity,
+    item: Entity,
+    hit_bonus: i32,
+    data: &CombatData,
+) -> Option<AttackProfile> {
+    let attributes = data.attributes.get(thrower)?;
+    let skills = data.skills.get(thrower)?;
+    let mut weapon = data.weapons.get(item)?.clone();
+    weapon.hit_bonus += hit_bonus;
+    Some(weapon_profile(
+        thrower,
+        AttackSource::Thrown,
+        weapon,
+        Some(item),
+        attributes,
+        skills,
+        data,
+    ))
+}
+
+fn weapon_profile(
+    attacker: Entity,
+    source: AttackSource,
+    weapon: Weapon,
+    weapon_entity: Option<Entity>,
+    attributes: &Attributes,
+    skills: &Skills,
+    data: &CombatData,
+) -> AttackProfile {
+    let mut weapon = weapon;
+    if let Some(weapon_entity) = weapon_entity {
+        let condition = item_condition(weapon_entity, data.durabilities);
+        if condition == ItemCondition::Broken {
+            // A broken weapon is just a clumsy club: its enchantments and tricks are gone
+            weapon.hit_bonus = i32::min(weapon.hit_bonus, 0);
+            weapon.damage_bonus = i32::min(weapon.damage_bonus, 0);
+            weapon.proc_chance = None;
+            weapon.crit_effects.clear();
+        }
+        let (hit_penalty, damage_penalty) = condition.weapon_penalty();
+        weapon.hit_bonus -= hit_penalty;
+        weapon.damage_bonus -= damage_penalty;
+    }
+
+    let attribute_hit_bonus = if weapon.attribute == WeaponAttribute::Might {
+        attributes.might.bonus
+    } else {
+        attributes.quickness.bonus
+    };
+
+    // Well-fed grants +1
+    let status_hit_bonus = match data.hunger_clock.get(attacker) {
+        Some(hc) if hc.state == HungerState::WellFed => 1,
+        _ => 0,
+    };
+
+    AttackProfile {
+        source,
+        weapon,
+        weapon_entity,
+        ammo_entity: None,
+        ammo_proc_chance: None,
+        attribute_hit_bonus,
+        skill_hit_bonus: skill_bonus(Skill::Melee, skills),
+        status_hit_bonus,
+        attribute_damage_bonus: attributes.might.bonus,
+        skill_damage_bonus: skill_bonus(Skill::Melee, skills),
+        always_hits: false,
+    }
+}
+
+/// Nocks an arrow or bolt: its bonuses, damage type and procs ride along with the shot
+pub fn load_ammo(attack: &mut AttackProfile, ammo_entity: Entity, ammo: &Ammunition) {
+    attack.weapon.hit_bonus += ammo.hit_bonus;
+    attack.weapon.damage_bonus += ammo.damage_bonus;
+    if let Some(damage_type) = ammo.damage_type {
+        attack.weapon.damage_type = damage_type;
+    }
+    attack.ammo_entity = Some(ammo_entity);
+    attack.ammo_proc_chance = ammo.proc_chance;
+}
+
+/// Spells and magic items always find their mark, for exactly the damage they list
+pub fn spell_profile(damage: i32, damage_type: DamageType) -> AttackProfile {
+    let mut weapon = unarmed();
+    weapon.damage_n_dice = 0;
+    weapon.damage_bonus = damage;
+    weapon.damage_type = damage_type;
+    AttackProfile {
+        source: AttackSource::Spell,
+        weapon,
+        weapon_entity: None,
+        ammo_entity: None,
+        ammo_proc_chance: None,
+        attribute_hit_bonus: 0,
+        skill_hit_bonus: 0,
+        status_hit_bonus: 0,
+        attribute_damage_bonus: 0,
+        skill_damage_bonus: 0,
+        always_hits: true,
+    }
+}
+
+pub fn defense_profile(defender: Entity, data: &CombatData) -> Option<DefenseProfile> {
+    let attributes = data.attributes.get(defender)?;
+    let skills = data.skills.get(defender)?;
+
+    let mut armor_item_bonus = 0.0;
+    for (item, wielded, armor) in (data.entities, data.equipped, data.wearables).join() {
+        if wielded.owner == defender {
+            armor_item_bonus +=
+                armor.armor_class * item_condition(item, data.durabilities).armor_factor();
+        }
+    }
+
+    Some(DefenseProfile {
+        base_armor_class: data
+            .natural
+            .get(defender)
+            .and_then(|nat| nat.armor_class)
+            .unwrap_or(10),
+        quickness_bonus: attributes.quickness.bonus,
+        skill_bonus: skill_bonus(Skill::Defense, skills),
+        item_bonus: armor_item_bonus as i32,
+    })
+}
+
+/// Rolls an attack against a defense. A natural 1 always misses and a natural 20 always hits.
+pub fn resolve_attack(
+    attack: &AttackProfile,
+    defense: &DefenseProfile,
+    dice: &mut dyn DiceRoller,
+) -> AttackResult {
+    let armor_class = defense.armor_class();
+
+    let (outcome, natural_roll, hit_roll) = if attack.always_hits {
+        (HitOutcome::Hit, 0, 0)
+    } else {
+        let natural_roll = dice.roll_dice(1, 20);
+        let hit_roll = natural_roll
+            + attack.attribute_hit_bonus
+            + attack.skill_hit_bonus
+            + attack.weapon.hit_bonus
+            + attack.status_hit_bonus;
+        let outcome = if natural_roll == 1 {
+            HitOutcome::Fumble
+        } else if natural_roll == 20 {
+            HitOutcome::CriticalHit
+        } else if hit_roll > armor_class {
+            HitOutcome::Hit
+        } else {
+            HitOutcome::Miss
+        };
+        (outcome, natural_roll, hit_roll)
+    };
+
+    let mut result = AttackResult {
+        outcome,
+        natural_roll,
+        hit_roll,
+        armor_class,
+        damage: DamageBreakdown::default(),
+        proc_triggered: false,
+        ammo_proc_triggered: false,
+        fumble: None,
+    };
+    if !result.is_hit() {
+        // One fumble in three costs the attacker their grip on the weapon, or its edge
+        if outcome == HitOutcome::Fumble
+            && attack.weapon_entity.is_some()
+            && attack.source != AttackSource::Thrown
+        {
+            result.fumble = match dice.roll_dice(1, 6) {
+                1 => Some(FumbleEffect::DropWeapon),
+                2 => Some(FumbleEffect::DamageWeapon),
+                _ => None,
+            };
+        }
+        return result;
+    }
+
+    result.damage = DamageBreakdown {
+        dice: if attack.weapon.damage_n_dice > 0 {
+            dice.roll_dice(attack.weapon.damage_n_dice, attack.weapon.damage_die_type)
+        } else {
+            0
+        },
+        attribute: attack.attribute_damage_bonus,
+        skill: attack.skill_damage_bonus,
+        weapon: attack.weapon.damage_bonus,
+        critical: 0,
+    };
+
+    // Critical hits roll the weapon's damage dice a second time
+    if outcome == HitOutcome::CriticalHit && attack.weapon.damage_n_dice > 0 {
+        result.damage.critical =
+            dice.roll_dice(attack.weapon.damage_n_dice, attack.weapon.damage_die_type);
+    }
+
+    if let (Some(chance), Some(_)) = (attack.weapon.proc_chance, attack.weapon_entity) {
+        result.proc_triggered = dice.roll_dice(1, 100) <= (chance * 100.0) as i32;
+    }
+    if let (Some(chance), Some(_)) = (attack.ammo_proc_chance, attack.ammo_entity) {
+        result.ammo_proc_triggered = dice.roll_dice(1, 100) <= (chance * 100.0) as i32;
+    }
+
+    result
+}
+
+/// How much of a damage type an entity shrugs off, counting its own hide, anything it is
+/// wearing and any effects it is under. Clamped to [-100, 100].
+pub fn total_resistance(
+    target: Entity,
+    damage_type: DamageType,
+    entities: &Entities,
+    resistances: &ReadStorage<Resistances>,
+    equipped: &ReadStorage<Equipped>,
+    statuses: &ReadStorage<StatusEffect>,
+    durabilities: &ReadStorage<Durability>,
+) -> i32 {
+    let mut total = 0;
+    for (entity, resist) in (entities, resistances).join() {
+        let worn = equipped.get(entity).is_some_and(|e| e.owner == target)
+            && item_condition(entity, durabilities) != ItemCondition::Broken;
+        let applies =
+            entity == target || worn || statuses.get(entity).is_some_and(|s| s.target == target);
+        if applies {
+            total += resist.resistances.get(&damage_type).copied().unwrap_or(0);
+        }
+    }
+    total.clamp(-100, 100)
+}
+
+pub fn resist_damage(amount: i32, resistance: i32) -> i32 {
+    amount * (100 - resistance) / 100
+}
+
+/// Turns a resolved attack into damage, weapon procs, particles and a log entry
+#[allow(clippy::too_many_arguments)]
+pub fn apply_attack(
+    attacker: Entity,
+    attacker_name: &str,
+    target: Entity,
+    target_name: &str,
+    attack: &AttackProfile,
+    result: &AttackResult,
+    data: &CombatData,
+    dice: &mut dyn DiceRoller,
+) {
+    if result.is_hit() {
+        let damage = result.damage.total();
+        let damage_type = attack.weapon.damage_type;
+        add_effect(
+            Some(attacker),
+            EffectType::Damage {
+                amount: damage,
+                damage_type: Some(damage_type),
+            },
+            Targets::Single { target },
+        );
+
+        // The damage effect applies resistances itself; work them out here too for the log
+        let resistance = total_resistance(
+            target,
+            damage_type,
+            data.entities,
+            data.resistances,
+            data.equipped,
+            data.statuses,
+            data.durabilities,
+        );
+        if resistance >= 100 {
+            crate::gamelog::Logger::new()
+                .npc_name(attacker_name)
+                .color(rltk::WHITE)
+                .append("hits")
+                .npc_name(target_name)
+                .color(rltk::WHITE)
+                .append(format!(
+                    "but it is immune to {} damage.",
+                    damage_type.name()
+                ))
+                .log();
+        } else {
+            let mut logger = crate::gamelog::Logger::new()
+                .npc_name(attacker_name)
+                .color(rltk::WHITE)
+                .append("hits")
+                .npc_name(target_name)
+                .color(rltk::WHITE)
+                .append("for")
+                .damage(resist_damage(damage, resistance))
+                .color(rltk::WHITE)
+                .append(format!("{} damage.", damage_type.name()));
+            if resistance < 0 {
+                logger = logger.color(rltk::ORANGE).append("It's vulnerable!");
+            }
+            if result.outcome == HitOutcome::CriticalHit {
+                logger = logger.color(rltk::YELLOW).append("Critical hit!");
+            }
+            logger.log();
+        }
+
+        // Every blow takes a little out of the weapon, and out of whatever armour it landed on
+        if let Some(weapon_entity) = attack.weapon_entity {
+            wear_item(attacker, weapon_entity, data, dice);
+        }
+        wear_armor(target, data, dice);
+
+        if result.outcome == HitOutcome::CriticalHit {
+            apply_crit_effects(attacker, target, &attack.weapon.crit_effects);
+        }
+
+        // Proc effects
+        if let (true, Some(weapon_entity)) = (result.proc_triggered, attack.weapon_entity) {
+            let effect_target = if attack.weapon.proc_target.as_deref() == Some("Self") {
+                attacker
+            } else {
+                target
+            };
+            add_effect(
+                Some(attacker),
+                EffectType::ItemUse {
+                    item: weapon_entity,
+                },
+                Targets::Single {
+                    target: effect_target,
+                },
+            );
+        }
+        if let (true, Some(ammo_entity)) = (result.ammo_proc_triggered, attack.ammo_entity) {
+            add_effect(
+                Some(attacker),
+                EffectType::ItemUse { item: ammo_entity },
+                Targets::Single { target },
+            );
+        }
+    } else {
+        if result.outcome == HitOutcome::Fumble {
+            crate::gamelog::Logger::new()
+                .item_name(attacker_name)
+                .color(rltk::WHITE)
+                .append("considers attacking")
+                .item_name(target_name)
+                .color(rltk::WHITE)
+                .append("but misjudges the timing!")
+                .log();
+        } else {
+            crate::gamelog::Logger::new()
+                .item_name(attacker_name)
+                .color(rltk::WHITE)
+                .append("attacks")
+                .item_name(target_name)
+                .color(rltk::WHITE)
+                .append("but can't connect")
+                .log();
+            // Blocked blows are taken on the defender's armour
+            if !attack.always_hits {
+                wear_armor(target, data, dice);
+            }
+        }
+        if let (Some(fumble), Some(weapon_entity)) = (result.fumble, attack.weapon_entity) {
+            let effect_type = match fumble {
+                FumbleEffect::DropWeapon => EffectType::DropWeapon {
+                    item: weapon_entity,
+                },
+                FumbleEffect::DamageWeapon => EffectType::DamageWeapon {
+                    item: weapon_entity,
+                },
+            };
+            add_effect(
+                Some(attacker),
+                effect_type,
+                Targets::Single { target: attacker },
+            );
+        }
+        let fg = if result.outcome == HitOutcome::Fumble {
+            RGB::named(rltk::BLUE)
+        } else {
+            RGB::named(rltk::CYAN)
+        };
+        add_effect(
+            Some(attacker),
+            EffectType::Particle {
+                glyph: rltk::to_cp437('‼'),
+                fg,
+                bg: RGB::named(rltk::BLACK),
+                lifespan: 200.0,
+            },
+            Targets::Single { target },
+        );
+    }
+}
+
+/// Sometimes knocks a point of durability off an item that was just used
+fn wear_item(owner: Entity, item: Entity, data: &CombatData, dice: &mut dyn DiceRoller) {
+    if data.durabilities.get(item).is_some_and(|d| d.current > 0)
+        && dice.roll_dice(1, 100) <= WEAR_CHANCE
+    {
+        add_effect(
+            Some(owner),
+            EffectType::Wear { item, amount: 1 },
+            Targets::Single { target: owner },
+        );
+    }
+}
+
+/// Picks one piece of the defender's armour that can still wear down and maybe wears it
+fn wear_armor(defender: Entity, data: &CombatData, dice: &mut dyn DiceRoller) {
+    let armor: Vec<Entity> = (
+        data.entities,
+        data.equipped,
+        data.wearables,
+        data.durabilities,
+    )
+        .join()
+        .filter(|(_, equipped, _, durability)| equipped.owner == defender && durability.current > 0)
+        .map(|(item, _, _, _)| item)
+        .collect();
+    if let Some(index) = dice.pick_index(armor.len()) {
+        wear_item(defender, armor[index], data, dice);
+    }
+}
+
+fn apply_crit_effects(attacker: Entity, target: Entity, crit_effects: &[CritEffect]) {
+    for crit in crit_effects.iter() {
+        let effect_type = match *crit {
+            CritEffect::Bleed { damage } => EffectType::DamageOverTime {
+                damage,
+                damage_type: DamageType::Slashing,
+                turns: DEFAULT_DAMAGE_OVER_TIME_TURNS,
+            },
+            CritEffect::Stun { turns } => EffectType::Stun { turns },
+            CritEffect::Knockback { distance } => EffectType::Knockback { distance },
+        };
+        add_effect(Some(attacker), effect_type, Targets::Single { target });
+    }
+}
+
+fn maneuver_bonus(attributes: &Attributes, attribute: WeaponAttribute) -> i32 {
+    match attribute {
+        WeaponAttribute::Might => attributes.might.bonus,
+        WeaponAttribute::Quickness => attributes.quickness.bonus,
+    }
+}
+
+/// The attribute each side of a maneuver leans on: (attacker, defender)
+fn maneuver_attributes(maneuver: Maneuver) -> (WeaponAttribute, WeaponAttribute) {
+    match maneuver {
+        Maneuver::Shove => (WeaponAttribute::Might, WeaponAttribute::Might),
+        Maneuver::Trip => (WeaponAttribute::Quickness, WeaponAttribute::Quickness),
+        Maneuver::Disarm => (WeaponAttribute::Quickness, WeaponAttribute::Might),
+    }
+}
+
+/// The melee weapon an entity is wielding, if any
+pub fn wielded_weapon(owner: Entity, data: &CombatData) -> Option<Entity> {
+    (data.entities, data.equipped, data.weapons)
+        .join()
+        .find(|(_, equipped, _)| equipped.owner == owner && equipped.slot == EquipmentSlot::Melee)
+        .map(|(entity, _, _)| entity)
+}
+
+/// An opposed roll: d20 plus the attacker's attribute and melee skill against d20 plus the
+/// defender's attribute and defense skill. Ties go to the defender.
+pub fn resolve_maneuver(
+    maneuver: Maneuver,
+    attacker: Entity,
+    defender: Entity,
+    data: &CombatData,
+    dice: &mut dyn DiceRoller,
+) -> Option<bool> {
+    let (attack_attribute, defense_attribute) = maneuver_attributes(maneuver);
+    let attack_roll = dice.roll_dice(1, 20)
+        + maneuver_bonus(data.attributes.get(attacker)?, attack_attribute)
+        + skill_bonus(Skill::Melee, data.skills.get(attacker)?);
+    let defense_roll = dice.roll_dice(1, 20)
+        + maneuver_bonus(data.attributes.get(defender)?, defense_attribute)
+        + skill_bonus(Skill::Defense, data.skills.get(defender)?);
+    Some(attack_roll > defense_roll)
+}
+
+/// Logs a maneuver and queues up whatever it does to the target
+pub fn apply_maneuver(
+    attacker: Entity,
+    attacker_name: &str,
+    target: Entity,
+    target_name: &str,
+    maneuver: Maneuver,
+    success: bool,
+    data: &CombatData,
+) {
+    let verb = maneuver.name().to_lowercase();
+    if !success {
+        crate::gamelog::Logger::new()
+            .npc_name(attacker_name)
+            .color(rltk::WHITE)
+            .append(format!("tries to {}", verb))
+            .npc_name(target_name)
+            .color(rltk::WHITE)
+            .append("but fails.")
+            .log();
+        return;
+    }
+
+    let (effect_type, past_tense, outcome) = match maneuver {
+        Maneuver::Shove => (EffectType::Knockback { distance: 1 }, "shoves", "back!"),
+        Maneuver::Trip => (EffectType::Stun { turns: 1 }, "knocks", "off their feet!"),
+        Maneuver::Disarm => {
+            if let Some(item) = wielded_weapon(target, data) {
+                (
+                    EffectType::DropWeapon { item },
+                    "relieves",
+                    "of their weapon!",
+                )
+            } else {
+                crate::gamelog::Logger::new()
+                    .npc_name(target_name)
+                    .color(rltk::WHITE)
+                    .append("has nothing to disarm.")
+                    .log();
+                return;
+            }
+        }
+    };
+
+    crate::gamelog::Logger::new()
+        .npc_name(attacker_name)
+        .color(rltk::WHITE)
+        .append(past_tense)
+        .npc_name(target_name)
+        .color(rltk::WHITE)
+        .append(outcome)
+        .log();
+    add_effect(Some(attacker), effect_type, Targets::Single { target });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Hands back a fixed list of rolls, in order, and complains if combat asks for more
+    struct ScriptedDice {
+        rolls: VecDeque<i32>,
+    }
+
+    impl ScriptedDice {
+        fn new(rolls: &[i32]) -> Self {
+            ScriptedDice {
+                rolls: rolls.iter().copied().collect(),
+            }
+        }
+    }
+
+    impl DiceRoller for ScriptedDice {
+        fn roll_dice(&mut self, _n: i32, _die_type: i32) -> i32 {
+            self.rolls.pop_front().expect("ran out of scripted rolls")
+        }
+    }
+
+    fn melee_attack(hit_bonus: i32) -> AttackProfile {
+        AttackProfile {
+            source: AttackSource::Melee,
+            weapon: unarmed(),
+            weapon_entity: None,
+            ammo_entity: None,
+            ammo_proc_chance: None,
+            attribute_hit_bonus: hit_bonus,
+            skill_hit_bonus: 0,
+            status_hit_bonus: 0,
+            attribute_damage_bonus: 0,
+            skill_damage_bonus: 0,
+            always_hits: false,
+        }
+    }
+
+    fn defense(armor_class: i32) -> DefenseProfile {
+        DefenseProfile {
+            base_armor_class: armor_class,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn natural_one_always_misses() {
+        let result = resolve_attack(&melee_attack(50), &defense(1), &mut ScriptedDice::new(&[1]));
+        assert_eq!(result.outcome, HitOutcome::Fumble);
+        assert!(!result.is_hit());
+        assert_eq!(result.damage.total(), 0);
+    }
+
+    #[test]
+    fn natural_twenty_always_crits() {
+        let result = resolve_attack(
+            &melee_attack(0),
+            &defense(100),
+            &mut ScriptedDice::new(&[20, 3, 4]),
+        );
+        assert_eq!(result.outcome, HitOutcome::CriticalHit);
+        assert_eq!(result.damage.dice, 3);
+        assert_eq!(result.damage.critical, 4);
+        assert_eq!(result.damage.total(), 7);
+    }
+
+    #[test]
+    fn hit_roll_must_beat_armor_class() {
+        let attack = melee_attack(2);
+        let miss = resolve_attack(&attack, &defense(12), &mut ScriptedDice::new(&[10]));
+        assert_eq!(miss.outcome, HitOutcome::Miss);
+        assert_eq!(miss.hit_roll, 12);
+
+        let hit = resolve_attack(&attack, &defense(12), &mut ScriptedDice::new(&[11, 2]));
+        assert_eq!(hit.outcome, HitOutcome::Hit);
+        assert_eq!(hit.damage.critical, 0);
+    }
+
+    #[test]
+    fn damage_breakdown_and_resistances() {
+        let mut attack = melee_attack(0);
+        attack.attribute_damage_bonus = 2;
+        attack.skill_damage_bonus = 1;
+        attack.weapon.damage_bonus = 1;
+        let result = resolve_attack(&attack, &defense(10), &mut ScriptedDice::new(&[15, 4]));
+        assert_eq!(result.damage.dice, 4);
+        assert_eq!(result.damage.attribute, 2);
+        assert_eq!(result.damage.skill, 1);
+        assert_eq!(result.damage.weapon, 1);
+        assert_eq!(result.damage.total(), 8);
+
+        assert_eq!(resist_damage(8, 50), 4);
+        assert_eq!(resist_damage(8, -50), 12);
+        assert_eq!(resist_damage(8, 100), 0);
+
+        // Penalties can take a blow down to nothing, but never below it
+        attack.weapon.damage_bonus = -10;
+        let result = resolve_attack(&attack, &defense(10), &mut ScriptedDice::new(&[15, 4]));
+        assert_eq!(result.damage.total(), 0);
+    }
+
+    #[test]
+    fn spells_always_hit_for_their_listed_damage() {
+        let result = resolve_attack(
+            &spell_profile(6, DamageType::Fire),
+            &defense(100),
+            &mut ScriptedDice::new(&[]),
+        );
+        assert_eq!(result.outcome, HitOutcome::Hit);
+        assert_eq!(result.damage.total(), 6);
+    }
+
+    #[test]
+    fn weapon_procs_on_the_listed_chance() {
+        let mut world = World::new();
+        let mut attack = melee_attack(0);
+        attack.weapon_entity = Some(world.create_entity().build());
+        attack.weapon.proc_chance = Some(0.25);
+
+        let procs = resolve_attack(&attack, &defense(10), &mut ScriptedDice::new(&[15, 2, 25]));
+        assert!(procs.proc_triggered);
+        let no_proc = resolve_attack(&attack, &defense(10), &mut ScriptedDice::new(&[15, 2, 26]));
+        assert!(!no_proc.proc_triggered);
+
+        // A miss never gets as far as rolling for a proc
+        let miss = resolve_attack(&attack, &defense(10), &mut ScriptedDice::new(&[5]));
+        assert!(!miss.proc_triggered);
+    }
+
+    #[test]
+    fn fumbles_can_cost_the_weapon() {
+        let mut world = World::new();
+        let mut attack = melee_attack(0);
+        attack.weapon_entity = Some(world.create_entity().build());
+
+        let dropped = resolve_attack(&attack, &defense(10), &mut ScriptedDice::new(&[1, 1]));
+        assert_eq!(dropped.fumble, Some(FumbleEffect::DropWeapon));
+        let kept = resolve_attack(&attack, &defense(10), &mut ScriptedDice::new(&[1, 6]));
+        assert_eq!(kept.fumble, None);
+    }
+
+    #[test]
+    fn pick_index_follows_the_dice() {
+        assert_eq!(ScriptedDice::new(&[3]).pick_index(4), Some(2));
+        assert_eq!(ScriptedDice::new(&[]).pick_index(0), None);
+    }
+}
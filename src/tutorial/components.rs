@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::attr_bonus;
-use crate::{Map, MasterDungeonMap};
+use crate::{HazardKind, Map, MasterDungeonMap};
 use rltk::{Point, RGB};
 use serde::{Deserialize, Serialize};
 use specs::error::NoError;
@@ -56,6 +56,55 @@ pub struct Slow {
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct DamageOverTime {
     pub damage: i32,
+    pub damage_type: DamageType,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct LeavesHazard {
+    pub kind: HazardKind,
+    pub turns: i32,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Blink {}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct RandomTeleport {
+    pub range: i32,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct SwapPlaces {}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Knockback {
+    pub distance: i32,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Pull {
+    pub distance: i32,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct SummonsCreature {
+    pub mob: String,
+    pub count: i32,
+    pub duration: i32,
+    pub faction: String,
+}
+
+/// Turns whoever it hits into `form` for `turns`; the form "random" picks any beast
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Polymorphs {
+    pub form: String,
+    pub turns: i32,
+}
+
+/// Sets the named trap prop on the target tile
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct DeploysTrap {
+    pub prop: String,
 }
 
 #[derive(Component, Serialize, Deserialize, Clone)]
@@ -82,6 +131,34 @@ pub struct KnownSpells {
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct SpellTemplate {
     pub mana_cost: i32,
+    pub school: SpellSchool,
+    pub description: String,
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum SpellSchool {
+    Evocation,
+    Conjuration,
+    Enchantment,
+    Necromancy,
+}
+
+impl SpellSchool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpellSchool::Evocation => "Evocation",
+            SpellSchool::Conjuration => "Conjuration",
+            SpellSchool::Enchantment => "Enchantment",
+            SpellSchool::Necromancy => "Necromancy",
+        }
+    }
+}
+
+/// How far a caster has come in each school, and how many casts towards the next step
+#[derive(Component, Serialize, Deserialize, Clone, Default)]
+pub struct SchoolSkills {
+    pub levels: HashMap<SpellSchool, i32>,
+    pub practice: HashMap<SpellSchool, i32>,
 }
 
 #[derive(Component, ConvertSaveload, Clone)]
@@ -104,6 +181,12 @@ pub struct AttributeBonus {
     pub intelligence: Option<i32>,
 }
 
+/// An effect described in the raws, run whenever the entity is used, fired or triggered
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct EffectScript {
+    pub root: crate::effects::EffectNode,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct ProvidesIdentification {}
 
@@ -170,11 +253,76 @@ pub struct WantsToFlee {
     pub indices: Vec<usize>,
 }
 
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct WantsToSkirmish {
+    pub target: Entity,
+    pub range: i32,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Faction {
     pub name: String,
 }
 
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct UsesItems {}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Schedule {
+    pub work: (i32, i32),
+    pub home: (i32, i32),
+}
+
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum AllyCommand {
+    Follow,
+    Wait,
+    Attack,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Ally {
+    pub command: AllyCommand,
+}
+
+/// A creature conjured by an effect; it leaves nothing behind, and vanishes when `turns` run out
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Summoned {
+    pub turns: Option<i32>,
+}
+
+/// Everything a polymorph swaps out: how a creature looks, fights, and which side it is on
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CreatureForm {
+    pub glyph: rltk::FontCharType,
+    pub fg: RGB,
+    pub natural: Option<NaturalAttackDefense>,
+    pub attributes: Attributes,
+    pub hit_points: Pool,
+    pub mana: Pool,
+    pub faction: Option<String>,
+}
+
+/// On a polymorph status: the shape its target had before, put back when the status ends.
+/// `level` and `borrowed` are the level and attributes the borrowed shape started out with, so
+/// anything gained while transformed can be carried back to the true form.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct TrueForm {
+    pub form: CreatureForm,
+    pub level: i32,
+    pub borrowed: Attributes,
+}
+
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct AllyTarget {
+    pub target: Entity,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Recruiter {
+    pub mercenaries: Vec<String>,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct MyTurn {}
 
@@ -207,6 +355,7 @@ pub struct NaturalAttack {
     pub damage_n_dice: i32,
     pub damage_die_type: i32,
     pub damage_bonus: i32,
+    pub damage_type: DamageType,
     pub hit_bonus: i32,
 }
 
@@ -225,6 +374,62 @@ pub struct Pool {
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct EquipmentChanged {}
 
+/// How heavily loaded an entity is compared to what its Might lets it carry
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum BurdenLevel {
+    Unburdened,
+    Burdened,
+    Stressed,
+    Overloaded,
+}
+
+impl BurdenLevel {
+    pub fn from_weight(weight_lbs: f32, capacity_lbs: i32) -> BurdenLevel {
+        let capacity = capacity_lbs as f32;
+        if weight_lbs <= capacity {
+            BurdenLevel::Unburdened
+        } else if weight_lbs <= capacity * 1.5 {
+            BurdenLevel::Burdened
+        } else if weight_lbs <= BurdenLevel::hard_cap(capacity_lbs) {
+            BurdenLevel::Stressed
+        } else {
+            BurdenLevel::Overloaded
+        }
+    }
+
+    /// Nobody will pick up something that takes them past this
+    pub fn hard_cap(capacity_lbs: i32) -> f32 {
+        capacity_lbs as f32 * 2.0
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BurdenLevel::Unburdened => "Unburdened",
+            BurdenLevel::Burdened => "Burdened",
+            BurdenLevel::Stressed => "Stressed",
+            BurdenLevel::Overloaded => "Overloaded",
+        }
+    }
+
+    pub fn initiative_penalty(&self) -> f32 {
+        match self {
+            BurdenLevel::Unburdened => 0.0,
+            BurdenLevel::Burdened => 2.0,
+            BurdenLevel::Stressed => 4.0,
+            BurdenLevel::Overloaded => 8.0,
+        }
+    }
+
+    /// Turns taken off the hunger clock each turn
+    pub fn hunger_rate(&self) -> i32 {
+        match self {
+            BurdenLevel::Unburdened | BurdenLevel::Burdened => 1,
+            BurdenLevel::Stressed => 2,
+            BurdenLevel::Overloaded => 3,
+        }
+    }
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Pools {
     pub hit_points: Pool,
@@ -232,12 +437,14 @@ pub struct Pools {
     pub xp: i32,
     pub level: i32,
     pub total_weight: f32,
+    pub carry_capacity_lbs: i32,
+    pub burden: BurdenLevel,
     pub total_initiative_penalty: f32,
     pub gold: f32,
     pub god_mode: bool,
 }
 
-#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub enum MagicItemClass {
     Common,
     Rare,
@@ -276,6 +483,7 @@ pub enum Skill {
     Melee,
     Defense,
     Magic,
+    Perception,
 }
 
 #[derive(Component, Serialize, Deserialize, Clone)]
@@ -343,6 +551,12 @@ pub struct EntryTrigger;
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Hidden;
 
+/// Something that can be spotted and disarmed; both rolls are made against `difficulty`
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Trap {
+    pub difficulty: i32,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct MagicMapper;
 
@@ -357,6 +571,15 @@ pub enum HungerState {
     Starving,
 }
 
+/// The player is resting, or searching, a turn at a time until something interrupts
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Resting {
+    pub search: bool,
+    pub turns: i32,
+    pub hunger: HungerState,
+    pub hit_points: i32,
+}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct HungerClock {
     pub state: HungerState,
@@ -372,6 +595,7 @@ pub enum EquipmentSlot {
     Legs,
     Feet,
     Hands,
+    Ammo,
 }
 
 #[derive(Component, ConvertSaveload, Clone)]
@@ -385,6 +609,87 @@ pub struct WantsToRemoveItem {
     pub item: Entity,
 }
 
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum DamageType {
+    Slashing,
+    Piercing,
+    Blunt,
+    Fire,
+    Cold,
+    Poison,
+    Arcane,
+}
+
+impl DamageType {
+    pub const ALL: [DamageType; 7] = [
+        DamageType::Slashing,
+        DamageType::Piercing,
+        DamageType::Blunt,
+        DamageType::Fire,
+        DamageType::Cold,
+        DamageType::Poison,
+        DamageType::Arcane,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DamageType::Slashing => "slashing",
+            DamageType::Piercing => "piercing",
+            DamageType::Blunt => "blunt",
+            DamageType::Fire => "fire",
+            DamageType::Cold => "cold",
+            DamageType::Poison => "poison",
+            DamageType::Arcane => "arcane",
+        }
+    }
+}
+
+/// Percentage of each damage type shrugged off: 100 is immunity, negative is a vulnerability
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Resistances {
+    pub resistances: HashMap<DamageType, i32>,
+}
+
+/// Extra punishment a weapon deals when it lands a critical hit
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum CritEffect {
+    Bleed { damage: i32 },
+    Stun { turns: i32 },
+    Knockback { distance: i32 },
+}
+
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum AmmoType {
+    Arrow,
+    Bolt,
+}
+
+impl AmmoType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AmmoType::Arrow => "arrows",
+            AmmoType::Bolt => "bolts",
+        }
+    }
+}
+
+/// Arrows or bolts, used up one per shot. How many are in the bundle is its `Stackable` quantity.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Ammunition {
+    pub ammo_type: AmmoType,
+    pub hit_bonus: i32,
+    pub damage_bonus: i32,
+    pub damage_type: Option<DamageType>,
+    pub recovery_chance: i32,
+    pub proc_chance: Option<f32>,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Throwable {
+    pub range: i32,
+    pub hit_bonus: i32,
+}
+
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum WeaponAttribute {
     Might,
@@ -398,9 +703,12 @@ pub struct Weapon {
     pub damage_n_dice: i32,
     pub damage_die_type: i32,
     pub damage_bonus: i32,
+    pub damage_type: DamageType,
     pub hit_bonus: i32,
     pub proc_chance: Option<f32>,
     pub proc_target: Option<String>,
+    pub crit_effects: Vec<CritEffect>,
+    pub ammo: Option<AmmoType>,
 }
 
 #[derive(Component, ConvertSaveload, Clone)]
@@ -426,6 +734,70 @@ pub struct Equippable {
     pub slot: EquipmentSlot,
 }
 
+/// Weapons and armour wear down as they are used, and stop helping once broken
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Durability {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl Durability {
+    pub fn condition(&self) -> ItemCondition {
+        if self.current <= 0 {
+            ItemCondition::Broken
+        } else if self.current * 4 <= self.max {
+            ItemCondition::Damaged
+        } else if self.current * 2 <= self.max {
+            ItemCondition::Worn
+        } else {
+            ItemCondition::Good
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum ItemCondition {
+    Good,
+    Worn,
+    Damaged,
+    Broken,
+}
+
+impl ItemCondition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemCondition::Good => "Good",
+            ItemCondition::Worn => "Worn",
+            ItemCondition::Damaged => "Damaged",
+            ItemCondition::Broken => "Broken",
+        }
+    }
+
+    /// How much of an armour piece's protection is left
+    pub fn armor_factor(&self) -> f32 {
+        match self {
+            ItemCondition::Good => 1.0,
+            ItemCondition::Worn => 0.75,
+            ItemCondition::Damaged => 0.5,
+            ItemCondition::Broken => 0.0,
+        }
+    }
+
+    /// Hit and damage taken off a weapon in this state
+    pub fn weapon_penalty(&self) -> (i32, i32) {
+        match self {
+            ItemCondition::Good => (0, 0),
+            ItemCondition::Worn => (0, 1),
+            ItemCondition::Damaged => (1, 2),
+            ItemCondition::Broken => (2, 3),
+        }
+    }
+}
+
+/// Vendors with this will mend damaged equipment for a fee
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct RepairService {}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct DMSerializationHelper {
     pub map: MasterDungeonMap,
@@ -441,6 +813,9 @@ pub struct SerializationHelper {
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Confusion;
 
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Stunned {}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct Duration {
     pub turns: i32,
@@ -449,11 +824,151 @@ pub struct Duration {
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct StatusEffect {
     pub target: Entity,
+    pub kind: StatusKind,
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum StatusKind {
+    Attribute,
+    Poisoned,
+    Bleeding,
+    Burning,
+    Chilled,
+    Withering,
+    Stunned,
+    Confused,
+    Slowed,
+    Hasted,
+    Regenerating,
+    Invisible,
+    Polymorphed,
+}
+
+/// What happens when a status lands on someone who already has it
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum StackPolicy {
+    /// Keep the one status, topping up its duration
+    Refresh,
+    /// Keep the one status, topping up its duration and adding to its strength
+    Intensify,
+    /// Every application is its own status
+    Independent,
+}
+
+impl StatusKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Attribute => "Altered",
+            StatusKind::Poisoned => "Poisoned",
+            StatusKind::Bleeding => "Bleeding",
+            StatusKind::Burning => "Burning",
+            StatusKind::Chilled => "Chilled",
+            StatusKind::Withering => "Withering",
+            StatusKind::Stunned => "Stunned",
+            StatusKind::Confused => "Confused",
+            StatusKind::Slowed => "Slowed",
+            StatusKind::Hasted => "Hasted",
+            StatusKind::Regenerating => "Regenerating",
+            StatusKind::Invisible => "Invisible",
+            StatusKind::Polymorphed => "Polymorphed",
+        }
+    }
+
+    pub fn stacking(&self) -> StackPolicy {
+        match self {
+            StatusKind::Poisoned | StatusKind::Bleeding | StatusKind::Withering => {
+                StackPolicy::Intensify
+            }
+            _ => StackPolicy::Refresh,
+        }
+    }
+
+    pub fn glyph(&self) -> rltk::FontCharType {
+        rltk::to_cp437(match self {
+            StatusKind::Attribute => '±',
+            StatusKind::Poisoned => '♣',
+            StatusKind::Bleeding => '♥',
+            StatusKind::Burning => '▲',
+            StatusKind::Chilled => '*',
+            StatusKind::Withering => '†',
+            StatusKind::Stunned => '☼',
+            StatusKind::Confused => '?',
+            StatusKind::Slowed => '↓',
+            StatusKind::Hasted => '↑',
+            StatusKind::Regenerating => '+',
+            StatusKind::Invisible => '○',
+            StatusKind::Polymorphed => '§',
+        })
+    }
+
+    pub fn color(&self) -> RGB {
+        RGB::named(match self {
+            StatusKind::Attribute => rltk::WHEAT,
+            StatusKind::Poisoned => rltk::GREEN,
+            StatusKind::Bleeding => rltk::RED,
+            StatusKind::Burning => rltk::ORANGE,
+            StatusKind::Chilled => rltk::LIGHT_BLUE,
+            StatusKind::Withering => rltk::PURPLE,
+            StatusKind::Stunned => rltk::YELLOW,
+            StatusKind::Confused => rltk::CYAN,
+            StatusKind::Slowed => rltk::SLATE_GRAY,
+            StatusKind::Hasted => rltk::LIGHT_GREEN,
+            StatusKind::Regenerating => rltk::PINK,
+            StatusKind::Invisible => rltk::LIGHT_GRAY,
+            StatusKind::Polymorphed => rltk::GOLD,
+        })
+    }
+}
+
+/// Status kinds that simply don't take hold on this creature
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct StatusImmunities {
+    pub kinds: Vec<StatusKind>,
+}
+
+/// Scripted effects a status runs each turn it ticks, and once more when it wears off
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct StatusHooks {
+    pub on_tick: Option<crate::effects::EffectNode>,
+    pub on_expire: Option<crate::effects::EffectNode>,
 }
 
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct AreaOfEffect {
-    pub radius: i32,
+    pub shape: AoeShape,
+}
+
+/// The footprint of an area effect. Cones, lines and walls are aimed from the user
+/// towards the target; blasts ignore the target and go off around the user.
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum AoeShape {
+    Circle { radius: i32 },
+    Cone { length: i32, angle: i32 },
+    Line { length: i32 },
+    Wall { length: i32 },
+    Ring { radius: i32 },
+    Blast { radius: i32 },
+}
+
+impl AoeShape {
+    /// Shapes that land where a thrown projectile would, rather than spreading from the user
+    pub fn lands_on_target(&self) -> bool {
+        matches!(
+            self,
+            AoeShape::Circle { .. } | AoeShape::Wall { .. } | AoeShape::Ring { .. }
+        )
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            AoeShape::Circle { radius } => format!("radius {}", radius),
+            AoeShape::Cone { length, angle } => format!("cone {} ({} deg)", length, angle),
+            AoeShape::Line { length } => format!("line {}", length),
+            AoeShape::Wall { length } => format!("wall {}", length),
+            AoeShape::Ring { radius } => format!("ring {}", radius),
+            AoeShape::Blast { radius } => format!("blast {}", radius),
+        }
+    }
 }
 
 #[derive(Component, ConvertSaveload, Clone)]
@@ -464,6 +979,7 @@ pub struct Ranged {
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct InflictsDamage {
     pub damage: i32,
+    pub damage_type: DamageType,
 }
 
 #[derive(Component, Serialize, Deserialize, Clone)]
@@ -491,6 +1007,28 @@ impl Owned for InBackpack {
     }
 }
 
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Container {
+    pub locked: bool,
+}
+
+/// Springs on whoever opens the container it is attached to
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct ContainerTrap {
+    pub container: Entity,
+}
+
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct InContainer {
+    pub container: Entity,
+}
+
+/// Carrying a bag raises how much weight its owner can haul before being overburdened
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Bag {
+    pub capacity_lbs: i32,
+}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct WantsToPickupItem {
     pub collected_by: Entity,
@@ -510,6 +1048,12 @@ pub struct Item {
     pub base_value: f32,
 }
 
+/// Identical single-use items carried by one owner share a single entity
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct Stackable {
+    pub quantity: i32,
+}
+
 #[derive(Component, ConvertSaveload, Clone)]
 pub struct ProvidesHealing {
     pub heal_amount: i32,
@@ -520,6 +1064,37 @@ pub struct WantsToMelee {
     pub target: Entity,
 }
 
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct WantsToThrow {
+    pub item: Entity,
+    pub target: Entity,
+}
+
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
+pub enum Maneuver {
+    Shove,
+    Trip,
+    Disarm,
+}
+
+impl Maneuver {
+    pub const ALL: [Maneuver; 3] = [Maneuver::Shove, Maneuver::Trip, Maneuver::Disarm];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Maneuver::Shove => "Shove",
+            Maneuver::Trip => "Trip",
+            Maneuver::Disarm => "Disarm",
+        }
+    }
+}
+
+#[derive(Component, ConvertSaveload, Clone)]
+pub struct WantsToManeuver {
+    pub target: Entity,
+    pub maneuver: Maneuver,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct BlocksTile {}
 
@@ -2,11 +2,13 @@ use crate::{
     effects::*,
     raws::{get_item_drop, spawn_named_entity, SpawnType, RAWS},
     AreaOfEffect, Equipped, InBackpack, LootTable, Map, Name, OnDeath, Player, Pools, Position,
-    RunState,
+    RunState, Summoned,
 };
 use specs::prelude::*;
 
 pub fn delete_the_dead(ecs: &mut World) {
+    revert_fatal_forms(ecs);
+
     let mut dead: Vec<Entity> = Vec::new();
 
     {
@@ -46,9 +48,25 @@ pub fn delete_the_dead(ecs: &mut World) {
         let mut carried = ecs.write_storage::<InBackpack>();
         let mut positions = ecs.write_storage::<Position>();
         let loot_tables = ecs.read_storage::<LootTable>();
+        let summoned = ecs.read_storage::<Summoned>();
 
         for victim in dead.iter() {
-            let pos = positions.get(*victim);
+            // Whatever a summoned creature was conjured with goes with it
+            let pos = if summoned.get(*victim).is_some() {
+                for (entity, equipped) in (&entities, &equipped).join() {
+                    if equipped.owner == *victim {
+                        entities.delete(entity).expect("Delete failed");
+                    }
+                }
+                for (entity, backpack) in (&entities, &carried).join() {
+                    if backpack.owner == *victim {
+                        entities.delete(entity).expect("Delete failed");
+                    }
+                }
+                None
+            } else {
+                positions.get(*victim)
+            };
 
             for (entity, equipped) in (&entities, &equipped).join() {
                 if equipped.owner == *victim {
@@ -100,6 +118,9 @@ pub fn delete_the_dead(ecs: &mut World) {
         }
     }
 
+    // Death throes go off while the victim is still around, so anything they summon knows
+    // whose side it is on
+    let mut death_spells = Vec::new();
     for victim in dead.iter() {
         let death_effects = ecs.read_storage::<OnDeath>();
         if let Some(death_effect) = death_effects.get(*victim) {
@@ -114,27 +135,32 @@ pub fn delete_the_dead(ecs: &mut World) {
                             ecs.read_storage::<AreaOfEffect>().get(spell_entity)
                         {
                             Targets::Tiles {
-                                tiles: aoe_tiles(&map, rltk::Point::new(pos.x, pos.y), aoe.radius),
+                                tiles: shape_tiles(
+                                    &map,
+                                    rltk::Point::new(pos.x, pos.y),
+                                    rltk::Point::new(pos.x, pos.y),
+                                    aoe.shape,
+                                ),
                             }
                         } else {
                             Targets::Tile {
                                 tile_idx: tile_idx as i32,
                             }
                         };
-                        add_effect(
-                            None,
-                            EffectType::SpellUse {
-                                spell: spell_entity,
-                            },
-                            target,
-                        );
+                        death_spells.push((*victim, spell_entity, target));
                     }
                 }
             }
         }
     }
+    for (victim, spell, target) in death_spells {
+        spell_trigger(Some(victim), spell, &target, ecs);
+    }
 
     for victim in dead {
-        ecs.delete_entity(victim).expect("Unable to delete");
+        // A single-use death spell may already have taken its caster with it
+        if ecs.is_alive(victim) {
+            ecs.delete_entity(victim).expect("Unable to delete");
+        }
     }
 }
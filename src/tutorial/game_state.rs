@@ -5,6 +5,13 @@ use rltk::{GameState, Point, Rltk};
 pub enum VendorMode {
     Buy,
     Sell,
+    Repair,
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ContainerMode {
+    Take,
+    Put,
 }
 
 #[derive(PartialEq, Copy, Clone)]
@@ -44,6 +51,17 @@ pub enum RunState {
     ShowRemoveCurse,
     ShowIdentify,
     SummonItem,
+    ShowRecruiter {
+        recruiter: Entity,
+    },
+    ShowAllyCommands,
+    ShowManeuvers,
+    ShowSpellbook,
+    ShowThrowItem,
+    ShowContainer {
+        container: Entity,
+        mode: ContainerMode,
+    },
 }
 
 pub struct State {
@@ -184,9 +202,15 @@ impl GameState for State {
                 newrunstate = RunState::AwaitingInput;
             }
             RunState::AwaitingInput => {
-                newrunstate = player_input(self, ctx);
+                newrunstate = if player::is_resting(&self.ecs) {
+                    player::continue_rest(&mut self.ecs, ctx)
+                } else {
+                    player_input(self, ctx)
+                };
                 if newrunstate != RunState::AwaitingInput {
+                    crate::tutorial::noise::clear();
                     crate::gamelog::record_event("Turn", 1);
+                    self.ecs.fetch_mut::<MasterDungeonMap>().turn += 1;
                 }
             }
             RunState::Ticking => {
@@ -245,7 +269,7 @@ impl GameState for State {
                 }
             }
             RunState::ShowTargeting { range, item } => {
-                let result = gui::ranged_target(self, ctx, range);
+                let result = gui::ranged_target(self, ctx, range, item);
                 match result.0 {
                     gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
                     gui::ItemMenuResult::NoResponse => {}
@@ -283,7 +307,7 @@ impl GameState for State {
                     gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
                     gui::ItemMenuResult::NoResponse => {}
                     gui::ItemMenuResult::Selected => {
-                        let item_entity = result.1.unwrap();
+                        let item_entity = split_stack(&mut self.ecs, result.1.unwrap());
                         let mut intent = self.ecs.write_storage::<WantsToDropItem>();
                         intent
                             .insert(
@@ -319,10 +343,11 @@ impl GameState for State {
                     gui::VendorResult::Cancel => newrunstate = RunState::AwaitingInput,
                     gui::VendorResult::NoResponse => {}
                     gui::VendorResult::Sell => {
+                        let item_entity = split_stack(&mut self.ecs, result.1.unwrap());
                         let price = self
                             .ecs
                             .read_storage::<Item>()
-                            .get(result.1.unwrap())
+                            .get(item_entity)
                             .unwrap()
                             .base_value
                             * 0.8;
@@ -332,12 +357,20 @@ impl GameState for State {
                             .unwrap()
                             .gold += price;
                         self.ecs
-                            .delete_entity(result.1.unwrap())
+                            .delete_entity(item_entity)
                             .expect("Unable to delete");
                     }
                     gui::VendorResult::Buy => {
                         let tag = result.2.unwrap();
                         let price = result.3.unwrap();
+                        let weight = crate::raws::get_item_weight(&RAWS.lock().unwrap(), &tag);
+                        // Only worry about the weight of something the player can afford
+                        let can_afford = self
+                            .ecs
+                            .read_storage::<Pools>()
+                            .get(*self.ecs.fetch::<Entity>())
+                            .is_some_and(|pools| pools.gold >= price);
+                        let can_carry = can_afford && player::can_carry(&self.ecs, weight);
                         let mut pools = self.ecs.write_storage::<Pools>();
                         let player_entity = self.ecs.fetch::<Entity>();
                         let mut identified = self.ecs.write_storage::<IdentifiedItem>();
@@ -350,16 +383,18 @@ impl GameState for State {
                         let player_pools = pools.get_mut(*player_entity).unwrap();
                         std::mem::drop(player_entity);
 
-                        if player_pools.gold >= price {
+                        if can_carry {
                             player_pools.gold -= price;
                             std::mem::drop(pools);
                             let player_entity = *self.ecs.fetch::<Entity>();
-                            crate::raws::spawn_named_entity(
+                            if let Some(bought) = crate::raws::spawn_named_entity(
                                 &RAWS.lock().unwrap(),
                                 &mut self.ecs,
                                 &tag,
                                 SpawnType::Carried { by: player_entity },
-                            );
+                            ) {
+                                merge_into_stack(&mut self.ecs, bought);
+                            }
                         }
                     }
                     gui::VendorResult::BuyMode => {
@@ -374,6 +409,154 @@ impl GameState for State {
                             mode: VendorMode::Sell,
                         }
                     }
+                    gui::VendorResult::RepairMode => {
+                        newrunstate = RunState::ShowVendor {
+                            vendor,
+                            mode: VendorMode::Repair,
+                        }
+                    }
+                    gui::VendorResult::Repair => {
+                        let item_entity = result.1.unwrap();
+                        let price = result.3.unwrap();
+                        let player_entity = *self.ecs.fetch::<Entity>();
+                        let mut pools = self.ecs.write_storage::<Pools>();
+                        let player_pools = pools.get_mut(player_entity).unwrap();
+                        if player_pools.gold >= price {
+                            player_pools.gold -= price;
+                            std::mem::drop(pools);
+                            if let Some(durability) =
+                                self.ecs.write_storage::<Durability>().get_mut(item_entity)
+                            {
+                                durability.current = durability.max;
+                            }
+                            self.ecs
+                                .write_storage::<EquipmentChanged>()
+                                .insert(player_entity, EquipmentChanged {})
+                                .expect("Unable to insert");
+                            crate::gamelog::Logger::new()
+                                .append("Your")
+                                .item_name(gui::get_item_display_name(&self.ecs, item_entity))
+                                .append("is good as new.")
+                                .log();
+                        } else {
+                            crate::gamelog::Logger::new()
+                                .append("You can't afford that repair.")
+                                .log();
+                        }
+                    }
+                }
+            }
+            RunState::ShowRecruiter { recruiter } => {
+                let result = gui::show_recruit_menu(self, ctx, recruiter);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        let name = result.1.unwrap();
+                        let price = result.2.unwrap();
+                        let player_entity = *self.ecs.fetch::<Entity>();
+                        let gold = self
+                            .ecs
+                            .read_storage::<Pools>()
+                            .get(player_entity)
+                            .unwrap()
+                            .gold;
+
+                        if gold >= price {
+                            // Only pay once the recruit has actually turned up
+                            if spawner::hire_mercenary(&mut self.ecs, &name).is_some() {
+                                let mut pools = self.ecs.write_storage::<Pools>();
+                                pools.get_mut(player_entity).unwrap().gold -= price;
+                                crate::gamelog::Logger::new()
+                                    .npc_name(&name)
+                                    .append("joins you!")
+                                    .log();
+                            } else {
+                                crate::gamelog::Logger::new()
+                                    .append("There's no room here for the")
+                                    .npc_name(&name)
+                                    .append("to join you.")
+                                    .log();
+                            }
+                            newrunstate = RunState::AwaitingInput;
+                        } else {
+                            crate::gamelog::Logger::new()
+                                .append("You can't afford to hire the")
+                                .npc_name(&name)
+                                .log();
+                        }
+                    }
+                }
+            }
+            RunState::ShowAllyCommands => {
+                let command = match gui::show_ally_commands(self, ctx) {
+                    gui::AllyMenuResult::NoResponse => None,
+                    gui::AllyMenuResult::Cancel => {
+                        newrunstate = RunState::AwaitingInput;
+                        None
+                    }
+                    gui::AllyMenuResult::Follow => Some(AllyCommand::Follow),
+                    gui::AllyMenuResult::Wait => Some(AllyCommand::Wait),
+                    gui::AllyMenuResult::Attack => Some(AllyCommand::Attack),
+                };
+                if let Some(command) = command {
+                    newrunstate = player::command_allies(&mut self.ecs, command);
+                }
+            }
+            RunState::ShowManeuvers => {
+                let result = gui::show_maneuver_menu(self, ctx);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        let (target, maneuver) = result.1.unwrap();
+                        newrunstate = player::perform_maneuver(&mut self.ecs, target, maneuver);
+                    }
+                }
+            }
+            RunState::ShowSpellbook => {
+                let result = gui::show_spellbook(self, ctx);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        newrunstate = player::cast_known_spell(self, result.1.unwrap() as i32);
+                    }
+                }
+            }
+            RunState::ShowThrowItem => {
+                let result = gui::throw_item_menu(self, ctx);
+                match result.0 {
+                    gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => {}
+                    gui::ItemMenuResult::Selected => {
+                        newrunstate = player::throw_at_target(&mut self.ecs, result.1.unwrap());
+                    }
+                }
+            }
+            RunState::ShowContainer { container, mode } => {
+                let result = gui::show_container_menu(self, ctx, container, mode);
+                match result.0 {
+                    gui::ContainerResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::ContainerResult::NoResponse => {}
+                    gui::ContainerResult::Take => {
+                        player::take_from_container(&mut self.ecs, result.1.unwrap());
+                    }
+                    gui::ContainerResult::Put => {
+                        player::put_in_container(&mut self.ecs, container, result.1.unwrap());
+                    }
+                    gui::ContainerResult::TakeMode => {
+                        newrunstate = RunState::ShowContainer {
+                            container,
+                            mode: ContainerMode::Take,
+                        }
+                    }
+                    gui::ContainerResult::PutMode => {
+                        newrunstate = RunState::ShowContainer {
+                            container,
+                            mode: ContainerMode::Put,
+                        }
+                    }
                 }
             }
             RunState::GameOver => {
@@ -422,6 +605,9 @@ impl GameState for State {
                 let mut ppos = self.ecs.fetch_mut::<Point>();
                 ppos.x = x;
                 ppos.y = y;
+                std::mem::drop(ppos);
+                std::mem::drop(player_entity);
+                map::bring_allies_along(&mut self.ecs);
                 self.mapgen_next_state = Some(RunState::PreRun);
                 newrunstate = RunState::MapGeneration;
             }
@@ -498,6 +684,7 @@ impl State {
         // Build a new map and place the player
         let current_depth = self.ecs.fetch::<Map>().depth;
         self.generate_world_map(current_depth + offset, offset);
+        map::bring_allies_along(&mut self.ecs);
 
         // Notify the player
         crate::gamelog::Logger::new()
@@ -546,6 +733,7 @@ impl State {
             self.mapgen_history = history;
         } else {
             map::thaw_level_entities(&mut self.ecs);
+            map::simulate_frozen_level(&mut self.ecs);
         }
 
         gamelog::clear_log();
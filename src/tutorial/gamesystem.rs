@@ -1,4 +1,4 @@
-use crate::{Skill, Skills};
+use crate::{Attributes, DiceRoller, Skill, Skills};
 
 pub fn attr_bonus(value: i32) -> i32 {
     // see https://roll20.net/compendium/dnd5e/Ability%20Scores#content
@@ -26,6 +26,54 @@ pub fn mana_at_level(intelligence: i32, level: i32) -> i32 {
     mana_per_level(intelligence) * level
 }
 
+/// Turns between each hit point a creature heals on its own. Tougher, more seasoned
+/// creatures recover faster.
+pub fn hp_regen_interval(fitness: i32, level: i32) -> i32 {
+    i32::max(2, 20 - 2 * attr_bonus(fitness) - level / 2)
+}
+
+/// Turns between each point of mana a creature recovers on its own
+pub fn mana_regen_interval(intelligence: i32, level: i32) -> i32 {
+    i32::max(2, 16 - 2 * attr_bonus(intelligence) - level / 2)
+}
+
+// How hard a hidden thing is to find when nothing says otherwise
+pub const DEFAULT_TRAP_DIFFICULTY: i32 = 15;
+// Noticing something in passing is this much harder than searching for it
+const PASSIVE_SPOT_PENALTY: i32 = 4;
+
+/// Roll to notice something hidden: d20 + Intelligence bonus + Perception against its difficulty.
+/// A natural 20 always finds it.
+pub fn spots_hidden(
+    attributes: Option<&Attributes>,
+    skills: Option<&Skills>,
+    difficulty: i32,
+    searching: bool,
+    dice: &mut dyn DiceRoller,
+) -> bool {
+    let intelligence = attributes.map_or(0, |attr| attr.intelligence.bonus);
+    let perception = skills.map_or(0, |skills| skill_bonus(Skill::Perception, skills));
+    let difficulty = if searching {
+        difficulty
+    } else {
+        difficulty + PASSIVE_SPOT_PENALTY
+    };
+    let roll = dice.roll_dice(1, 20);
+    roll == 20 || roll + intelligence + perception >= difficulty
+}
+
+/// Roll to disarm a trap: d20 + Quickness bonus + Perception. Returns how far the roll beat
+/// (or missed) the trap's difficulty.
+pub fn disarm_margin(
+    attributes: Option<&Attributes>,
+    skills: Option<&Skills>,
+    difficulty: i32,
+) -> i32 {
+    let quickness = attributes.map_or(0, |attr| attr.quickness.bonus);
+    let perception = skills.map_or(0, |skills| skill_bonus(Skill::Perception, skills));
+    crate::tutorial::rng::roll_dice(1, 20) + quickness + perception - difficulty
+}
+
 pub fn skill_bonus(skill: Skill, skills: &Skills) -> i32 {
     if skills.skills.contains_key(&skill) {
         skills.skills[&skill]
@@ -33,3 +81,111 @@ pub fn skill_bonus(skill: Skill, skills: &Skills) -> i32 {
         -4
     }
 }
+
+/// Percent chance a spell fizzles. Costlier spells are harder, practice and a sharp mind
+/// help, and heavy armour gets in the way of the gestures.
+pub fn spell_failure_chance(
+    mana_cost: i32,
+    magic_skill: i32,
+    school_skill: i32,
+    intelligence_bonus: i32,
+    armor_weight: f32,
+) -> i32 {
+    let chance = 10 + mana_cost * 5 + (armor_weight / 5.0) as i32
+        - 5 * magic_skill
+        - 5 * school_skill
+        - 5 * intelligence_bonus;
+    chance.clamp(0, 95)
+}
+
+/// Successful casts needed to advance a school skill from the given level
+pub fn school_practice_needed(level: i32) -> i32 {
+    5 * (level + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedDice(i32);
+
+    impl DiceRoller for FixedDice {
+        fn roll_dice(&mut self, _n: i32, _die_type: i32) -> i32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn searching_meets_the_difficulty() {
+        let skills = Skills {
+            skills: [(Skill::Perception, 2)].into_iter().collect(),
+        };
+        assert!(spots_hidden(
+            None,
+            Some(&skills),
+            15,
+            true,
+            &mut FixedDice(13)
+        ));
+        assert!(!spots_hidden(
+            None,
+            Some(&skills),
+            15,
+            true,
+            &mut FixedDice(12)
+        ));
+    }
+
+    #[test]
+    fn passing_glance_is_harder_than_searching() {
+        let skills = Skills {
+            skills: [(Skill::Perception, 2)].into_iter().collect(),
+        };
+        assert!(!spots_hidden(
+            None,
+            Some(&skills),
+            15,
+            false,
+            &mut FixedDice(13)
+        ));
+        assert!(spots_hidden(
+            None,
+            Some(&skills),
+            15,
+            false,
+            &mut FixedDice(17)
+        ));
+    }
+
+    #[test]
+    fn natural_twenty_always_spots() {
+        assert!(spots_hidden(None, None, 40, false, &mut FixedDice(20)));
+        assert!(!spots_hidden(None, None, 40, false, &mut FixedDice(19)));
+    }
+
+    #[test]
+    fn spell_failure_rises_with_cost_and_armour() {
+        assert_eq!(spell_failure_chance(1, 0, 0, 0, 0.0), 15);
+        assert_eq!(spell_failure_chance(4, 0, 0, 0, 0.0), 30);
+        assert_eq!(spell_failure_chance(1, 0, 0, 0, 50.0), 25);
+    }
+
+    #[test]
+    fn spell_failure_falls_with_skill_and_wits() {
+        assert_eq!(spell_failure_chance(2, 1, 0, 0, 0.0), 15);
+        assert_eq!(spell_failure_chance(2, 0, 1, 1, 0.0), 10);
+    }
+
+    #[test]
+    fn spell_failure_is_clamped() {
+        assert_eq!(spell_failure_chance(1, 5, 5, 5, 0.0), 0);
+        assert_eq!(spell_failure_chance(30, 0, 0, 0, 200.0), 95);
+    }
+
+    #[test]
+    fn school_practice_grows_with_level() {
+        assert_eq!(school_practice_needed(0), 5);
+        assert_eq!(school_practice_needed(1), 10);
+        assert_eq!(school_practice_needed(4), 25);
+    }
+}
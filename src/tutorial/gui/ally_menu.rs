@@ -0,0 +1,50 @@
+use rltk::prelude::*;
+
+use super::{menu_box, menu_option};
+use crate::State;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum AllyMenuResult {
+    NoResponse,
+    Cancel,
+    Follow,
+    Wait,
+    Attack,
+}
+
+pub fn show_ally_commands(_gs: &mut State, ctx: &mut Rltk) -> AllyMenuResult {
+    let black = RGB::named(BLACK);
+    let yellow = RGB::named(YELLOW);
+    let count = 3;
+
+    let mut draw_batch = DrawBatch::new();
+    let mut y = 25 - (count / 2);
+    menu_box(&mut draw_batch, 15, y, count + 3, "Command Allies");
+
+    draw_batch.print_color(
+        Point::new(18, y + count + 1),
+        "ESCAPE to cancel",
+        ColorPair::new(yellow, black),
+    );
+
+    menu_option(&mut draw_batch, 17, y, to_cp437('F'), "Follow me");
+
+    y += 1;
+    menu_option(&mut draw_batch, 17, y, to_cp437('W'), "Wait here");
+
+    y += 1;
+    menu_option(&mut draw_batch, 17, y, to_cp437('A'), "Attack my target");
+
+    draw_batch.submit(6000).expect("Unable to draw ally menu");
+
+    match ctx.key {
+        None => AllyMenuResult::NoResponse,
+        Some(key) => match key {
+            VirtualKeyCode::F => AllyMenuResult::Follow,
+            VirtualKeyCode::W => AllyMenuResult::Wait,
+            VirtualKeyCode::A => AllyMenuResult::Attack,
+            VirtualKeyCode::Escape => AllyMenuResult::Cancel,
+            _ => AllyMenuResult::NoResponse,
+        },
+    }
+}
@@ -0,0 +1,72 @@
+use super::{get_item_display_name, item_result_menu, ItemMenuResult};
+use crate::{ContainerMode, InBackpack, InContainer, Name, State};
+use rltk::prelude::*;
+use specs::prelude::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ContainerResult {
+    NoResponse,
+    Cancel,
+    Take,
+    Put,
+    TakeMode,
+    PutMode,
+}
+
+pub fn show_container_menu(
+    gs: &mut State,
+    ctx: &mut Rltk,
+    container: Entity,
+    mode: ContainerMode,
+) -> (ContainerResult, Option<Entity>) {
+    let mut draw_batch = DrawBatch::new();
+    let player_entity = gs.ecs.fetch::<Entity>();
+    let backpack = gs.ecs.read_storage::<InBackpack>();
+    let contents = gs.ecs.read_storage::<InContainer>();
+    let entities = gs.ecs.entities();
+    let name = gs
+        .ecs
+        .read_storage::<Name>()
+        .get(container)
+        .map_or("Container".to_string(), |n| n.name.clone());
+
+    let mut items = Vec::new();
+    let title = match mode {
+        ContainerMode::Take => {
+            (&entities, &contents)
+                .join()
+                .filter(|item| item.1.container == container)
+                .for_each(|item| items.push((item.0, get_item_display_name(&gs.ecs, item.0))));
+            format!("{} (SPACE to put)", name)
+        }
+        ContainerMode::Put => {
+            (&entities, &backpack)
+                .join()
+                .filter(|item| item.1.owner == *player_entity)
+                .for_each(|item| items.push((item.0, get_item_display_name(&gs.ecs, item.0))));
+            format!("Put in {} (SPACE to take)", name)
+        }
+    };
+
+    let result = item_result_menu(&mut draw_batch, title, &items, ctx.key);
+
+    draw_batch
+        .submit(6000)
+        .expect("Unable to draw Container menu");
+
+    if ctx.key == Some(VirtualKeyCode::Space) {
+        return match mode {
+            ContainerMode::Take => (ContainerResult::PutMode, None),
+            ContainerMode::Put => (ContainerResult::TakeMode, None),
+        };
+    }
+
+    match result.0 {
+        ItemMenuResult::Cancel => (ContainerResult::Cancel, None),
+        ItemMenuResult::NoResponse => (ContainerResult::NoResponse, None),
+        ItemMenuResult::Selected => match mode {
+            ContainerMode::Take => (ContainerResult::Take, result.1),
+            ContainerMode::Put => (ContainerResult::Put, result.1),
+        },
+    }
+}
@@ -3,8 +3,8 @@ use specs::prelude::*;
 
 use crate::{
     draw_tooltips, gamelog, get_item_color, get_item_display_name, Attribute, Attributes,
-    Consumable, Duration, Entity, Equipped, HungerClock, HungerState, InBackpack, KnownSpells, Map,
-    Name, Pools, StatusEffect, Weapon,
+    BurdenLevel, Consumable, Duration, Entity, Equipped, HungerClock, HungerState, InBackpack,
+    KnownSpells, Map, MasterDungeonMap, Name, Pools, StatusEffect, Weapon,
 };
 
 fn draw_attribute(name: &str, attribute: &Attribute, y: i32, draw_batch: &mut DrawBatch) {
@@ -121,6 +121,31 @@ fn map_label(ecs: &World, draw_batch: &mut DrawBatch) {
     );
 }
 
+fn clock_label(ecs: &World, draw_batch: &mut DrawBatch) {
+    let box_grey: RGB = RGB::from_hex("#999999").expect("oops");
+    let black: RGB = RGB::named(BLACK);
+
+    let dm = ecs.fetch::<MasterDungeonMap>();
+    let clock = format!("Day {} {:02}:00", dm.day(), dm.hour());
+    let color = if dm.is_night() {
+        RGB::named(SLATEBLUE)
+    } else {
+        RGB::named(YELLOW)
+    };
+    let x_pos = 47 - clock.len() as i32;
+    draw_batch.set(
+        Point::new(x_pos - 1, 0),
+        ColorPair::new(box_grey, black),
+        to_cp437('┤'),
+    );
+    draw_batch.set(
+        Point::new(x_pos + clock.len() as i32, 0),
+        ColorPair::new(box_grey, black),
+        to_cp437('├'),
+    );
+    draw_batch.print_color(Point::new(x_pos, 0), &clock, ColorPair::new(color, black));
+}
+
 fn draw_stats(ecs: &World, draw_batch: &mut DrawBatch, player_entity: &Entity) {
     let black: RGB = RGB::named(BLACK);
     let white: RGB = RGB::named(WHITE);
@@ -180,20 +205,34 @@ fn initiative_weight(ecs: &World, draw_batch: &mut DrawBatch, player_entity: &En
     let white: RGB = RGB::named(WHITE);
     let gold: RGB = RGB::named(GOLD);
 
-    let attributes = ecs.read_storage::<Attributes>();
-    let attr = attributes.get(*player_entity).unwrap();
     let pools = ecs.read_storage::<Pools>();
     let player_pools = pools.get(*player_entity).unwrap();
 
+    let burden_color = match player_pools.burden {
+        BurdenLevel::Unburdened => RGB::named(GREEN),
+        BurdenLevel::Burdened => RGB::named(YELLOW),
+        BurdenLevel::Stressed => RGB::named(ORANGE),
+        BurdenLevel::Overloaded => RGB::named(RED),
+    };
     draw_batch.print_color(
         Point::new(50, 9),
         &format!(
-            "{:.0} lbs ({} lbs max)",
-            player_pools.total_weight,
-            (attr.might.base + attr.might.modifiers) * 15
+            "{:.0}/{} lbs",
+            player_pools.total_weight, player_pools.carry_capacity_lbs
         ),
         ColorPair::new(white, black),
     );
+    // The bar fills up to the hard cap, past which nothing more can be picked up
+    draw_batch.bar_horizontal(
+        Point::new(64, 9),
+        14,
+        player_pools.total_weight as i32,
+        i32::max(
+            1,
+            BurdenLevel::hard_cap(player_pools.carry_capacity_lbs) as i32,
+        ),
+        ColorPair::new(burden_color, black),
+    );
     draw_batch.print_color(
         Point::new(50, 10),
         &format!(
@@ -208,6 +247,13 @@ fn initiative_weight(ecs: &World, draw_batch: &mut DrawBatch, player_entity: &En
         &format!("Gold: {:.1}", player_pools.gold),
         ColorPair::new(gold, black),
     );
+    if player_pools.burden != BurdenLevel::Unburdened {
+        draw_batch.print_color(
+            Point::new(64, 11),
+            player_pools.burden.name(),
+            ColorPair::new(burden_color, black),
+        );
+    }
 }
 
 fn equipped(ecs: &World, draw_batch: &mut DrawBatch, player_entity: &Entity) -> i32 {
@@ -342,10 +388,12 @@ fn status(ecs: &World, draw_batch: &mut DrawBatch, player_entity: &Entity) {
     let names = ecs.read_storage::<Name>();
     for (status, duration, name) in (&statuses, &durations, &names).join() {
         if status.target == *player_entity {
+            let color = ColorPair::new(status.kind.color(), black);
+            draw_batch.set(Point::new(50, y), color, status.kind.glyph());
             draw_batch.print_color(
-                Point::new(50, y),
+                Point::new(52, y),
                 &format!("{} ({})", name.name, duration.turns),
-                ColorPair::new(red, black),
+                color,
             );
             y -= 1;
         }
@@ -358,6 +406,7 @@ pub fn draw_ui(ecs: &World, ctx: &mut Rltk) {
 
     box_framework(&mut draw_batch);
     map_label(ecs, &mut draw_batch);
+    clock_label(ecs, &mut draw_batch);
     draw_stats(ecs, &mut draw_batch, &player_entity);
     draw_attributes(ecs, &mut draw_batch, &player_entity);
     initiative_weight(ecs, &mut draw_batch, &player_entity);
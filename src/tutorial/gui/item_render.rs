@@ -1,9 +1,27 @@
 use super::*;
 use rltk::prelude::*;
 
-use crate::{Consumable, CursedItem, MagicItem, MagicItemClass, Name, ObfuscatedName};
+use crate::{
+    Consumable, CursedItem, Durability, ItemCondition, MagicItem, MagicItemClass, Name,
+    ObfuscatedName, Stackable,
+};
 
 pub fn get_item_display_name(ecs: &World, item: Entity) -> String {
+    let mut name = get_single_item_name(ecs, item);
+    if let Some(durability) = ecs.read_storage::<Durability>().get(item) {
+        let condition = durability.condition();
+        if condition != ItemCondition::Good {
+            name = format!("{} [{}]", name, condition.name());
+        }
+    }
+    match ecs.read_storage::<Stackable>().get(item) {
+        Some(stack) if stack.quantity > 1 => format!("{}x {}", stack.quantity, name),
+        _ => name,
+    }
+}
+
+/// The item's name as the player knows it, without stack size or condition
+pub fn get_single_item_name(ecs: &World, item: Entity) -> String {
     if let Some(name) = ecs.read_storage::<Name>().get(item) {
         if ecs.read_storage::<MagicItem>().get(item).is_some() {
             let dm = ecs.fetch::<crate::map::MasterDungeonMap>();
@@ -0,0 +1,61 @@
+use super::*;
+use rltk::prelude::*;
+
+use crate::{Maneuver, State};
+
+pub fn show_maneuver_menu(
+    gs: &mut State,
+    ctx: &mut Rltk,
+) -> (ItemMenuResult, Option<(Entity, Maneuver)>) {
+    let mut draw_batch = DrawBatch::new();
+
+    // Every maneuver against every adjacent creature, up to one per letter
+    let options: Vec<(Entity, Maneuver, String)> = crate::player::maneuver_targets(&gs.ecs)
+        .iter()
+        .flat_map(|target| {
+            let name = get_item_display_name(&gs.ecs, *target);
+            Maneuver::ALL
+                .iter()
+                .map(move |maneuver| (*target, *maneuver, format!("{} {}", maneuver.name(), name)))
+        })
+        .take(26)
+        .collect();
+    let count = options.len();
+
+    let y = (25 - (count / 2)) as i32;
+    menu_box(&mut draw_batch, 15, y, (count + 3) as i32, "Maneuver");
+    draw_batch.print_color(
+        Point::new(18, y + count as i32 + 1),
+        "ESCAPE to cancel",
+        ColorPair::new(RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK)),
+    );
+
+    for (j, option) in options.iter().enumerate() {
+        menu_option(
+            &mut draw_batch,
+            17,
+            y + j as i32,
+            97 + j as rltk::FontCharType,
+            &option.2,
+        );
+    }
+
+    draw_batch
+        .submit(6000)
+        .expect("Unable to draw Maneuver menu");
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None),
+        Some(key) => match key {
+            VirtualKeyCode::Escape => (ItemMenuResult::Cancel, None),
+            _ => {
+                let selection = rltk::letter_to_option(key);
+                if selection > -1 && selection < count as i32 {
+                    let (target, maneuver, _) = &options[selection as usize];
+                    return (ItemMenuResult::Selected, Some((*target, *maneuver)));
+                }
+                (ItemMenuResult::NoResponse, None)
+            }
+        },
+    }
+}
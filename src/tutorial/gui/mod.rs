@@ -40,6 +40,24 @@ pub use vendor_menu::*;
 mod menus;
 pub use menus::*;
 
+mod ally_menu;
+pub use ally_menu::*;
+
+mod recruit_menu;
+pub use recruit_menu::*;
+
+mod maneuver_menu;
+pub use maneuver_menu::*;
+
+mod throw_item_menu;
+pub use throw_item_menu::*;
+
+mod container_menu;
+pub use container_menu::*;
+
+mod spellbook_menu;
+pub use spellbook_menu::*;
+
 use specs::prelude::*;
 
 #[derive(PartialEq, Copy, Clone)]
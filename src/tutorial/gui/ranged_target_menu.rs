@@ -2,12 +2,13 @@ use super::ItemMenuResult;
 use rltk::prelude::*;
 use specs::prelude::*;
 
-use crate::{map::camera, State, Viewshed};
+use crate::{map::camera, AreaOfEffect, Map, State, Viewshed};
 
 pub fn ranged_target(
     gs: &mut State,
     ctx: &mut Rltk,
     range: i32,
+    item: Entity,
 ) -> (ItemMenuResult, Option<Point>) {
     let (min_x, max_x, min_y, max_y) = camera::get_screen_bounds(&gs.ecs, ctx);
     let player_entity = gs.ecs.fetch::<Entity>();
@@ -57,6 +58,22 @@ pub fn ranged_target(
     }
 
     if valid_target {
+        // Show what the effect would cover if it went off here
+        if let Some(aoe) = gs.ecs.read_storage::<AreaOfEffect>().get(item) {
+            let map = gs.ecs.fetch::<Map>();
+            let target = Point::new(mouse_map_pos.0, mouse_map_pos.1);
+            for idx in crate::effects::shape_tiles(&map, *player_pos, target, aoe.shape) {
+                let screen_x = idx % map.width - min_x;
+                let screen_y = idx / map.width - min_y;
+                if screen_x > 0
+                    && screen_x < (max_x - min_x) - 1
+                    && screen_y > 0
+                    && screen_y < (max_y - min_y) - 1
+                {
+                    draw_batch.set_bg(Point::new(screen_x, screen_y), RGB::named(ORANGE));
+                }
+            }
+        }
         draw_batch.set_bg(Point::new(mouse_pos.0, mouse_pos.1), RGB::named(CYAN));
         if ctx.left_click {
             return (
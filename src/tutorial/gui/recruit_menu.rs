@@ -0,0 +1,63 @@
+use super::*;
+use rltk::prelude::*;
+
+use crate::{Entity, Recruiter, State};
+
+pub fn show_recruit_menu(
+    gs: &mut State,
+    ctx: &mut Rltk,
+    recruiter: Entity,
+) -> (ItemMenuResult, Option<String>, Option<f32>) {
+    use crate::raws::*;
+    let mut draw_batch = DrawBatch::new();
+
+    let recruiters = gs.ecs.read_storage::<Recruiter>();
+
+    let mercenaries = get_mercenaries(
+        &recruiters.get(recruiter).unwrap().mercenaries,
+        &RAWS.lock().unwrap(),
+    );
+    let count = mercenaries.len();
+
+    let y = (25 - (count / 2)) as i32;
+    menu_box(&mut draw_batch, 15, y, (count + 3) as i32, "Hire Whom?");
+    draw_batch.print_color(
+        Point::new(18, y + count as i32 + 1),
+        "ESCAPE to cancel",
+        ColorPair::new(RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK)),
+    );
+
+    for (j, hire) in mercenaries.iter().enumerate() {
+        let row = y + j as i32;
+        menu_option(
+            &mut draw_batch,
+            17,
+            row,
+            97 + j as rltk::FontCharType,
+            &hire.0,
+        );
+        draw_batch.print(Point::new(50, row), format!("{:.1} gp", hire.1));
+    }
+
+    draw_batch
+        .submit(6000)
+        .expect("Unable to draw Recruit menu");
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None, None),
+        Some(key) => match key {
+            VirtualKeyCode::Escape => (ItemMenuResult::Cancel, None, None),
+            _ => {
+                let selection = rltk::letter_to_option(key);
+                if selection > -1 && selection < count as i32 {
+                    return (
+                        ItemMenuResult::Selected,
+                        Some(mercenaries[selection as usize].0.clone()),
+                        Some(mercenaries[selection as usize].1),
+                    );
+                }
+                (ItemMenuResult::NoResponse, None, None)
+            }
+        },
+    }
+}
@@ -0,0 +1,112 @@
+use super::*;
+use rltk::prelude::*;
+
+use crate::{
+    raws::find_spell_entity, AreaOfEffect, KnownSpells, Ranged, SchoolSkills, SpellTemplate, State,
+};
+
+pub fn show_spellbook(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option<usize>) {
+    let mut draw_batch = DrawBatch::new();
+    let white = RGB::named(rltk::WHITE);
+    let black = RGB::named(rltk::BLACK);
+    let yellow = RGB::named(rltk::YELLOW);
+    let cyan = RGB::named(rltk::CYAN);
+    let gray = RGB::named(rltk::GRAY);
+
+    let player_entity = *gs.ecs.fetch::<Entity>();
+    let known_spells = gs
+        .ecs
+        .read_storage::<KnownSpells>()
+        .get(player_entity)
+        .map_or(Vec::new(), |known| known.spells.clone());
+    let templates = gs.ecs.read_storage::<SpellTemplate>();
+    let ranged = gs.ecs.read_storage::<Ranged>();
+    let aoe = gs.ecs.read_storage::<AreaOfEffect>();
+    let schools = gs.ecs.read_storage::<SchoolSkills>();
+    let count = known_spells.len().min(26);
+
+    // Two lines per spell: the numbers, then what it does
+    let height = (count * 2) as i32;
+    let mut y = 25 - height / 2;
+    draw_batch.draw_box(
+        Rect::with_size(8, y - 2, 63, height + 3),
+        ColorPair::new(white, black),
+    );
+    draw_batch.print_color(
+        Point::new(11, y - 2),
+        "Spellbook",
+        ColorPair::new(yellow, black),
+    );
+    draw_batch.print_color(
+        Point::new(11, y + height + 1),
+        "ESCAPE to cancel",
+        ColorPair::new(yellow, black),
+    );
+    if count == 0 {
+        draw_batch.print_color(
+            Point::new(11, y),
+            "You don't know any spells yet.",
+            ColorPair::new(gray, black),
+        );
+    }
+
+    for (j, spell) in known_spells.iter().take(count).enumerate() {
+        menu_option(
+            &mut draw_batch,
+            10,
+            y,
+            97 + j as rltk::FontCharType,
+            &spell.display_name,
+        );
+        if let Some(spell_entity) = find_spell_entity(&gs.ecs, &spell.display_name) {
+            if let Some(template) = templates.get(spell_entity) {
+                let level = schools
+                    .get(player_entity)
+                    .and_then(|s| s.levels.get(&template.school).copied())
+                    .unwrap_or(0);
+                let range = ranged
+                    .get(spell_entity)
+                    .map_or("self".to_string(), |r| r.range.to_string());
+                let area = aoe
+                    .get(spell_entity)
+                    .map_or("-".to_string(), |a| a.shape.describe());
+                let failure = crate::effects::spell_failure(&gs.ecs, player_entity, spell_entity);
+                draw_batch.print_color(
+                    Point::new(32, y),
+                    format!(
+                        "{} {}  {} mp  rng {}  {}  {}% fail",
+                        template.school.name(),
+                        level,
+                        template.mana_cost,
+                        range,
+                        area,
+                        failure
+                    ),
+                    ColorPair::new(cyan, black),
+                );
+                draw_batch.print_color(
+                    Point::new(15, y + 1),
+                    &template.description,
+                    ColorPair::new(gray, black),
+                );
+            }
+        }
+        y += 2;
+    }
+
+    draw_batch.submit(6000).expect("Unable to draw Spellbook");
+
+    match ctx.key {
+        None => (ItemMenuResult::NoResponse, None),
+        Some(key) => match key {
+            VirtualKeyCode::Escape => (ItemMenuResult::Cancel, None),
+            _ => {
+                let selection = rltk::letter_to_option(key);
+                if selection > -1 && selection < count as i32 {
+                    return (ItemMenuResult::Selected, Some(selection as usize));
+                }
+                (ItemMenuResult::NoResponse, None)
+            }
+        },
+    }
+}
@@ -0,0 +1,34 @@
+use super::{get_item_display_name, item_result_menu, ItemMenuResult};
+use crate::{Equipped, InBackpack, State, Throwable};
+use rltk::prelude::*;
+use specs::prelude::*;
+
+pub fn throw_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option<Entity>) {
+    let mut draw_batch = DrawBatch::new();
+    let player_entity = gs.ecs.fetch::<Entity>();
+    let throwables = gs.ecs.read_storage::<Throwable>();
+    let backpack = gs.ecs.read_storage::<InBackpack>();
+    let equipped = gs.ecs.read_storage::<Equipped>();
+    let entities = gs.ecs.entities();
+
+    let mut items = Vec::new();
+    (&entities, &throwables)
+        .join()
+        .filter(|(item, _)| {
+            backpack
+                .get(*item)
+                .is_some_and(|b| b.owner == *player_entity)
+                || equipped
+                    .get(*item)
+                    .is_some_and(|e| e.owner == *player_entity)
+        })
+        .for_each(|(item, _)| items.push((item, get_item_display_name(&gs.ecs, item))));
+
+    let result = item_result_menu(&mut draw_batch, "Throw which item?", &items, ctx.key);
+
+    draw_batch
+        .submit(6000)
+        .expect("Unable to draw Throw Item menu");
+
+    result
+}
@@ -1,7 +1,10 @@
 use super::*;
 use rltk::prelude::*;
 
-use crate::{map::camera, Attributes, Duration, Hidden, Map, Name, Pools, StatusEffect};
+use crate::{
+    map::camera, Attributes, DamageType, Durability, Duration, Equipped, Hidden, Map,
+    MasterDungeonMap, Name, Pools, Resistances, StatusEffect,
+};
 
 pub struct Tooltip {
     lines: Vec<String>,
@@ -59,6 +62,14 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
     let hidden = ecs.read_storage::<Hidden>();
     let attributes = ecs.read_storage::<Attributes>();
     let pools = ecs.read_storage::<Pools>();
+    let entities = ecs.entities();
+    let resistances = ecs.read_storage::<Resistances>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let statuses = ecs.read_storage::<StatusEffect>();
+    let durabilities = ecs.read_storage::<Durability>();
+    let names = ecs.read_storage::<Name>();
+    let dm = ecs.fetch::<MasterDungeonMap>();
+    let player_entity = *ecs.fetch::<Entity>();
 
     let mouse_pos = ctx.mouse_pos();
     let mut mouse_map_pos = mouse_pos;
@@ -90,6 +101,15 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
         let mut tip = Tooltip::new();
         tip.add(get_item_display_name(ecs, entity));
 
+        if let Some(durability) = durabilities.get(entity) {
+            tip.add(format!(
+                "Condition: {} ({}/{})",
+                durability.condition().name(),
+                durability.current,
+                durability.max
+            ));
+        }
+
         if let Some(attr) = attributes.get(entity) {
             let mut s = "".to_string(); // String::new()
             if attr.might.bonus < 0 {
@@ -125,10 +145,50 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
         // Comment on pools
         if let Some(stat) = pools.get(entity) {
             tip.add(format!("Level: {}", stat.level));
+
+            // Known resistances and weaknesses: the player's own, and whatever they have seen
+            // this kind of creature shrug off or reel from
+            let mut immune = Vec::new();
+            let mut resists = Vec::new();
+            let mut vulnerable = Vec::new();
+            for damage_type in DamageType::ALL.iter() {
+                let known = entity == player_entity
+                    || names.get(entity).is_some_and(|name| {
+                        dm.known_resistances
+                            .contains(&(name.name.clone(), *damage_type))
+                    });
+                if !known {
+                    continue;
+                }
+                let resist = crate::total_resistance(
+                    entity,
+                    *damage_type,
+                    &entities,
+                    &resistances,
+                    &equipped,
+                    &statuses,
+                    &durabilities,
+                );
+                if resist >= 100 {
+                    immune.push(damage_type.name());
+                } else if resist > 0 {
+                    resists.push(damage_type.name());
+                } else if resist < 0 {
+                    vulnerable.push(damage_type.name());
+                }
+            }
+            if !immune.is_empty() {
+                tip.add(format!("Immune: {}", immune.join(", ")));
+            }
+            if !resists.is_empty() {
+                tip.add(format!("Resists: {}", resists.join(", ")));
+            }
+            if !vulnerable.is_empty() {
+                tip.add(format!("Vulnerable: {}", vulnerable.join(", ")));
+            }
         }
 
         // Status effects
-        let statuses = ecs.read_storage::<StatusEffect>();
         let durations = ecs.read_storage::<Duration>();
         let names = ecs.read_storage::<Name>();
         for (status, duration, name) in (&statuses, &durations, &names).join() {
@@ -140,6 +200,12 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
         tip_boxes.push(tip);
     });
 
+    if let Some(hazard) = map.hazards.get(&mouse_idx) {
+        let mut tip = Tooltip::new();
+        tip.add(format!("{} ({})", hazard.kind.name(), hazard.turns));
+        tip_boxes.push(tip);
+    }
+
     if tip_boxes.is_empty() {
         return;
     }
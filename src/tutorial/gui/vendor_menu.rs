@@ -1,7 +1,10 @@
 use super::*;
 use rltk::prelude::*;
 
-use crate::{Entity, InBackpack, Item, State, Vendor, VendorMode};
+use crate::{
+    Durability, Entity, Equipped, Faction, InBackpack, Item, MasterDungeonMap, RepairService,
+    State, Vendor, VendorMode,
+};
 
 pub fn show_vendor_menu(
     gs: &mut State,
@@ -12,6 +15,7 @@ pub fn show_vendor_menu(
     match mode {
         VendorMode::Buy => vendor_buy_menu(gs, ctx, vendor, mode),
         VendorMode::Sell => vendor_sell_menu(gs, ctx, vendor, mode),
+        VendorMode::Repair => vendor_repair_menu(gs, ctx, vendor, mode),
     }
 }
 
@@ -22,7 +26,34 @@ pub enum VendorResult {
     Sell,
     BuyMode,
     SellMode,
+    RepairMode,
     Buy,
+    Repair,
+}
+
+/// The fraction of an item's value that a smith charges to mend it from broken
+const REPAIR_COST_FACTOR: f32 = 0.5;
+
+/// What it costs to have a vendor restore an item to full durability
+pub fn repair_price(ecs: &World, vendor: Entity, item: Entity) -> f32 {
+    let base_value = ecs
+        .read_storage::<Item>()
+        .get(item)
+        .map_or(0.0, |i| i.base_value);
+    let missing = match ecs.read_storage::<Durability>().get(item) {
+        Some(durability) if durability.max > 0 => {
+            (durability.max - durability.current) as f32 / durability.max as f32
+        }
+        _ => 0.0,
+    };
+    let modifier = ecs
+        .read_storage::<Faction>()
+        .get(vendor)
+        .map_or(1.0, |faction| {
+            ecs.fetch::<MasterDungeonMap>()
+                .price_modifier(&faction.name)
+        });
+    f32::max(1.0, base_value * missing * REPAIR_COST_FACTOR * modifier)
 }
 
 // Player sells, vendor buys
@@ -129,11 +160,23 @@ pub fn vendor_buy_menu(
     let mut draw_batch = DrawBatch::new();
 
     let vendors = gs.ecs.read_storage::<Vendor>();
+    let repairs = gs.ecs.read_storage::<RepairService>().get(vendor).is_some();
 
-    let inventory = get_vendor_items(
+    let mut inventory = get_vendor_items(
         &vendors.get(vendor).unwrap().categories,
         &RAWS.lock().unwrap(),
     );
+
+    // Vendors charge less to those their faction thinks well of, and more to the rest
+    if let Some(faction) = gs.ecs.read_storage::<Faction>().get(vendor) {
+        let modifier = gs
+            .ecs
+            .fetch::<MasterDungeonMap>()
+            .price_modifier(&faction.name);
+        for sale in inventory.iter_mut() {
+            sale.1 *= modifier;
+        }
+    }
     let count = inventory.len();
 
     let mut y = (25 - (count / 2)) as i32;
@@ -142,7 +185,11 @@ pub fn vendor_buy_menu(
         15,
         y,
         (count + 3) as i32,
-        "Buy Which Item? (space to switch to sell mode)",
+        if repairs {
+            "Buy Which Item? (space to switch to repair mode)"
+        } else {
+            "Buy Which Item? (space to switch to sell mode)"
+        },
     );
     draw_batch.print_color(
         Point::new(18, y + count as i32 + 1),
@@ -179,6 +226,7 @@ pub fn vendor_buy_menu(
     match ctx.key {
         None => (VendorResult::NoResponse, None, None, None),
         Some(key) => match key {
+            VirtualKeyCode::Space if repairs => (VendorResult::RepairMode, None, None, None),
             VirtualKeyCode::Space => (VendorResult::SellMode, None, None, None),
             VirtualKeyCode::Escape => (VendorResult::Cancel, None, None, None),
             _ => {
@@ -196,3 +244,94 @@ pub fn vendor_buy_menu(
         },
     }
 }
+
+// Player pays to have worn equipment, carried or worn, mended
+pub fn vendor_repair_menu(
+    gs: &mut State,
+    ctx: &mut Rltk,
+    vendor: Entity,
+    _mode: VendorMode,
+) -> (VendorResult, Option<Entity>, Option<String>, Option<f32>) {
+    let mut draw_batch = DrawBatch::new();
+
+    let player_entity = gs.ecs.fetch::<Entity>();
+    let backpack = gs.ecs.read_storage::<InBackpack>();
+    let equipped = gs.ecs.read_storage::<Equipped>();
+    let durabilities = gs.ecs.read_storage::<Durability>();
+    let entities = gs.ecs.entities();
+
+    let damaged: Vec<(Entity, f32)> = (&entities, &durabilities)
+        .join()
+        .filter(|(item, durability)| {
+            durability.current < durability.max
+                && (backpack
+                    .get(*item)
+                    .is_some_and(|pack| pack.owner == *player_entity)
+                    || equipped
+                        .get(*item)
+                        .is_some_and(|equip| equip.owner == *player_entity))
+        })
+        .map(|(item, _)| (item, repair_price(&gs.ecs, vendor, item)))
+        .collect();
+    let count = damaged.len();
+
+    let y = (25 - (count / 2)) as i32;
+    menu_box(
+        &mut draw_batch,
+        15,
+        y,
+        (count + 3) as i32,
+        "Repair Which Item? (space to switch to sell mode)",
+    );
+    draw_batch.print_color(
+        Point::new(18, y + count as i32 + 1),
+        "ESCAPE to cancel",
+        ColorPair::new(RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK)),
+    );
+
+    for (j, (item, price)) in damaged.iter().enumerate() {
+        let y = y + j as i32;
+        draw_batch.set(
+            Point::new(17, y),
+            ColorPair::new(RGB::named(rltk::WHITE), RGB::named(rltk::BLACK)),
+            rltk::to_cp437('('),
+        );
+        draw_batch.set(
+            Point::new(18, y),
+            ColorPair::new(RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK)),
+            97 + j as rltk::FontCharType,
+        );
+        draw_batch.set(
+            Point::new(19, y),
+            ColorPair::new(RGB::named(rltk::WHITE), RGB::named(rltk::BLACK)),
+            rltk::to_cp437(')'),
+        );
+
+        draw_batch.print_color(
+            Point::new(21, y),
+            get_item_display_name(&gs.ecs, *item),
+            ColorPair::new(get_item_color(&gs.ecs, *item), RGB::named(rltk::BLACK)),
+        );
+        draw_batch.print(Point::new(50, y), format!("{:.1} gp", price));
+    }
+
+    draw_batch
+        .submit(6000)
+        .expect("Unable to draw Vendor Repair menu");
+
+    match ctx.key {
+        None => (VendorResult::NoResponse, None, None, None),
+        Some(key) => match key {
+            VirtualKeyCode::Space => (VendorResult::SellMode, None, None, None),
+            VirtualKeyCode::Escape => (VendorResult::Cancel, None, None, None),
+            _ => {
+                let selection = rltk::letter_to_option(key);
+                if selection > -1 && selection < count as i32 {
+                    let (item, price) = damaged[selection as usize];
+                    return (VendorResult::Repair, Some(item), None, Some(price));
+                }
+                (VendorResult::NoResponse, None, None, None)
+            }
+        },
+    }
+}
@@ -1,3 +1,6 @@
+pub mod combat;
+pub use combat::*;
+
 pub mod damage_system;
 pub use damage_system::*;
 
@@ -13,6 +16,9 @@ pub use components::*;
 pub mod player;
 pub use player::*;
 
+pub mod projectile;
+pub use projectile::*;
+
 pub mod rect;
 pub use rect::*;
 
@@ -28,6 +34,9 @@ pub use gamelog::*;
 pub mod spawner;
 pub use spawner::*;
 
+pub mod stacks;
+pub use stacks::*;
+
 pub mod saveload_system;
 pub use saveload_system::*;
 
@@ -39,5 +48,7 @@ pub use gamesystem::*;
 
 pub mod systems;
 
+pub mod noise;
+
 pub mod rng;
 pub use rng::*;
@@ -0,0 +1,29 @@
+use lazy_static::lazy_static;
+use rltk::{DistanceAlg, Point};
+use std::sync::Mutex;
+
+// How far the sounds of a fight carry
+pub const NOISE_RANGE: f32 = 10.0;
+
+lazy_static! {
+    static ref NOISES: Mutex<Vec<Point>> = Mutex::new(Vec::new());
+}
+
+/// Something loud happened here: a blow landing, a bolt striking home
+pub fn make_noise(at: Point) {
+    NOISES.lock().unwrap().push(at);
+}
+
+/// Forgets everything heard so far; called whenever the player takes a turn
+pub fn clear() {
+    NOISES.lock().unwrap().clear();
+}
+
+/// Has anything been heard within earshot of `listener` since the player last acted?
+pub fn heard_near(listener: Point) -> bool {
+    NOISES
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|noise| DistanceAlg::Pythagoras.distance2d(listener, *noise) <= NOISE_RANGE)
+}
@@ -20,6 +20,12 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
     let mut renderables = ecs.write_storage::<Renderable>();
     let factions = ecs.read_storage::<Faction>();
     let vendors = ecs.read_storage::<Vendor>();
+    let recruiters = ecs.read_storage::<Recruiter>();
+    let mut containers = ecs.write_storage::<Container>();
+    let mut container_traps = ecs.write_storage::<ContainerTrap>();
+    let attributes = ecs.read_storage::<Attributes>();
+    let names = ecs.read_storage::<Name>();
+    let dm = ecs.fetch::<MasterDungeonMap>();
 
     let mut result = RunState::AwaitingInput;
     let mut swap_entities: Vec<(Entity, i32, i32)> = Vec::new();
@@ -35,6 +41,9 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
             return result;
         }
         let dest = map.xy_idx(pos.x + delta_x, pos.y + delta_y);
+        let overloaded = combat_stats
+            .get(entity)
+            .is_some_and(|p| p.burden == BurdenLevel::Overloaded);
 
         result = crate::spatial::for_each_tile_content_with_gamemode(dest, |potential_target| {
             if vendors.get(potential_target).is_some() {
@@ -43,10 +52,15 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
                     mode: VendorMode::Sell,
                 });
             }
+            if recruiters.get(potential_target).is_some() {
+                return Some(RunState::ShowRecruiter {
+                    recruiter: potential_target,
+                });
+            }
             let mut hostile = true;
             if combat_stats.get(potential_target).is_some() {
                 if let Some(faction) = factions.get(potential_target) {
-                    let reaction = crate::raws::faction_reaction(
+                    let reaction = dm.faction_reaction(
                         &faction.name,
                         "Player",
                         &crate::raws::RAWS.lock().unwrap(),
@@ -57,6 +71,11 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
                 }
             }
             if !hostile {
+                if overloaded {
+                    log_overloaded();
+                    return Some(RunState::AwaitingInput);
+                }
+
                 // Note that we want to move the bystander
                 swap_entities.push((potential_target, pos.x, pos.y));
 
@@ -87,6 +106,19 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
                 }
             }
 
+            if let Some(container) = containers.get_mut(potential_target) {
+                return Some(open_container(
+                    entity,
+                    potential_target,
+                    container,
+                    &mut container_traps,
+                    &entities,
+                    &attributes,
+                    &names,
+                    map.xy_idx(pos.x, pos.y) as i32,
+                ));
+            }
+
             if let Some(door) = doors.get_mut(potential_target) {
                 door.open = true;
                 blocks_visibility.remove(potential_target);
@@ -99,7 +131,9 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
             None
         });
 
-        if !crate::spatial::is_blocked(dest) {
+        if overloaded && !crate::spatial::is_blocked(dest) {
+            log_overloaded();
+        } else if !crate::spatial::is_blocked(dest) {
             let old_idx = map.xy_idx(pos.x, pos.y);
             pos.x = min(map.width - 1, max(0, pos.x + delta_x));
             pos.y = min(map.height - 1, max(0, pos.y + delta_y));
@@ -199,7 +233,7 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
                 _ => None,
             };
             if let Some(key) = key {
-                return use_spell_hotkey(gs, key - 1);
+                return cast_known_spell(gs, key - 1);
             }
         }
 
@@ -212,7 +246,10 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
             Numpad7 | Y => try_move_player(-1, -1, &mut gs.ecs),
             Numpad3 | N => try_move_player(1, 1, &mut gs.ecs),
             Numpad1 | B => try_move_player(-1, 1, &mut gs.ecs),
-            Numpad5 | Space => skip_turn(&mut gs.ecs),
+            Numpad5 | Space => skip_turn(),
+            W => start_rest(&mut gs.ecs, false),
+            S => start_rest(&mut gs.ecs, true),
+            X => disarm_trap(&mut gs.ecs),
             F => fire_on_target(&mut gs.ecs),
             V => {
                 cycle_target(&mut gs.ecs);
@@ -235,12 +272,22 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
             Escape => RunState::SaveGame,
             Backslash => RunState::ShowCheatMenu,
             D => RunState::ShowDropItem,
-            G => {
-                get_item(&mut gs.ecs);
-                RunState::Ticking
-            }
+            G => get_item(&mut gs.ecs),
             I => RunState::ShowInventory,
             R => RunState::ShowRemoveItem,
+            C => RunState::ShowAllyCommands,
+            T => RunState::ShowThrowItem,
+            Z => RunState::ShowSpellbook,
+            M => {
+                if maneuver_targets(&gs.ecs).is_empty() {
+                    crate::gamelog::Logger::new()
+                        .append("There is nobody close enough to grapple with.")
+                        .log();
+                    RunState::AwaitingInput
+                } else {
+                    RunState::ShowManeuvers
+                }
+            }
             _ => RunState::AwaitingInput,
         }
     } else {
@@ -248,7 +295,7 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
     }
 }
 
-fn use_spell_hotkey(gs: &mut State, key: i32) -> RunState {
+pub fn cast_known_spell(gs: &mut State, key: i32) -> RunState {
     let player_entity = gs.ecs.fetch::<Entity>();
     let known_spells_storage = gs.ecs.read_storage::<KnownSpells>();
     let known_spells = &known_spells_storage.get(*player_entity).unwrap().spells;
@@ -337,6 +384,7 @@ fn get_player_target_list(ecs: &mut World) -> Vec<(f32, Entity)> {
     let map = ecs.fetch::<Map>();
     let positions = ecs.read_storage::<Position>();
     let factions = ecs.read_storage::<Faction>();
+    let allies = ecs.read_storage::<Ally>();
 
     let mut possible_targets = Vec::new();
 
@@ -354,6 +402,7 @@ fn get_player_target_list(ecs: &mut World) -> Vec<(f32, Entity)> {
                         crate::spatial::for_each_tile_content(tile_idx, |possible_target| {
                             if possible_target != *player_entity
                                 && factions.get(possible_target).is_some()
+                                && allies.get(possible_target).is_none()
                             {
                                 possible_targets.push((distance_to_target, possible_target));
                             }
@@ -414,6 +463,217 @@ fn fire_on_target(ecs: &mut World) -> RunState {
     }
 }
 
+fn log_overloaded() {
+    crate::gamelog::Logger::new()
+        .color(rltk::ORANGE)
+        .append("You are carrying too much to move!")
+        .log();
+}
+
+/// Refuses a load that would take the player past their hard cap, and warns when it would
+/// push them into a heavier burden tier
+pub fn can_carry(ecs: &World, extra_lbs: f32) -> bool {
+    let player_entity = ecs.fetch::<Entity>();
+    let pools = ecs.read_storage::<Pools>();
+    let pools = if let Some(pools) = pools.get(*player_entity) {
+        pools
+    } else {
+        return true;
+    };
+
+    let new_weight = pools.total_weight + extra_lbs;
+    if extra_lbs > 0.0 && new_weight > BurdenLevel::hard_cap(pools.carry_capacity_lbs) {
+        crate::gamelog::Logger::new()
+            .color(rltk::ORANGE)
+            .append("You can't carry that much!")
+            .log();
+        return false;
+    }
+
+    let burden = BurdenLevel::from_weight(new_weight, pools.carry_capacity_lbs);
+    if burden > pools.burden {
+        crate::gamelog::Logger::new()
+            .color(rltk::ORANGE)
+            .append(format!(
+                "That will leave you {}.",
+                burden.name().to_lowercase()
+            ))
+            .log();
+    }
+    true
+}
+
+pub fn item_weight(ecs: &World, item: Entity) -> f32 {
+    let quantity = ecs
+        .read_storage::<Stackable>()
+        .get(item)
+        .map_or(1, |s| s.quantity);
+    ecs.read_storage::<Item>()
+        .get(item)
+        .map_or(0.0, |i| i.weight_lbs * quantity as f32)
+}
+
+// Roll d20 + Might bonus against this to break a lock
+const FORCE_LOCK_DIFFICULTY: i32 = 15;
+
+/// Forcing a lock or springing a trap takes the turn; otherwise the container is opened
+#[allow(clippy::too_many_arguments)]
+fn open_container(
+    player: Entity,
+    container_entity: Entity,
+    container: &mut Container,
+    container_traps: &mut WriteStorage<ContainerTrap>,
+    entities: &Entities,
+    attributes: &ReadStorage<Attributes>,
+    names: &ReadStorage<Name>,
+    player_tile: i32,
+) -> RunState {
+    let name = names
+        .get(container_entity)
+        .map_or("container".to_string(), |n| n.name.clone());
+
+    if container.locked {
+        let might = attributes.get(player).map_or(0, |attr| attr.might.bonus);
+        if crate::tutorial::rng::roll_dice(1, 20) + might >= FORCE_LOCK_DIFFICULTY {
+            container.locked = false;
+            crate::gamelog::Logger::new()
+                .append("You force open the")
+                .item_name(&name)
+                .log();
+        } else {
+            crate::gamelog::Logger::new()
+                .append("The")
+                .item_name(&name)
+                .color(rltk::WHITE)
+                .append("is locked, and you fail to force it.")
+                .log();
+        }
+        return RunState::Ticking;
+    }
+
+    let trap = (&**entities, &*container_traps)
+        .join()
+        .find(|(_, trap)| trap.container == container_entity)
+        .map(|(trap, _)| trap);
+    if let Some(trap) = trap {
+        container_traps.remove(trap);
+        crate::gamelog::Logger::new()
+            .color(rltk::RED)
+            .append(&name)
+            .color(rltk::WHITE)
+            .append("was trapped!")
+            .log();
+        add_effect(
+            Some(player),
+            EffectType::TriggerFire { trigger: trap },
+            Targets::Tile {
+                tile_idx: player_tile,
+            },
+        );
+        return RunState::Ticking;
+    }
+
+    RunState::ShowContainer {
+        container: container_entity,
+        mode: ContainerMode::Take,
+    }
+}
+
+pub fn take_from_container(ecs: &mut World, item: Entity) {
+    if !can_carry(ecs, item_weight(ecs, item)) {
+        return;
+    }
+    let player_entity = *ecs.fetch::<Entity>();
+    crate::gamelog::Logger::new()
+        .append("You take the")
+        .item_name(crate::gui::get_item_display_name(ecs, item))
+        .log();
+
+    ecs.write_storage::<InContainer>().remove(item);
+    ecs.write_storage::<InBackpack>()
+        .insert(
+            item,
+            InBackpack {
+                owner: player_entity,
+            },
+        )
+        .expect("Insert fail");
+    ecs.write_storage::<EquipmentChanged>()
+        .insert(player_entity, EquipmentChanged {})
+        .expect("Insert fail");
+    merge_into_stack(ecs, item);
+}
+
+pub fn put_in_container(ecs: &mut World, container: Entity, item: Entity) {
+    let player_entity = *ecs.fetch::<Entity>();
+    crate::gamelog::Logger::new()
+        .append("You put away the")
+        .item_name(crate::gui::get_item_display_name(ecs, item))
+        .log();
+
+    ecs.write_storage::<InBackpack>().remove(item);
+    ecs.write_storage::<InContainer>()
+        .insert(item, InContainer { container })
+        .expect("Insert fail");
+    ecs.write_storage::<EquipmentChanged>()
+        .insert(player_entity, EquipmentChanged {})
+        .expect("Insert fail");
+}
+
+pub fn throw_at_target(ecs: &mut World, item: Entity) -> RunState {
+    let targets = ecs.read_storage::<Target>();
+    let entities = ecs.entities();
+    let target = (&entities, &targets).join().map(|(e, _)| e).last();
+
+    let target = if let Some(target) = target {
+        target
+    } else {
+        crate::gamelog::Logger::new()
+            .color(rltk::WHITE)
+            .append("You don't have a target selected!")
+            .log();
+        return RunState::AwaitingInput;
+    };
+
+    let player_entity = ecs.fetch::<Entity>();
+    let positions = ecs.read_storage::<Position>();
+    let range = ecs
+        .read_storage::<Throwable>()
+        .get(item)
+        .map_or(0, |throwable| throwable.range);
+    if let (Some(player_pos), Some(target_pos)) =
+        (positions.get(*player_entity), positions.get(target))
+    {
+        let distance = rltk::DistanceAlg::Pythagoras.distance2d(
+            Point::new(player_pos.x, player_pos.y),
+            Point::new(target_pos.x, target_pos.y),
+        );
+        if distance > range as f32 {
+            crate::gamelog::Logger::new()
+                .color(rltk::WHITE)
+                .append("That's too far away to throw at.")
+                .log();
+            return RunState::AwaitingInput;
+        }
+    }
+
+    if let Some(name) = ecs.read_storage::<Name>().get(target) {
+        crate::gamelog::Logger::new()
+            .color(rltk::WHITE)
+            .append("You throw the")
+            .item_name(crate::gui::get_item_display_name(ecs, item))
+            .color(rltk::WHITE)
+            .append("at")
+            .item_name(&name.name)
+            .log();
+    }
+    ecs.write_storage::<WantsToThrow>()
+        .insert(*player_entity, WantsToThrow { item, target })
+        .expect("Insert fail");
+
+    RunState::Ticking
+}
+
 fn cycle_target(ecs: &mut World) {
     let possible_targets = get_player_target_list(ecs);
     let mut targets = ecs.write_storage::<Target>();
@@ -447,51 +707,118 @@ fn cycle_target(ecs: &mut World) {
     }
 }
 
-fn skip_turn(ecs: &mut World) -> RunState {
+pub fn command_allies(ecs: &mut World, command: AllyCommand) -> RunState {
+    let entities = ecs.entities();
+    let mut allies = ecs.write_storage::<Ally>();
+    let mut ally_targets = ecs.write_storage::<AllyTarget>();
+    let positions = ecs.read_storage::<Position>();
+    let targets = ecs.read_storage::<Target>();
+
+    let current_target = (&entities, &targets).join().map(|(e, _)| e).next();
+    if command == AllyCommand::Attack && current_target.is_none() {
+        crate::gamelog::Logger::new()
+            .color(rltk::WHITE)
+            .append("You don't have a target selected!")
+            .log();
+        return RunState::AwaitingInput;
+    }
+
+    // Only allies on this level can hear you
+    let mut count = 0;
+    for (entity, ally, _pos) in (&entities, &mut allies, &positions).join() {
+        ally.command = command;
+        ally_targets.remove(entity);
+        if let Some(target) = current_target.filter(|_| command == AllyCommand::Attack) {
+            ally_targets
+                .insert(entity, AllyTarget { target })
+                .expect("Unable to insert");
+        }
+        count += 1;
+    }
+
+    if count == 0 {
+        crate::gamelog::Logger::new()
+            .append("You have nobody to command.")
+            .log();
+        return RunState::AwaitingInput;
+    }
+
+    crate::gamelog::Logger::new()
+        .append(match command {
+            AllyCommand::Follow => "You tell your allies to follow you.",
+            AllyCommand::Wait => "You tell your allies to wait here.",
+            AllyCommand::Attack => "You order your allies to attack!",
+        })
+        .log();
+    RunState::Ticking
+}
+
+/// Everyone standing next to the player that could be shoved, tripped or disarmed
+pub fn maneuver_targets(ecs: &World) -> Vec<Entity> {
+    let player_entity = ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let positions = ecs.read_storage::<Position>();
+    let pools = ecs.read_storage::<Pools>();
+
+    let player_pos = match positions.get(*player_entity) {
+        Some(pos) => Point::new(pos.x, pos.y),
+        None => return Vec::new(),
+    };
+
+    (&entities, &positions, &pools)
+        .join()
+        .filter(|(entity, pos, _)| {
+            *entity != *player_entity
+                && rltk::DistanceAlg::Pythagoras.distance2d(player_pos, Point::new(pos.x, pos.y))
+                    < 1.5
+        })
+        .map(|(entity, _, _)| entity)
+        .collect()
+}
+
+pub fn perform_maneuver(ecs: &mut World, target: Entity, maneuver: Maneuver) -> RunState {
+    let player_entity = ecs.fetch::<Entity>();
+    ecs.write_storage::<WantsToManeuver>()
+        .insert(*player_entity, WantsToManeuver { target, maneuver })
+        .expect("Unable to insert intent");
+    RunState::Ticking
+}
+
+/// The first creature in sight that would attack the player, if any
+fn visible_hostile(ecs: &World) -> Option<Entity> {
     let player_entity = ecs.fetch::<Entity>();
     let viewshed_components = ecs.read_storage::<Viewshed>();
     let factions = ecs.read_storage::<Faction>();
+    let dm = ecs.fetch::<MasterDungeonMap>();
 
     let worldmap_resource = ecs.fetch::<Map>();
 
-    let mut can_heal = true;
+    let mut hostile = None;
     let viewshed = viewshed_components.get(*player_entity).unwrap();
     for tile in viewshed.visible_tiles.iter() {
         let idx = worldmap_resource.xy_idx(tile.x, tile.y);
         crate::spatial::for_each_tile_content(idx, |entity_id| {
             if let Some(faction) = factions.get(entity_id) {
-                let reaction = crate::raws::faction_reaction(
+                let reaction = dm.faction_reaction(
                     &faction.name,
                     "Player",
                     &crate::raws::RAWS.lock().unwrap(),
                 );
-                if reaction == Reaction::Attack {
-                    can_heal = false;
+                if reaction == Reaction::Attack && hostile.is_none() {
+                    hostile = Some(entity_id);
                 }
             }
         });
     }
+    hostile
+}
 
-    let hunger_clocks = ecs.read_storage::<HungerClock>();
-    if let Some(hc) = hunger_clocks.get(*player_entity) {
-        can_heal =
-            can_heal && (hc.state != HungerState::Hungry && hc.state != HungerState::Starving);
-    }
-
-    if can_heal {
-        let mut health_components = ecs.write_storage::<Pools>();
-        let pools = health_components.get_mut(*player_entity).unwrap();
-        pools.hit_points.current = i32::min(pools.hit_points.current + 1, pools.hit_points.max);
-
-        if crate::tutorial::rng::roll_dice(1, 6) == 1 {
-            pools.mana.current = i32::min(pools.mana.current + 1, pools.mana.max);
-        }
-    }
-
+/// Waiting just lets time pass; RegenerationSystem takes care of any healing
+fn skip_turn() -> RunState {
     RunState::Ticking
 }
 
-fn get_item(ecs: &mut World) {
+fn get_item(ecs: &mut World) -> RunState {
     let player_pos = ecs.fetch::<Point>();
     let player_entity = ecs.fetch::<Entity>();
     let entities = ecs.entities();
@@ -511,6 +838,9 @@ fn get_item(ecs: &mut World) {
             .append("There is nothing here to pick up")
             .log(),
         Some(item) => {
+            if !can_carry(ecs, item_weight(ecs, item)) {
+                return RunState::AwaitingInput;
+            }
             let mut pickup = ecs.write_storage::<WantsToPickupItem>();
             pickup
                 .insert(
@@ -523,4 +853,399 @@ fn get_item(ecs: &mut World) {
                 .expect("Unable to insert want to pickup");
         }
     }
+
+    RunState::Ticking
+}
+
+// How many turns a search goes on when nothing turns up
+const SEARCH_TURNS: i32 = 20;
+
+fn start_rest(ecs: &mut World, search: bool) -> RunState {
+    if let Some(hostile) = visible_hostile(ecs) {
+        if let Some(name) = ecs.read_storage::<Name>().get(hostile) {
+            crate::gamelog::Logger::new()
+                .append("You can't rest with")
+                .npc_name(&name.name)
+                .append("nearby.")
+                .log();
+        }
+        return RunState::AwaitingInput;
+    }
+
+    let player_entity = *ecs.fetch::<Entity>();
+    let hunger = ecs
+        .read_storage::<HungerClock>()
+        .get(player_entity)
+        .map_or(HungerState::Normal, |clock| clock.state);
+    let (hit_points, rested) = {
+        let pools = ecs.read_storage::<Pools>();
+        let stats = pools.get(player_entity).unwrap();
+        (
+            stats.hit_points.current,
+            stats.hit_points.current == stats.hit_points.max
+                && stats.mana.current == stats.mana.max,
+        )
+    };
+    if !search {
+        if hunger == HungerState::Hungry || hunger == HungerState::Starving {
+            crate::gamelog::Logger::new()
+                .append("You are too hungry to rest.")
+                .log();
+            return RunState::AwaitingInput;
+        }
+        if rested {
+            crate::gamelog::Logger::new()
+                .append("You are already fully rested.")
+                .log();
+            return RunState::AwaitingInput;
+        }
+    }
+
+    ecs.write_storage::<Resting>()
+        .insert(
+            player_entity,
+            Resting {
+                search,
+                turns: 0,
+                hunger,
+                hit_points,
+            },
+        )
+        .expect("Unable to insert");
+    crate::gamelog::Logger::new()
+        .append(if search {
+            "You search the area."
+        } else {
+            "You settle down to rest."
+        })
+        .log();
+    rest_turn(ecs)
+}
+
+pub fn is_resting(ecs: &World) -> bool {
+    ecs.read_storage::<Resting>()
+        .get(*ecs.fetch::<Entity>())
+        .is_some()
+}
+
+/// Takes the next turn of a rest or search, unless something has come up. Any key stops it.
+pub fn continue_rest(ecs: &mut World, ctx: &Rltk) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let Some(resting) = ecs.read_storage::<Resting>().get(player_entity).cloned() else {
+        return RunState::AwaitingInput;
+    };
+
+    let stop = if ctx.key.is_some() {
+        Some(crate::gamelog::Logger::new().append(if resting.search {
+            "You stop searching."
+        } else {
+            "You stop resting."
+        }))
+    } else if let Some(hostile) = visible_hostile(ecs) {
+        ecs.read_storage::<Name>().get(hostile).map(|name| {
+            crate::gamelog::Logger::new()
+                .color(rltk::ORANGE)
+                .npc_name(&name.name)
+                .append("comes into view!")
+        })
+    } else if ecs
+        .read_storage::<HungerClock>()
+        .get(player_entity)
+        .is_some_and(|clock| clock.state as i32 > resting.hunger as i32)
+    {
+        Some(crate::gamelog::Logger::new().append("Your growling stomach disturbs you."))
+    } else if heard_fighting(ecs) {
+        Some(
+            crate::gamelog::Logger::new()
+                .color(rltk::ORANGE)
+                .append("You hear fighting nearby!"),
+        )
+    } else if ecs
+        .read_storage::<Pools>()
+        .get(player_entity)
+        .is_some_and(|stats| stats.hit_points.current < resting.hit_points)
+    {
+        Some(
+            crate::gamelog::Logger::new()
+                .color(rltk::ORANGE)
+                .append("Something disturbs you!"),
+        )
+    } else if resting.search && resting.turns >= SEARCH_TURNS {
+        Some(crate::gamelog::Logger::new().append("You finish searching."))
+    } else if !resting.search
+        && ecs
+            .read_storage::<Pools>()
+            .get(player_entity)
+            .is_some_and(|stats| {
+                stats.hit_points.current == stats.hit_points.max
+                    && stats.mana.current == stats.mana.max
+            })
+    {
+        Some(crate::gamelog::Logger::new().append("You feel rested."))
+    } else {
+        None
+    };
+
+    if let Some(log) = stop {
+        log.log();
+        ecs.write_storage::<Resting>().remove(player_entity);
+        return RunState::AwaitingInput;
+    }
+    rest_turn(ecs)
+}
+
+/// Sounds of a fight close enough to wake the player, even one they can't see
+fn heard_fighting(ecs: &World) -> bool {
+    let player_pos = *ecs.fetch::<Point>();
+    crate::tutorial::noise::heard_near(player_pos)
+}
+
+fn rest_turn(ecs: &mut World) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let found = {
+        let resting = ecs.read_storage::<Resting>();
+        resting.get(player_entity).is_some_and(|r| r.search)
+    } && search_for_hidden(ecs);
+    if found {
+        // Whatever turned up deserves a look before carrying on
+        ecs.write_storage::<Resting>().remove(player_entity);
+    }
+
+    let result = skip_turn();
+    let hit_points = ecs
+        .read_storage::<Pools>()
+        .get(player_entity)
+        .map_or(0, |stats| stats.hit_points.current);
+    if let Some(resting) = ecs.write_storage::<Resting>().get_mut(player_entity) {
+        resting.turns += 1;
+        resting.hit_points = hit_points;
+    }
+    result
+}
+
+/// A careful look at everything in sight, much likelier to turn up hidden things than a passing
+/// glance. Things further away are harder to make out.
+fn search_for_hidden(ecs: &mut World) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    let player_pos = *ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let entities = ecs.entities();
+    let mut hidden = ecs.write_storage::<Hidden>();
+    let names = ecs.read_storage::<Name>();
+    let positions = ecs.read_storage::<Position>();
+    let viewsheds = ecs.read_storage::<Viewshed>();
+    let attributes = ecs.read_storage::<Attributes>();
+    let skills = ecs.read_storage::<Skills>();
+    let traps = ecs.read_storage::<Trap>();
+    let container_traps = ecs.read_storage::<ContainerTrap>();
+
+    let searcher_attributes = attributes.get(player_entity);
+    let searcher_skills = skills.get(player_entity);
+    let spots = |entity: Entity, at: Point| {
+        let distance = rltk::DistanceAlg::Pythagoras.distance2d(player_pos, at) as i32;
+        let difficulty = traps
+            .get(entity)
+            .map_or(DEFAULT_TRAP_DIFFICULTY, |trap| trap.difficulty);
+        spots_hidden(
+            searcher_attributes,
+            searcher_skills,
+            difficulty + distance / 2,
+            true,
+            &mut GameDice,
+        )
+    };
+
+    let mut found = false;
+    let Some(viewshed) = viewsheds.get(player_entity) else {
+        return false;
+    };
+    for tile in viewshed.visible_tiles.iter() {
+        let idx = map.xy_idx(tile.x, tile.y);
+        crate::spatial::for_each_tile_content(idx, |e| {
+            if hidden.get(e).is_some() && spots(e, *tile) {
+                if let Some(name) = names.get(e) {
+                    crate::gamelog::Logger::new()
+                        .append("You spotted:")
+                        .color(rltk::RED)
+                        .append(&name.name)
+                        .log();
+                }
+                hidden.remove(e);
+                found = true;
+            }
+        });
+    }
+
+    // Container traps live off the map, so look for them by the container they guard
+    let mut noticed = Vec::new();
+    for (trap, container_trap, _hidden) in (&entities, &container_traps, &hidden).join() {
+        let Some(pos) = positions.get(container_trap.container) else {
+            continue;
+        };
+        let at = Point::new(pos.x, pos.y);
+        if map.visible_tiles[map.xy_idx(pos.x, pos.y)] && spots(trap, at) {
+            if let Some(name) = names.get(container_trap.container) {
+                crate::gamelog::Logger::new()
+                    .append("You notice that the")
+                    .item_name(&name.name)
+                    .color(rltk::WHITE)
+                    .append("is trapped!")
+                    .log();
+            }
+            noticed.push(trap);
+        }
+    }
+    for trap in noticed {
+        hidden.remove(trap);
+        found = true;
+    }
+    found
+}
+
+// Missing a disarm roll by this much sets the trap off
+const DISARM_FUMBLE_MARGIN: i32 = 5;
+// Beating it by this much leaves the trap in good enough shape to carry off as a kit
+const DISARM_SALVAGE_MARGIN: i32 = 5;
+
+/// A trap the player knows about on or next to their tile, with where it is and, for a trapped
+/// container, the container it guards
+fn known_trap_near(ecs: &World, player_pos: Point) -> Option<(Entity, Point, Option<Entity>)> {
+    let map = ecs.fetch::<Map>();
+    let hidden = ecs.read_storage::<Hidden>();
+    let traps = ecs.read_storage::<Trap>();
+    let triggers = ecs.read_storage::<EntryTrigger>();
+
+    let mut found = None;
+    for y in player_pos.y - 1..=player_pos.y + 1 {
+        for x in player_pos.x - 1..=player_pos.x + 1 {
+            if x < 0 || x >= map.width || y < 0 || y >= map.height {
+                continue;
+            }
+            crate::spatial::for_each_tile_content(map.xy_idx(x, y), |e| {
+                if found.is_none()
+                    && traps.get(e).is_some()
+                    && triggers.get(e).is_some()
+                    && hidden.get(e).is_none()
+                {
+                    found = Some((e, Point::new(x, y), None));
+                }
+            });
+        }
+    }
+    if found.is_some() {
+        return found;
+    }
+
+    let entities = ecs.entities();
+    let positions = ecs.read_storage::<Position>();
+    let container_traps = ecs.read_storage::<ContainerTrap>();
+    (&entities, &container_traps, !&hidden)
+        .join()
+        .find_map(|(trap, container_trap, _)| {
+            let pos = positions.get(container_trap.container)?;
+            if i32::abs(pos.x - player_pos.x) <= 1 && i32::abs(pos.y - player_pos.y) <= 1 {
+                Some((
+                    trap,
+                    Point::new(pos.x, pos.y),
+                    Some(container_trap.container),
+                ))
+            } else {
+                None
+            }
+        })
+}
+
+fn disarm_trap(ecs: &mut World) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let player_pos = *ecs.fetch::<Point>();
+    let Some((trap, trap_pos, container)) = known_trap_near(ecs, player_pos) else {
+        crate::gamelog::Logger::new()
+            .append("You don't know of any trap close enough to disarm.")
+            .log();
+        return RunState::AwaitingInput;
+    };
+
+    let name = ecs
+        .read_storage::<Name>()
+        .get(container.unwrap_or(trap))
+        .map_or("trap".to_string(), |n| n.name.clone());
+    let difficulty = ecs
+        .read_storage::<Trap>()
+        .get(trap)
+        .map_or(DEFAULT_TRAP_DIFFICULTY, |t| t.difficulty);
+    let margin = disarm_margin(
+        ecs.read_storage::<Attributes>().get(player_entity),
+        ecs.read_storage::<Skills>().get(player_entity),
+        difficulty,
+    );
+
+    if margin >= 0 {
+        crate::gamelog::Logger::new()
+            .append(if container.is_some() {
+                "You disarm the trap on the"
+            } else {
+                "You disarm the"
+            })
+            .item_name(&name)
+            .log();
+        ecs.entities().delete(trap).expect("Unable to delete");
+
+        if container.is_none() && margin >= DISARM_SALVAGE_MARGIN {
+            let kit = format!("{} Kit", name);
+            if spawn_named_entity(
+                &crate::raws::RAWS.lock().unwrap(),
+                ecs,
+                &kit,
+                SpawnType::Carried { by: player_entity },
+            )
+            .is_some()
+            {
+                crate::gamelog::Logger::new()
+                    .append("You salvage a")
+                    .item_name(&kit)
+                    .log();
+                ecs.write_storage::<EquipmentChanged>()
+                    .insert(player_entity, EquipmentChanged {})
+                    .expect("Unable to insert");
+            }
+        }
+    } else if margin <= -DISARM_FUMBLE_MARGIN {
+        crate::gamelog::Logger::new()
+            .color(rltk::RED)
+            .append(if container.is_some() {
+                "You set off the trap on the"
+            } else {
+                "You set off the"
+            })
+            .item_name(&name)
+            .log();
+        // A sprung container trap is spent whatever it does
+        ecs.write_storage::<ContainerTrap>().remove(trap);
+
+        let map = ecs.fetch::<Map>();
+        let targets = if let Some(aoe) = ecs.read_storage::<AreaOfEffect>().get(trap) {
+            Targets::Tiles {
+                tiles: shape_tiles(&map, trap_pos, trap_pos, aoe.shape),
+            }
+        } else {
+            Targets::Tile {
+                tile_idx: map.xy_idx(player_pos.x, player_pos.y) as i32,
+            }
+        };
+        add_effect(
+            Some(player_entity),
+            EffectType::TriggerFire { trigger: trap },
+            targets,
+        );
+    } else {
+        crate::gamelog::Logger::new()
+            .append(if container.is_some() {
+                "You fail to disarm the trap on the"
+            } else {
+                "You fail to disarm the"
+            })
+            .item_name(&name)
+            .log();
+    }
+    RunState::Ticking
 }
@@ -0,0 +1,118 @@
+use crate::{
+    defense_profile, map::Map, resolve_attack, skill_bonus, spell_profile, tile_opaque,
+    AttackProfile, AttackResult, BlocksTile, CombatData, DamageType, DiceRoller, Pools, Skill,
+};
+use rltk::Point;
+use specs::prelude::*;
+
+/// Why a projectile stopped where it did
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ProjectileStop {
+    Target,
+    Obstacle,
+    Creature(Entity),
+}
+
+pub struct ProjectileFlight {
+    pub path: Vec<Point>,
+    pub landed: Point,
+    pub stop: ProjectileStop,
+}
+
+/// Walks the line from the shooter towards the aim point. Walls, closed doors and other
+/// blocking props stop the projectile on the tile in front of them, as does the edge of the
+/// map. Each creature along the way is asked `intercepts` whether it catches the shot; the
+/// aim point itself always does.
+pub fn trace_projectile(
+    map: &Map,
+    from: Point,
+    to: Point,
+    shooter: Entity,
+    pools: &ReadStorage<Pools>,
+    blockers: &ReadStorage<BlocksTile>,
+    intercepts: &mut dyn FnMut(Entity) -> bool,
+) -> ProjectileFlight {
+    let mut path = vec![from];
+    let mut landed = from;
+
+    for pt in rltk::line2d(rltk::LineAlg::Bresenham, from, to) {
+        if pt == from {
+            continue;
+        }
+        let off_map = pt.x < 1 || pt.x > map.width - 1 || pt.y < 1 || pt.y > map.height - 1;
+        if off_map || tile_opaque(map.tiles[map.xy_idx(pt.x, pt.y)]) {
+            return ProjectileFlight {
+                path,
+                landed,
+                stop: ProjectileStop::Obstacle,
+            };
+        }
+
+        let idx = map.xy_idx(pt.x, pt.y);
+        let mut obstacle = false;
+        let mut creatures = Vec::new();
+        crate::spatial::for_each_tile_content(idx, |entity| {
+            if entity == shooter {
+                return;
+            }
+            if pools.get(entity).is_some() {
+                creatures.push(entity);
+            } else if blockers.get(entity).is_some() {
+                obstacle = true;
+            }
+        });
+        if obstacle {
+            return ProjectileFlight {
+                path,
+                landed,
+                stop: ProjectileStop::Obstacle,
+            };
+        }
+
+        path.push(pt);
+        landed = pt;
+        if pt == to {
+            break;
+        }
+        for creature in creatures {
+            if intercepts(creature) {
+                return ProjectileFlight {
+                    path,
+                    landed,
+                    stop: ProjectileStop::Creature(creature),
+                };
+            }
+        }
+    }
+
+    ProjectileFlight {
+        path,
+        landed,
+        stop: ProjectileStop::Target,
+    }
+}
+
+/// The shooter's attack roll against a creature standing in the line of fire; a hit means
+/// the bystander takes the shot meant for someone else
+pub fn roll_against_bystander(
+    attack: &AttackProfile,
+    bystander: Entity,
+    data: &CombatData,
+    dice: &mut dyn DiceRoller,
+) -> Option<AttackResult> {
+    let defense = defense_profile(bystander, data)?;
+    let result = resolve_attack(attack, &defense, dice);
+    result.is_hit().then_some(result)
+}
+
+/// Bolts from wands, scrolls and spells still have to be aimed past whoever is in the way.
+/// Nothing here does damage; it is only the roll to see who gets in the way.
+pub fn aim_profile(user: Entity, data: &CombatData) -> Option<AttackProfile> {
+    let attributes = data.attributes.get(user)?;
+    let skills = data.skills.get(user)?;
+    let mut aim = spell_profile(0, DamageType::Blunt);
+    aim.always_hits = false;
+    aim.attribute_hit_bonus = attributes.quickness.bonus;
+    aim.skill_hit_bonus = skill_bonus(Skill::Magic, skills);
+    Some(aim)
+}
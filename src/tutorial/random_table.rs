@@ -46,6 +46,10 @@ impl MasterTable {
             _ => "None".to_string(),
         }
     }
+
+    pub fn roll_mob(&self) -> String {
+        self.mobs.roll()
+    }
 }
 
 #[derive(Default)]
@@ -99,6 +99,41 @@ pub fn save_game(ecs: &mut World) {
             NaturalAttackDefense,
             Name,
             Faction,
+            UsesItems,
+            Ally,
+            AllyTarget,
+            Recruiter,
+            Schedule,
+            Resistances,
+            Stunned,
+            WantsToManeuver,
+            Ammunition,
+            Throwable,
+            WantsToThrow,
+            Stackable,
+            Container,
+            ContainerTrap,
+            InContainer,
+            Bag,
+            Durability,
+            RepairService,
+            EffectScript,
+            StatusImmunities,
+            StatusHooks,
+            SchoolSkills,
+            LeavesHazard,
+            Summoned,
+            SummonsCreature,
+            Blink,
+            RandomTeleport,
+            SwapPlaces,
+            Knockback,
+            Pull,
+            Polymorphs,
+            TrueForm,
+            Resting,
+            Trap,
+            DeploysTrap,
             BlocksTile,
             BlocksVisibility,
             Door,
@@ -114,6 +149,7 @@ pub fn save_game(ecs: &mut World) {
             WantsToCastSpell,
             TeachesSpell,
             WantsToFlee,
+            WantsToSkirmish,
             Item,
             Vendor,
             Consumable,
@@ -218,6 +254,41 @@ pub fn load_game(ecs: &mut World) {
             NaturalAttackDefense,
             Name,
             Faction,
+            UsesItems,
+            Ally,
+            AllyTarget,
+            Recruiter,
+            Schedule,
+            Resistances,
+            Stunned,
+            WantsToManeuver,
+            Ammunition,
+            Throwable,
+            WantsToThrow,
+            Stackable,
+            Container,
+            ContainerTrap,
+            InContainer,
+            Bag,
+            Durability,
+            RepairService,
+            EffectScript,
+            StatusImmunities,
+            StatusHooks,
+            SchoolSkills,
+            LeavesHazard,
+            Summoned,
+            SummonsCreature,
+            Blink,
+            RandomTeleport,
+            SwapPlaces,
+            Knockback,
+            Pull,
+            Polymorphs,
+            TrueForm,
+            Resting,
+            Trap,
+            DeploysTrap,
             BlocksTile,
             BlocksVisibility,
             Door,
@@ -233,6 +304,7 @@ pub fn load_game(ecs: &mut World) {
             WantsToCastSpell,
             TeachesSpell,
             WantsToFlee,
+            WantsToSkirmish,
             Item,
             Vendor,
             Consumable,
@@ -81,6 +81,49 @@ pub fn spawn_entity(ecs: &mut World, spawn: &(&usize, &String)) {
     rltk::console::log(format!("Waring: we don't know how to spawn [{}]!", spawn.1));
 }
 
+pub fn hire_mercenary(ecs: &mut World, name: &str) -> Option<Entity> {
+    // Find somewhere next to the player for the new recruit to stand
+    let map = ecs.fetch::<Map>();
+    let player_pos = *ecs.fetch::<Point>();
+    let mut spot = None;
+    'search: for radius in 1..=3 {
+        for y in player_pos.y - radius..=player_pos.y + radius {
+            for x in player_pos.x - radius..=player_pos.x + radius {
+                if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+                    continue;
+                }
+                let idx = map.xy_idx(x, y);
+                if tile_walkable(map.tiles[idx]) && !crate::spatial::is_blocked(idx) {
+                    spot = Some(Point::new(x, y));
+                    break 'search;
+                }
+            }
+        }
+    }
+    std::mem::drop(map);
+    let spot = spot?;
+
+    let hireling = spawn_named_entity(
+        &RAWS.lock().unwrap(),
+        ecs,
+        name,
+        SpawnType::AtPosition {
+            x: spot.x,
+            y: spot.y,
+        },
+    )?;
+    ecs.write_storage::<Ally>()
+        .insert(
+            hireling,
+            Ally {
+                command: AllyCommand::Follow,
+            },
+        )
+        .expect("Unable to insert");
+
+    Some(hireling)
+}
+
 pub fn spawn_town_portal(ecs: &mut World) {
     // Get current position & depth
     let map = ecs.fetch::<Map>();
@@ -143,6 +186,7 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
     skills.skills.insert(Skill::Melee, 1);
     skills.skills.insert(Skill::Defense, 1);
     skills.skills.insert(Skill::Magic, 1);
+    skills.skills.insert(Skill::Perception, 1);
 
     let player = ecs
         .create_entity()
@@ -193,12 +237,15 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
             xp: 0,
             level: 1,
             total_weight: 0.0,
+            carry_capacity_lbs: 0,
+            burden: BurdenLevel::Unburdened,
             total_initiative_penalty: 0.0,
             gold: 0.0,
             god_mode: false,
         })
         .with(EquipmentChanged {})
         .with(KnownSpells { spells: Vec::new() })
+        .with(SchoolSkills::default())
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 
@@ -245,10 +292,19 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
         "Shortbow",
         SpawnType::Carried { by: player },
     );
+    spawn_named_entity(
+        &RAWS.lock().unwrap(),
+        ecs,
+        "Arrows",
+        SpawnType::Carried { by: player },
+    );
 
     // Starting hangover
     ecs.create_entity()
-        .with(StatusEffect { target: player })
+        .with(StatusEffect {
+            target: player,
+            kind: StatusKind::Attribute,
+        })
         .with(Duration { turns: 10 })
         .with(Name {
             name: "Hangover".to_string(),
@@ -0,0 +1,85 @@
+use crate::{
+    raws::{spawn_named_entity, SpawnType, RAWS},
+    Equipped, InBackpack, Name, Stackable,
+};
+use specs::prelude::*;
+
+/// Finds a stack of the same item that `owner` is already carrying, or has readied like a quiver
+pub fn find_stack(ecs: &World, item: Entity, owner: Entity) -> Option<Entity> {
+    let names = ecs.read_storage::<Name>();
+    let stackables = ecs.read_storage::<Stackable>();
+    let backpack = ecs.read_storage::<InBackpack>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let entities = ecs.entities();
+
+    stackables.get(item)?;
+    let item_name = &names.get(item)?.name;
+    (&entities, &names, &stackables)
+        .join()
+        .find(|(other, name, _)| {
+            *other != item
+                && name.name == *item_name
+                && (backpack.get(*other).is_some_and(|b| b.owner == owner)
+                    || equipped.get(*other).is_some_and(|e| e.owner == owner))
+        })
+        .map(|(other, _, _)| other)
+}
+
+/// Folds a newly carried item into a matching stack, returning whichever entity now holds it
+pub fn merge_into_stack(ecs: &mut World, item: Entity) -> Entity {
+    let owner = if let Some(carried) = ecs.read_storage::<InBackpack>().get(item) {
+        carried.owner
+    } else {
+        return item;
+    };
+
+    if let Some(stack) = find_stack(ecs, item, owner) {
+        let mut stackables = ecs.write_storage::<Stackable>();
+        let quantity = stackables.get(item).unwrap().quantity;
+        stackables.get_mut(stack).unwrap().quantity += quantity;
+        std::mem::drop(stackables);
+        ecs.delete_entity(item).expect("Unable to delete");
+        stack
+    } else {
+        item
+    }
+}
+
+/// Takes a single item off a carried stack, leaving the rest behind in the backpack. If no
+/// single copy can be made, the stack is handed back untouched.
+pub fn split_stack(ecs: &mut World, item: Entity) -> Entity {
+    let owner = if let Some(carried) = ecs.read_storage::<InBackpack>().get(item) {
+        carried.owner
+    } else {
+        return item;
+    };
+    let name = if let Some(name) = ecs.read_storage::<Name>().get(item) {
+        name.name.clone()
+    } else {
+        return item;
+    };
+
+    if ecs
+        .read_storage::<Stackable>()
+        .get(item)
+        .is_none_or(|stack| stack.quantity < 2)
+    {
+        return item;
+    }
+
+    // Only take one off the stack once its replacement actually exists
+    let Some(single) = spawn_named_entity(
+        &RAWS.lock().unwrap(),
+        ecs,
+        &name,
+        SpawnType::Carried { by: owner },
+    ) else {
+        return item;
+    };
+    let mut stackables = ecs.write_storage::<Stackable>();
+    stackables.get_mut(item).unwrap().quantity -= 1;
+    if let Some(stack) = stackables.get_mut(single) {
+        stack.quantity = 1;
+    }
+    single
+}
@@ -1,5 +1,11 @@
-use crate::{raws::Reaction, Faction, Map, MyTurn, Position, TileSize, WantsToMelee};
+use super::reactions::{tile_reactions, unseen_entities};
+use super::skirmish_ai_system::equipped_weapon_range;
+use crate::{
+    raws::Reaction, Ammunition, EquipmentSlot, Equipped, Faction, Maneuver, Map, MasterDungeonMap,
+    MyTurn, Position, StatusEffect, TileSize, UsesItems, WantsToManeuver, WantsToMelee, Weapon,
+};
 use specs::prelude::*;
+use std::collections::HashSet;
 
 pub struct AdjacentAI {}
 
@@ -12,17 +18,44 @@ type AdjacentData<'a> = (
     Entities<'a>,
     ReadExpect<'a, Entity>,
     ReadStorage<'a, TileSize>,
+    ReadStorage<'a, Equipped>,
+    ReadStorage<'a, Weapon>,
+    ReadExpect<'a, MasterDungeonMap>,
+    ReadStorage<'a, UsesItems>,
+    WriteStorage<'a, WantsToManeuver>,
+    ReadStorage<'a, Ammunition>,
+    ReadStorage<'a, StatusEffect>,
 );
 
 impl<'a> System<'a> for AdjacentAI {
     type SystemData = AdjacentData<'a>;
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut turns, factions, positions, map, mut want_melee, entities, player, sizes) = data;
+        let (
+            mut turns,
+            factions,
+            positions,
+            map,
+            mut want_melee,
+            entities,
+            player,
+            sizes,
+            equipped,
+            weapons,
+            dm,
+            uses_items,
+            mut want_maneuver,
+            ammunition,
+            statuses,
+        ) = data;
 
+        let unseen = unseen_entities(&statuses);
         let mut turn_done: Vec<Entity> = Vec::new();
         for (entity, _turn, my_faction, pos) in (&entities, &turns, &factions, &positions).join() {
-            if entity != *player {
+            // Ranged combatants would rather back off; SkirmishAI decides
+            if entity != *player
+                && equipped_weapon_range(entity, &equipped, &weapons, &ammunition).is_none()
+            {
                 let mut reactions: Vec<(Entity, Reaction)> = Vec::new();
 
                 let idx = map.xy_idx(pos.x, pos.y);
@@ -39,45 +72,131 @@ impl<'a> System<'a> for AdjacentAI {
                         .for_each(|t| {
                             if t.0 > 0 && t.0 < w - 1 && t.1 > 0 && t.1 < h - 1 {
                                 let target_idx = map.xy_idx(t.0, t.1);
-                                evaluate(target_idx, &factions, &my_faction.name, &mut reactions);
+                                evaluate(
+                                    target_idx,
+                                    &factions,
+                                    &dm,
+                                    &my_faction.name,
+                                    &unseen,
+                                    &mut reactions,
+                                );
                             }
                         });
                 } else {
                     let u = w as usize;
 
                     if pos.x > 0 {
-                        evaluate(idx - 1, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx - 1,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                     if pos.x < w - 1 {
-                        evaluate(idx + 1, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx + 1,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                     if pos.y > 0 {
-                        evaluate(idx - u, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx - u,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                     if pos.y < h - 1 {
-                        evaluate(idx + u, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx + u,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                     if pos.y > 0 && pos.x > 0 {
-                        evaluate(idx - u - 1, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx - u - 1,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                     if pos.y > 0 && pos.x < w - 1 {
-                        evaluate(idx - u + 1, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx - u + 1,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                     if pos.y < h - 1 && pos.x > 0 {
-                        evaluate(idx + u - 1, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx + u - 1,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                     if pos.y < h - 1 && pos.x < w - 1 {
-                        evaluate(idx + u + 1, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx + u + 1,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                 }
 
-                let mut done = false;
+                let mut target = None;
                 for reaction in reactions.iter() {
                     if Reaction::Attack == reaction.1 {
+                        target = Some(reaction.0);
+                    }
+                }
+
+                let done = target.is_some();
+                if let Some(target) = target {
+                    // Cunning fighters sometimes try something dirtier than a plain swing
+                    if uses_items.get(entity).is_some()
+                        && crate::tutorial::rng::roll_dice(1, 6) == 1
+                    {
+                        let armed = (&equipped, &weapons).join().any(|(equip, _)| {
+                            equip.owner == target && equip.slot == EquipmentSlot::Melee
+                        });
+                        let maneuver = if armed {
+                            Maneuver::Disarm
+                        } else if crate::tutorial::rng::roll_dice(1, 2) == 1 {
+                            Maneuver::Shove
+                        } else {
+                            Maneuver::Trip
+                        };
+                        want_maneuver
+                            .insert(entity, WantsToManeuver { target, maneuver })
+                            .expect("Error inserting maneuver");
+                    } else {
                         want_melee
-                            .insert(entity, WantsToMelee { target: reaction.0 })
+                            .insert(entity, WantsToMelee { target })
                             .expect("Error inserting melee");
-                        done = true;
                     }
                 }
 
@@ -97,19 +216,12 @@ impl<'a> System<'a> for AdjacentAI {
 fn evaluate(
     idx: usize,
     factions: &ReadStorage<Faction>,
+    dm: &MasterDungeonMap,
     my_faction: &str,
+    unseen: &HashSet<Entity>,
     reactions: &mut Vec<(Entity, Reaction)>,
 ) {
-    crate::spatial::for_each_tile_content(idx, |other_entity| {
-        if let Some(faction) = factions.get(other_entity) {
-            reactions.push((
-                other_entity,
-                crate::raws::faction_reaction(
-                    my_faction,
-                    &faction.name,
-                    &crate::raws::RAWS.lock().unwrap(),
-                ),
-            ));
-        }
+    tile_reactions(idx, factions, dm, my_faction, unseen, |other, reaction| {
+        reactions.push((other, reaction))
     });
 }
@@ -0,0 +1,199 @@
+use super::reactions::unseen_entities;
+use super::skirmish_ai_system::equipped_weapon_range;
+use crate::{
+    raws::Reaction, Ally, AllyCommand, AllyTarget, Ammunition, ApplyMove, Equipped, Faction, Map,
+    MasterDungeonMap, MyTurn, Pools, Position, StatusEffect, Viewshed, WantsToMelee, WantsToShoot,
+    Weapon,
+};
+use rltk::{DistanceAlg, Point};
+use specs::prelude::*;
+
+// How far a following ally will let the player get before catching up
+const FOLLOW_DISTANCE: f32 = 2.5;
+
+pub struct AllyAI {}
+
+type AllyData<'a> = (
+    WriteStorage<'a, MyTurn>,
+    WriteStorage<'a, Ally>,
+    WriteStorage<'a, AllyTarget>,
+    ReadStorage<'a, Faction>,
+    ReadStorage<'a, Position>,
+    ReadStorage<'a, Pools>,
+    ReadStorage<'a, Viewshed>,
+    ReadExpect<'a, Map>,
+    Entities<'a>,
+    ReadExpect<'a, Entity>,
+    ReadStorage<'a, Equipped>,
+    ReadStorage<'a, Weapon>,
+    WriteStorage<'a, WantsToMelee>,
+    WriteStorage<'a, WantsToShoot>,
+    WriteStorage<'a, ApplyMove>,
+    ReadExpect<'a, MasterDungeonMap>,
+    ReadStorage<'a, Ammunition>,
+    ReadStorage<'a, StatusEffect>,
+);
+
+impl<'a> System<'a> for AllyAI {
+    type SystemData = AllyData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut turns,
+            mut allies,
+            mut ally_targets,
+            factions,
+            positions,
+            pools,
+            viewsheds,
+            map,
+            entities,
+            player,
+            equipped,
+            weapons,
+            mut wants_melee,
+            mut wants_shoot,
+            mut apply_move,
+            dm,
+            ammunition,
+            statuses,
+        ) = data;
+        let unseen = unseen_entities(&statuses);
+
+        // Followers join in on whatever the player is fighting
+        let player_target = if let Some(melee) = wants_melee.get(*player) {
+            Some(melee.target)
+        } else {
+            wants_shoot.get(*player).map(|shoot| shoot.target)
+        };
+
+        // Forget about targets that have died or left the level
+        for (entity, ally) in (&entities, &mut allies).join() {
+            if let Some(target) = ally_targets.get(entity) {
+                let alive = pools
+                    .get(target.target)
+                    .is_some_and(|p| p.hit_points.current > 0);
+                if !alive || positions.get(target.target).is_none() {
+                    ally_targets.remove(entity);
+                    if ally.command == AllyCommand::Attack {
+                        ally.command = AllyCommand::Follow;
+                    }
+                }
+            }
+            if let Some(target) = player_target {
+                if ally.command == AllyCommand::Follow {
+                    ally_targets
+                        .insert(entity, AllyTarget { target })
+                        .expect("Unable to insert");
+                }
+            }
+        }
+
+        let player_pos = if let Some(pos) = positions.get(*player) {
+            Point::new(pos.x, pos.y)
+        } else {
+            return;
+        };
+
+        let mut turn_done = Vec::new();
+        for (entity, ally, _turn, pos, viewshed) in
+            (&entities, &allies, &turns, &positions, &viewsheds).join()
+        {
+            turn_done.push(entity);
+            let my_pos = Point::new(pos.x, pos.y);
+
+            // Pick a fight: the assigned target first, then the nearest enemy of the player
+            let target = if let Some(target) = ally_targets.get(entity) {
+                Some(target.target).filter(|target| !unseen.contains(target))
+            } else {
+                let mut nearest: Option<(Entity, f32)> = None;
+                for tile in viewshed.visible_tiles.iter() {
+                    let distance = DistanceAlg::Pythagoras.distance2d(my_pos, *tile);
+                    if ally.command == AllyCommand::Wait && distance >= 1.5 {
+                        continue;
+                    }
+                    crate::spatial::for_each_tile_content(map.xy_idx(tile.x, tile.y), |other| {
+                        if unseen.contains(&other) {
+                            return;
+                        }
+                        if let Some(faction) = factions.get(other) {
+                            let reaction = dm.faction_reaction(
+                                &faction.name,
+                                "Player",
+                                &crate::raws::RAWS.lock().unwrap(),
+                            );
+                            if reaction == Reaction::Attack
+                                && nearest.is_none_or(|(_, d)| distance < d)
+                            {
+                                nearest = Some((other, distance));
+                            }
+                        }
+                    });
+                }
+                nearest.map(|(other, _)| other)
+            };
+
+            if let Some((target, target_pos)) =
+                target.and_then(|target| positions.get(target).map(|pos| (target, pos)))
+            {
+                let target_pt = Point::new(target_pos.x, target_pos.y);
+                let distance = DistanceAlg::Pythagoras.distance2d(my_pos, target_pt);
+                let range = equipped_weapon_range(entity, &equipped, &weapons, &ammunition);
+
+                if distance < 1.5 {
+                    wants_melee
+                        .insert(entity, WantsToMelee { target })
+                        .expect("Unable to insert");
+                    continue;
+                }
+                if range.is_some_and(|r| distance <= r as f32)
+                    && viewshed.visible_tiles.contains(&target_pt)
+                {
+                    wants_shoot
+                        .insert(entity, WantsToShoot { target })
+                        .expect("Unable to insert");
+                    continue;
+                }
+                if ally.command != AllyCommand::Wait {
+                    step_towards(&map, my_pos, target_pt, entity, &mut apply_move);
+                    continue;
+                }
+            }
+
+            if ally.command != AllyCommand::Wait
+                && DistanceAlg::Pythagoras.distance2d(my_pos, player_pos) > FOLLOW_DISTANCE
+            {
+                step_towards(&map, my_pos, player_pos, entity, &mut apply_move);
+            }
+        }
+
+        // Remove turn marker for those that are done
+        for done in turn_done.iter() {
+            turns.remove(*done);
+        }
+    }
+}
+
+fn step_towards(
+    map: &Map,
+    from: Point,
+    to: Point,
+    entity: Entity,
+    apply_move: &mut WriteStorage<ApplyMove>,
+) {
+    let path = rltk::a_star_search(
+        map.xy_idx(from.x, from.y) as i32,
+        map.xy_idx(to.x, to.y) as i32,
+        map,
+    );
+    if path.success && path.steps.len() > 1 && !crate::spatial::is_blocked(path.steps[1]) {
+        apply_move
+            .insert(
+                entity,
+                ApplyMove {
+                    dest_idx: path.steps[1],
+                },
+            )
+            .expect("Unable to insert");
+    }
+}
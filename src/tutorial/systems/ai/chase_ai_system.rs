@@ -1,4 +1,5 @@
-use crate::{ApplyMove, Chasing, Map, MyTurn, Position, TileSize};
+use super::reactions::unseen_entities;
+use crate::{ApplyMove, Chasing, Map, MyTurn, Position, StatusEffect, TileSize};
 use specs::prelude::*;
 use std::collections::HashMap;
 
@@ -12,19 +13,25 @@ type ChaseData<'a> = (
     Entities<'a>,
     WriteStorage<'a, ApplyMove>,
     ReadStorage<'a, TileSize>,
+    ReadStorage<'a, StatusEffect>,
 );
 
 impl<'a> System<'a> for ChaseAI {
     type SystemData = ChaseData<'a>;
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut turns, mut chasing, positions, map, entities, mut apply_move, sizes) = data;
+        let (mut turns, mut chasing, positions, map, entities, mut apply_move, sizes, statuses) =
+            data;
+        let unseen = unseen_entities(&statuses);
 
         let mut targets = HashMap::new();
         let mut end_chase = Vec::new();
 
         for (entity, _turn, chasing) in (&entities, &turns, &chasing).join() {
-            if let Some(target_pos) = positions.get(chasing.target) {
+            // Lose the trail of anyone who has vanished from sight
+            if unseen.contains(&chasing.target) {
+                end_chase.push(entity);
+            } else if let Some(target_pos) = positions.get(chasing.target) {
                 targets.insert(entity, (target_pos.x, target_pos.y));
             } else {
                 end_chase.push(entity);
@@ -1,6 +1,6 @@
 use crate::{
-    attr_bonus, AttributeBonus, Attributes, EquipmentChanged, Equipped, InBackpack, Item, Pools,
-    Slow, StatusEffect,
+    attr_bonus, AttributeBonus, Attributes, Bag, BurdenLevel, Durability, EquipmentChanged,
+    Equipped, InBackpack, Item, ItemCondition, Pools, Slow, Stackable, StatusEffect,
 };
 use specs::prelude::*;
 use std::collections::HashMap;
@@ -19,6 +19,9 @@ type EncumbranceData<'a> = (
     ReadStorage<'a, AttributeBonus>,
     ReadStorage<'a, StatusEffect>,
     ReadStorage<'a, Slow>,
+    ReadStorage<'a, Stackable>,
+    ReadStorage<'a, Bag>,
+    ReadStorage<'a, Durability>,
 );
 
 impl<'a> System<'a> for EncumbranceSystem {
@@ -37,6 +40,9 @@ impl<'a> System<'a> for EncumbranceSystem {
             attrbonus,
             statuses,
             slowed,
+            stackables,
+            bags,
+            durabilities,
         ) = data;
 
         if equip_dirty.is_empty() {
@@ -51,6 +57,7 @@ impl<'a> System<'a> for EncumbranceSystem {
             fitness: i32,
             quickness: i32,
             intelligence: i32,
+            bag_capacity: i32,
         }
 
         // Build the  map of who needs updating
@@ -67,7 +74,10 @@ impl<'a> System<'a> for EncumbranceSystem {
                 let totals = to_update.get_mut(&equipped.owner).unwrap();
                 totals.weight += item.weight_lbs;
                 totals.initiative += item.initiative_penalty;
-                if let Some(attr) = attrbonus.get(entity) {
+                let broken = durabilities
+                    .get(entity)
+                    .is_some_and(|d| d.condition() == ItemCondition::Broken);
+                if let (Some(attr), false) = (attrbonus.get(entity), broken) {
                     totals.might += attr.might.unwrap_or(0);
                     totals.fitness += attr.fitness.unwrap_or(0);
                     totals.quickness += attr.quickness.unwrap_or(0);
@@ -76,11 +86,16 @@ impl<'a> System<'a> for EncumbranceSystem {
             }
         }
 
-        for (item, carried) in (&items, &backpacks).join() {
+        for (entity, item, carried) in (&entities, &items, &backpacks).join() {
             if to_update.contains_key(&carried.owner) {
                 let totals = to_update.get_mut(&carried.owner).unwrap();
-                totals.weight += item.weight_lbs;
+                let quantity = stackables.get(entity).map_or(1, |s| s.quantity);
+                totals.weight += item.weight_lbs * quantity as f32;
                 totals.initiative += item.initiative_penalty;
+                // Only the roomiest bag counts; bags don't nest
+                if let Some(bag) = bags.get(entity) {
+                    totals.bag_capacity = i32::max(totals.bag_capacity, bag.capacity_lbs);
+                }
             }
         }
 
@@ -119,17 +134,26 @@ impl<'a> System<'a> for EncumbranceSystem {
                         attr_bonus(attr.quickness.base + attr.quickness.modifiers);
                     attr.intelligence.bonus =
                         attr_bonus(attr.intelligence.base + attr.intelligence.modifiers);
-                    let carry_capacity_lbs = (attr.might.base + attr.might.modifiers) * 15;
-                    if pool.total_weight as i32 > carry_capacity_lbs {
-                        // Overburdened
-                        pool.total_initiative_penalty += 4.0;
-                        if *entity == *player {
-                            crate::gamelog::Logger::new()
-                                .color(rltk::ORANGE)
-                                .append("You are overburdened, and suffering an initiative penalty")
-                                .log();
-                        }
+                    pool.carry_capacity_lbs =
+                        (attr.might.base + attr.might.modifiers) * 15 + item.bag_capacity;
+
+                    let burden =
+                        BurdenLevel::from_weight(pool.total_weight, pool.carry_capacity_lbs);
+                    pool.total_initiative_penalty += burden.initiative_penalty();
+                    if burden != pool.burden && *entity == *player {
+                        let message = if burden > pool.burden {
+                            format!("You are now {}.", burden.name().to_lowercase())
+                        } else if burden == BurdenLevel::Unburdened {
+                            "Your load no longer slows you down.".to_string()
+                        } else {
+                            format!("Your load eases; you are {}.", burden.name().to_lowercase())
+                        };
+                        crate::gamelog::Logger::new()
+                            .color(rltk::ORANGE)
+                            .append(message)
+                            .log();
                     }
+                    pool.burden = burden;
                 }
             }
         }
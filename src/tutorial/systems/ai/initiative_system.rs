@@ -1,7 +1,4 @@
-use crate::{
-    Attributes, DamageOverTime, Duration, EquipmentChanged, Initiative, MyTurn, Pools, Position,
-    RunState, StatusEffect,
-};
+use crate::{Attributes, Initiative, MyTurn, Pools, Position, RunState};
 use rltk::Point;
 use specs::prelude::*;
 
@@ -17,10 +14,6 @@ type InitiativeData<'a> = (
     ReadExpect<'a, Entity>,
     ReadExpect<'a, Point>,
     ReadStorage<'a, Pools>,
-    WriteStorage<'a, Duration>,
-    WriteStorage<'a, EquipmentChanged>,
-    ReadStorage<'a, StatusEffect>,
-    ReadStorage<'a, DamageOverTime>,
 );
 
 impl<'a> System<'a> for InitiativeSystem {
@@ -37,10 +30,6 @@ impl<'a> System<'a> for InitiativeSystem {
             player,
             player_pos,
             pools,
-            mut durations,
-            mut dirty,
-            statuses,
-            dots,
         ) = data;
 
         if *runstate != RunState::Ticking {
@@ -89,30 +78,5 @@ impl<'a> System<'a> for InitiativeSystem {
                 }
             }
         }
-
-        // Handle durations
-        if *runstate == RunState::AwaitingInput {
-            use crate::effects::*;
-            for (effect_entity, duration, status) in (&entities, &mut durations, &statuses).join() {
-                if entities.is_alive(status.target) {
-                    duration.turns -= 1;
-                    if let Some(dot) = dots.get(effect_entity) {
-                        add_effect(
-                            None,
-                            EffectType::Damage { amount: dot.damage },
-                            Targets::Single {
-                                target: status.target,
-                            },
-                        );
-                    }
-                    if duration.turns < 1 {
-                        dirty
-                            .insert(status.target, EquipmentChanged {})
-                            .expect("Unable to insert");
-                        entities.delete(effect_entity).expect("Unable to delete");
-                    }
-                }
-            }
-        }
     }
 }
@@ -0,0 +1,384 @@
+use super::reactions::{tile_reactions, unseen_entities};
+use crate::{
+    raws::Reaction, Ammunition, BurdenLevel, Equippable, Equipped, Faction, InBackpack, Item, Map,
+    MasterDungeonMap, MyTurn, Name, Pools, Position, ProvidesHealing, Ranged, Stackable,
+    StatusEffect, UsesItems, Viewshed, WantsToApproach, WantsToPickupItem, WantsToUseItem, Weapon,
+    Wearable,
+};
+use rltk::{DistanceAlg, Point};
+use specs::prelude::*;
+
+pub struct ItemAI {}
+
+type ItemAIData<'a> = (
+    WriteStorage<'a, MyTurn>,
+    ReadStorage<'a, UsesItems>,
+    ReadStorage<'a, Faction>,
+    ReadStorage<'a, Position>,
+    ReadStorage<'a, Pools>,
+    ReadStorage<'a, Viewshed>,
+    ReadExpect<'a, Map>,
+    Entities<'a>,
+    ReadExpect<'a, Entity>,
+    ReadStorage<'a, Item>,
+    ReadStorage<'a, InBackpack>,
+    ReadStorage<'a, Equipped>,
+    ReadStorage<'a, Equippable>,
+    ReadStorage<'a, Weapon>,
+    ReadStorage<'a, Wearable>,
+    ReadStorage<'a, ProvidesHealing>,
+    ReadStorage<'a, Ranged>,
+    WriteStorage<'a, WantsToUseItem>,
+    WriteStorage<'a, WantsToPickupItem>,
+    WriteStorage<'a, WantsToApproach>,
+    ReadExpect<'a, MasterDungeonMap>,
+    ReadStorage<'a, Name>,
+    ReadStorage<'a, Stackable>,
+    ReadStorage<'a, StatusEffect>,
+    ReadStorage<'a, Ammunition>,
+);
+
+impl<'a> System<'a> for ItemAI {
+    type SystemData = ItemAIData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut turns,
+            uses_items,
+            factions,
+            positions,
+            pools,
+            viewsheds,
+            map,
+            entities,
+            player,
+            items,
+            backpack,
+            equipped,
+            equippable,
+            weapons,
+            wearables,
+            healing,
+            ranged,
+            mut wants_use,
+            mut wants_pickup,
+            mut want_approach,
+            dm,
+            names,
+            stackables,
+            statuses,
+            ammunition,
+        ) = data;
+        let unseen = unseen_entities(&statuses);
+
+        let mut turn_done = Vec::new();
+
+        for (entity, _turn, _uses, my_faction, pos, stats, viewshed) in (
+            &entities,
+            &turns,
+            &uses_items,
+            &factions,
+            &positions,
+            &pools,
+            &viewsheds,
+        )
+            .join()
+        {
+            if entity == *player {
+                continue;
+            }
+
+            let carried: Vec<Entity> = (&entities, &backpack)
+                .join()
+                .filter(|(_, pack)| pack.owner == entity)
+                .map(|(item, _)| item)
+                .collect();
+
+            // Is there anything here worth fighting or running from?
+            let mut hostiles = Vec::new();
+            let mut threatened = false;
+            for tile in viewshed.visible_tiles.iter() {
+                let idx = map.xy_idx(tile.x, tile.y);
+                tile_reactions(
+                    idx,
+                    &factions,
+                    &dm,
+                    &my_faction.name,
+                    &unseen,
+                    |other, reaction| {
+                        if other == entity {
+                            return;
+                        }
+                        match reaction {
+                            Reaction::Attack => {
+                                hostiles.push(*tile);
+                                threatened = true;
+                            }
+                            Reaction::Flee => threatened = true,
+                            Reaction::Ignore => {}
+                        }
+                    },
+                );
+            }
+
+            // Quaff a healing potion when badly hurt
+            if stats.hit_points.current < stats.hit_points.max / 2 {
+                if let Some(potion) = carried.iter().find(|item| healing.get(**item).is_some()) {
+                    wants_use
+                        .insert(
+                            entity,
+                            WantsToUseItem {
+                                item: *potion,
+                                target: None,
+                            },
+                        )
+                        .expect("Unable to insert");
+                    turn_done.push(entity);
+                    continue;
+                }
+            }
+
+            // Read a scroll (or zap a rod) at the closest enemy in range
+            let my_pos = Point::new(pos.x, pos.y);
+            let mut used_ranged = false;
+            for item in carried.iter() {
+                if let Some(range) = ranged.get(*item) {
+                    let target = hostiles
+                        .iter()
+                        .map(|pt| (*pt, DistanceAlg::Pythagoras.distance2d(my_pos, *pt)))
+                        .filter(|(_, distance)| *distance <= range.range as f32)
+                        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                    if let Some((target, _)) = target {
+                        if crate::tutorial::rng::roll_dice(1, 2) == 1 {
+                            wants_use
+                                .insert(
+                                    entity,
+                                    WantsToUseItem {
+                                        item: *item,
+                                        target: Some(target),
+                                    },
+                                )
+                                .expect("Unable to insert");
+                            used_ranged = true;
+                        }
+                        break;
+                    }
+                }
+            }
+            if used_ranged {
+                turn_done.push(entity);
+                continue;
+            }
+
+            // Stop to reload when the quiver runs dry and there is more in the pack
+            if let Some(ammo) = reload(entity, &carried, &equipped, &weapons, &ammunition) {
+                wants_use
+                    .insert(
+                        entity,
+                        WantsToUseItem {
+                            item: ammo,
+                            target: None,
+                        },
+                    )
+                    .expect("Unable to insert");
+                turn_done.push(entity);
+                continue;
+            }
+
+            // Wear anything better than what we currently have on
+            if let Some(upgrade) = carried.iter().find(|item| {
+                is_upgrade(
+                    entity,
+                    **item,
+                    &entities,
+                    &equipped,
+                    &equippable,
+                    &weapons,
+                    &wearables,
+                )
+            }) {
+                wants_use
+                    .insert(
+                        entity,
+                        WantsToUseItem {
+                            item: *upgrade,
+                            target: None,
+                        },
+                    )
+                    .expect("Unable to insert");
+                turn_done.push(entity);
+                continue;
+            }
+
+            if threatened {
+                continue;
+            }
+
+            // Look around for something worth picking up, and grab it or go and get it
+            let wanted = (&entities, &items, &positions)
+                .join()
+                .filter(|(item, item_info, item_pos)| {
+                    // Anything that would take them past what they can haul stays where it is
+                    let quantity = stackables.get(*item).map_or(1, |s| s.quantity);
+                    let fits = stats.total_weight + item_info.weight_lbs * quantity as f32
+                        <= BurdenLevel::hard_cap(stats.carry_capacity_lbs);
+                    fits && viewshed
+                        .visible_tiles
+                        .contains(&Point::new(item_pos.x, item_pos.y))
+                        && !already_carried(*item, &carried, &names, &stackables)
+                        && (healing.get(*item).is_some()
+                            || ranged.get(*item).is_some()
+                            || is_upgrade(
+                                entity,
+                                *item,
+                                &entities,
+                                &equipped,
+                                &equippable,
+                                &weapons,
+                                &wearables,
+                            ))
+                })
+                .map(|(item, _, item_pos)| {
+                    let item_pt = Point::new(item_pos.x, item_pos.y);
+                    (
+                        item,
+                        item_pt,
+                        DistanceAlg::Pythagoras.distance2d(my_pos, item_pt),
+                    )
+                })
+                .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+
+            if let Some((item, item_pt, _)) = wanted {
+                if item_pt == my_pos {
+                    wants_pickup
+                        .insert(
+                            entity,
+                            WantsToPickupItem {
+                                collected_by: entity,
+                                item,
+                            },
+                        )
+                        .expect("Unable to insert");
+                    turn_done.push(entity);
+                } else {
+                    // ApproachAI takes the turn from here
+                    want_approach
+                        .insert(
+                            entity,
+                            WantsToApproach {
+                                idx: map.xy_idx(item_pt.x, item_pt.y) as i32,
+                            },
+                        )
+                        .expect("Unable to insert");
+                }
+            }
+        }
+
+        // Remove turn marker for those that are done
+        for done in turn_done.iter() {
+            turns.remove(*done);
+        }
+    }
+}
+
+/// Is this a second copy of something already in the backpack? Stacks are always worth topping up.
+fn already_carried(
+    item: Entity,
+    carried: &[Entity],
+    names: &ReadStorage<Name>,
+    stackables: &ReadStorage<Stackable>,
+) -> bool {
+    if stackables.get(item).is_some() {
+        return false;
+    }
+    let Some(name) = names.get(item) else {
+        return false;
+    };
+    carried.iter().any(|other| {
+        names
+            .get(*other)
+            .is_some_and(|other| other.name == name.name)
+    })
+}
+
+/// Ammunition from the backpack for an equipped weapon whose ammo slot has run empty
+fn reload(
+    owner: Entity,
+    carried: &[Entity],
+    equipped: &ReadStorage<Equipped>,
+    weapons: &ReadStorage<Weapon>,
+    ammunition: &ReadStorage<Ammunition>,
+) -> Option<Entity> {
+    let ammo_type = (equipped, weapons)
+        .join()
+        .filter(|(equip, _)| equip.owner == owner)
+        .find_map(|(_, weapon)| weapon.ammo)?;
+    let loaded = (equipped, ammunition)
+        .join()
+        .any(|(equip, ammo)| equip.owner == owner && ammo.ammo_type == ammo_type);
+    if loaded {
+        return None;
+    }
+    carried.iter().copied().find(|item| {
+        ammunition
+            .get(*item)
+            .is_some_and(|ammo| ammo.ammo_type == ammo_type)
+    })
+}
+
+/// Would equipping this item improve on whatever the owner has in that slot?
+fn is_upgrade(
+    owner: Entity,
+    item: Entity,
+    entities: &Entities,
+    equipped: &ReadStorage<Equipped>,
+    equippable: &ReadStorage<Equippable>,
+    weapons: &ReadStorage<Weapon>,
+    wearables: &ReadStorage<Wearable>,
+) -> bool {
+    let slot = if let Some(can_equip) = equippable.get(item) {
+        can_equip.slot
+    } else {
+        return false;
+    };
+
+    let mut best_current = None;
+    for (current, equip) in (entities, equipped).join() {
+        if current == item {
+            return false;
+        }
+        if equip.owner == owner && equip.slot == slot {
+            // Archers don't trade their bows for swords, and vice versa
+            if let (Some(new_weapon), Some(old_weapon)) = (weapons.get(item), weapons.get(current))
+            {
+                if new_weapon.range.is_some() != old_weapon.range.is_some() {
+                    return false;
+                }
+            }
+            let score = item_score(current, weapons, wearables);
+            if best_current.is_none_or(|best| score > best) {
+                best_current = Some(score);
+            }
+        }
+    }
+
+    match best_current {
+        None => true,
+        Some(best) => item_score(item, weapons, wearables) > best,
+    }
+}
+
+fn item_score(
+    item: Entity,
+    weapons: &ReadStorage<Weapon>,
+    wearables: &ReadStorage<Wearable>,
+) -> f32 {
+    if let Some(weapon) = weapons.get(item) {
+        weapon.damage_n_dice as f32 * (weapon.damage_die_type as f32 + 1.0) / 2.0
+            + (weapon.damage_bonus + weapon.hit_bonus) as f32
+    } else if let Some(wearable) = wearables.get(item) {
+        wearable.armor_class
+    } else {
+        0.0
+    }
+}
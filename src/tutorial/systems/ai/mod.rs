@@ -1,6 +1,9 @@
 pub mod adjacent_ai_system;
 pub use adjacent_ai_system::AdjacentAI;
 
+pub mod ally_ai_system;
+pub use ally_ai_system::AllyAI;
+
 pub mod approach_ai_system;
 pub use approach_ai_system::ApproachAI;
 
@@ -19,9 +22,23 @@ pub use flee_ai_system::FleeAI;
 pub mod initiative_system;
 pub use initiative_system::InitiativeSystem;
 
+pub mod item_ai_system;
+pub use item_ai_system::ItemAI;
+
 pub mod quipping;
 pub use quipping::QuipSystem;
 
+pub mod reactions;
+
+pub mod schedule_ai_system;
+pub use schedule_ai_system::ScheduleAI;
+
+pub mod skirmish_ai_system;
+pub use skirmish_ai_system::SkirmishAI;
+
+pub mod status_tick_system;
+pub use status_tick_system::StatusTickSystem;
+
 pub mod turn_status;
 pub use turn_status::*;
 
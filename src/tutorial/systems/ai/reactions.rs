@@ -0,0 +1,39 @@
+use crate::{raws::Reaction, Faction, MasterDungeonMap, StatusEffect, StatusKind};
+use specs::prelude::*;
+use std::collections::HashSet;
+
+/// Everyone nobody can see right now
+pub fn unseen_entities(statuses: &ReadStorage<StatusEffect>) -> HashSet<Entity> {
+    statuses
+        .join()
+        .filter(|status| status.kind == StatusKind::Invisible)
+        .map(|status| status.target)
+        .collect()
+}
+
+/// How `my_faction` feels about each creature on a tile. This is where every AI looks for
+/// someone to fight or run from, so the invisible are left out here and nowhere else.
+pub fn tile_reactions(
+    idx: usize,
+    factions: &ReadStorage<Faction>,
+    dm: &MasterDungeonMap,
+    my_faction: &str,
+    unseen: &HashSet<Entity>,
+    mut react: impl FnMut(Entity, Reaction),
+) {
+    crate::spatial::for_each_tile_content(idx, |other_entity| {
+        if unseen.contains(&other_entity) {
+            return;
+        }
+        if let Some(faction) = factions.get(other_entity) {
+            react(
+                other_entity,
+                dm.faction_reaction(
+                    my_faction,
+                    &faction.name,
+                    &crate::raws::RAWS.lock().unwrap(),
+                ),
+            );
+        }
+    });
+}
@@ -0,0 +1,54 @@
+use crate::{Map, MasterDungeonMap, MyTurn, Position, Schedule, WantsToApproach, WantsToFlee};
+use specs::prelude::*;
+
+pub struct ScheduleAI {}
+
+type ScheduleData<'a> = (
+    WriteStorage<'a, MyTurn>,
+    ReadStorage<'a, Schedule>,
+    ReadStorage<'a, Position>,
+    ReadExpect<'a, Map>,
+    ReadExpect<'a, MasterDungeonMap>,
+    Entities<'a>,
+    WriteStorage<'a, WantsToApproach>,
+    ReadStorage<'a, WantsToFlee>,
+);
+
+impl<'a> System<'a> for ScheduleAI {
+    type SystemData = ScheduleData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut turns, schedules, positions, map, dm, entities, mut want_approach, want_flee) =
+            data;
+
+        let night = dm.is_night();
+        let mut turn_done = Vec::new();
+
+        for (entity, schedule, pos, _turn) in (&entities, &schedules, &positions, &turns).join() {
+            // Running away from trouble, or already off somewhere, comes first
+            if want_flee.get(entity).is_some() || want_approach.get(entity).is_some() {
+                continue;
+            }
+
+            let (x, y) = if night { schedule.home } else { schedule.work };
+            let dest = map.xy_idx(x, y);
+            // Somebody (or some furniture) is already there: next to it will do
+            let close_enough = crate::spatial::is_blocked(dest)
+                && i32::abs(pos.x - x) <= 1
+                && i32::abs(pos.y - y) <= 1;
+            if (pos.x == x && pos.y == y) || close_enough {
+                turn_done.push(entity);
+            } else {
+                // ApproachAI walks them there
+                want_approach
+                    .insert(entity, WantsToApproach { idx: dest as i32 })
+                    .expect("Unable to insert");
+            }
+        }
+
+        // Remove turn marker for those that are done
+        for done in turn_done.iter() {
+            turns.remove(*done);
+        }
+    }
+}
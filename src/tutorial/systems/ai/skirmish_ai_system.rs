@@ -0,0 +1,172 @@
+use crate::{
+    tile_opaque, tile_walkable, Ammunition, ApplyMove, Equipped, Map, MyTurn, Position, Viewshed,
+    WantsToMelee, WantsToShoot, WantsToSkirmish, Weapon,
+};
+use rltk::{BaseMap, DistanceAlg, Point};
+use specs::prelude::*;
+
+// Ranged combatants would rather not be closer than this to their target
+const MIN_SKIRMISH_RANGE: f32 = 2.0;
+
+pub struct SkirmishAI {}
+
+type SkirmishData<'a> = (
+    WriteStorage<'a, MyTurn>,
+    WriteStorage<'a, WantsToSkirmish>,
+    ReadStorage<'a, Position>,
+    ReadExpect<'a, Map>,
+    Entities<'a>,
+    WriteStorage<'a, ApplyMove>,
+    WriteStorage<'a, WantsToShoot>,
+    WriteStorage<'a, WantsToMelee>,
+    ReadStorage<'a, Viewshed>,
+);
+
+impl<'a> System<'a> for SkirmishAI {
+    type SystemData = SkirmishData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut turns,
+            mut want_skirmish,
+            positions,
+            map,
+            entities,
+            mut apply_move,
+            mut wants_shoot,
+            mut wants_melee,
+            viewsheds,
+        ) = data;
+
+        let mut turn_done = Vec::new();
+
+        for (entity, pos, skirmish, viewshed, _myturn) in
+            (&entities, &positions, &want_skirmish, &viewsheds, &turns).join()
+        {
+            turn_done.push(entity);
+
+            let target_pos = if let Some(target_pos) = positions.get(skirmish.target) {
+                Point::new(target_pos.x, target_pos.y)
+            } else {
+                continue;
+            };
+
+            // Score where we are standing against every tile we could step to, and
+            // only move if one of them is a better place to shoot from.
+            let my_idx = map.xy_idx(pos.x, pos.y);
+            let current_score =
+                tile_score(&map, my_idx, target_pos, skirmish.range, viewshed.range);
+            let mut best: Option<(usize, i32)> = None;
+            for (dest, _cost) in map.get_available_exits(my_idx).iter() {
+                if crate::spatial::is_blocked(*dest) {
+                    continue;
+                }
+                let score = tile_score(&map, *dest, target_pos, skirmish.range, viewshed.range);
+                if score > current_score && (best.is_none() || score > best.unwrap().1) {
+                    best = Some((*dest, score));
+                }
+            }
+
+            if let Some((dest_idx, _)) = best {
+                apply_move
+                    .insert(entity, ApplyMove { dest_idx })
+                    .expect("Unable to insert");
+            } else {
+                let distance =
+                    DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), target_pos);
+                if distance < 1.5 {
+                    // Cornered: fight back with whatever is at hand
+                    wants_melee
+                        .insert(
+                            entity,
+                            WantsToMelee {
+                                target: skirmish.target,
+                            },
+                        )
+                        .expect("Unable to insert");
+                } else if distance <= skirmish.range as f32
+                    && viewshed.visible_tiles.contains(&target_pos)
+                {
+                    wants_shoot
+                        .insert(
+                            entity,
+                            WantsToShoot {
+                                target: skirmish.target,
+                            },
+                        )
+                        .expect("Unable to insert");
+                }
+            }
+        }
+
+        want_skirmish.clear();
+
+        // Remove turn marker for those that are done
+        for done in turn_done.iter() {
+            turns.remove(*done);
+        }
+    }
+}
+
+/// How far an entity can shoot, if it has a ranged weapon and something to load it with
+pub fn equipped_weapon_range(
+    entity: Entity,
+    equipped: &ReadStorage<Equipped>,
+    weapons: &ReadStorage<Weapon>,
+    ammunition: &ReadStorage<Ammunition>,
+) -> Option<i32> {
+    let weapon = (equipped, weapons)
+        .join()
+        .filter(|(equip, _)| equip.owner == entity)
+        .find(|(_, weapon)| weapon.range.is_some())
+        .map(|(_, weapon)| weapon)?;
+
+    // An archer with an empty quiver is just another brawler
+    if let Some(ammo_type) = weapon.ammo {
+        let loaded = (equipped, ammunition)
+            .join()
+            .any(|(equip, ammo)| equip.owner == entity && ammo.ammo_type == ammo_type);
+        if !loaded {
+            return None;
+        }
+    }
+    weapon.range
+}
+
+fn tile_score(map: &Map, idx: usize, target: Point, range: i32, sight: i32) -> i32 {
+    let x = idx as i32 % map.width;
+    let y = idx as i32 / map.width;
+    let distance = DistanceAlg::Pythagoras.distance2d(Point::new(x, y), target);
+
+    let mut score = if distance < MIN_SKIRMISH_RANGE {
+        -((MIN_SKIRMISH_RANGE - distance) * 10.0) as i32
+    } else if distance > range as f32 {
+        -((distance - range as f32) * 10.0) as i32
+    } else {
+        20
+    };
+
+    if can_see_from(map, Point::new(x, y), target, sight) {
+        score += 10;
+    }
+
+    // Walls next to the tile give some cover
+    let mut walls = 0;
+    for ty in y - 1..=y + 1 {
+        for tx in x - 1..=x + 1 {
+            if tx >= 0 && tx < map.width && ty >= 0 && ty < map.height {
+                let tt = map.tiles[map.xy_idx(tx, ty)];
+                if !tile_walkable(tt) || tile_opaque(tt) {
+                    walls += 1;
+                }
+            }
+        }
+    }
+    score + i32::min(walls, 3) * 2
+}
+
+/// Would a viewshed standing on `from` take in `to`? Shots are only taken at what the
+/// shooter can see, so this has to agree with the visibility system rather than a plain line.
+fn can_see_from(map: &Map, from: Point, to: Point, sight: i32) -> bool {
+    rltk::field_of_view(from, sight, map).contains(&to)
+}
@@ -0,0 +1,79 @@
+use crate::effects::*;
+use crate::{
+    DamageOverTime, Duration, EquipmentChanged, RunState, StatusEffect, StatusHooks, TrueForm,
+};
+use specs::prelude::*;
+
+pub struct StatusTickSystem {}
+
+type StatusTickData<'a> = (
+    Entities<'a>,
+    ReadExpect<'a, RunState>,
+    WriteStorage<'a, Duration>,
+    WriteStorage<'a, EquipmentChanged>,
+    ReadStorage<'a, StatusEffect>,
+    ReadStorage<'a, DamageOverTime>,
+    ReadStorage<'a, StatusHooks>,
+    ReadStorage<'a, TrueForm>,
+);
+
+impl<'a> System<'a> for StatusTickSystem {
+    type SystemData = StatusTickData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, runstate, mut durations, mut dirty, statuses, dots, hooks, true_forms) =
+            data;
+
+        // Statuses count down once per player turn
+        if *runstate != RunState::AwaitingInput {
+            return;
+        }
+
+        for (effect_entity, duration, status) in (&entities, &mut durations, &statuses).join() {
+            if !entities.is_alive(status.target) {
+                continue;
+            }
+            let target = Targets::Single {
+                target: status.target,
+            };
+
+            duration.turns -= 1;
+            if let Some(dot) = dots.get(effect_entity) {
+                add_effect(
+                    None,
+                    EffectType::Damage {
+                        amount: dot.damage,
+                        damage_type: Some(dot.damage_type),
+                    },
+                    target.clone(),
+                );
+            }
+            if let Some(node) = hooks.get(effect_entity).and_then(|h| h.on_tick.as_ref()) {
+                add_effect(
+                    None,
+                    EffectType::Script { node: node.clone() },
+                    target.clone(),
+                );
+            }
+
+            if duration.turns < 1 {
+                if let Some(node) = hooks.get(effect_entity).and_then(|h| h.on_expire.as_ref()) {
+                    add_effect(
+                        None,
+                        EffectType::Script { node: node.clone() },
+                        target.clone(),
+                    );
+                }
+                // A polymorph has a form to give back; reverting clears the status itself
+                if true_forms.get(effect_entity).is_some() {
+                    add_effect(None, EffectType::RevertForm, target);
+                    continue;
+                }
+                dirty
+                    .insert(status.target, EquipmentChanged {})
+                    .expect("Unable to insert");
+                entities.delete(effect_entity).expect("Unable to delete");
+            }
+        }
+    }
+}
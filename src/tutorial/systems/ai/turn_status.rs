@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::{add_effect, Confusion, EffectType, MyTurn, RunState, StatusEffect, Targets};
+use crate::{add_effect, Confusion, EffectType, MyTurn, RunState, StatusEffect, Stunned, Targets};
 use specs::prelude::*;
 
 pub struct TurnStatusSystem {}
@@ -12,10 +12,11 @@ impl<'a> System<'a> for TurnStatusSystem {
         Entities<'a>,
         ReadExpect<'a, RunState>,
         ReadStorage<'a, StatusEffect>,
+        ReadStorage<'a, Stunned>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut turns, confusion, entities, runstate, statuses) = data;
+        let (mut turns, confusion, entities, runstate, statuses, stunned) = data;
 
         if *runstate != RunState::Ticking {
             return;
@@ -47,6 +48,22 @@ impl<'a> System<'a> for TurnStatusSystem {
                     );
                     not_my_turn.push(status_effect.target);
                 }
+                // Stunned creatures lose their turn picking themselves back up
+                if stunned.get(effect_entity).is_some() {
+                    add_effect(
+                        None,
+                        EffectType::Particle {
+                            glyph: rltk::to_cp437('*'),
+                            fg: rltk::RGB::named(rltk::YELLOW),
+                            bg: rltk::RGB::named(rltk::BLACK),
+                            lifespan: 200.0,
+                        },
+                        Targets::Single {
+                            target: status_effect.target,
+                        },
+                    );
+                    not_my_turn.push(status_effect.target);
+                }
             }
         }
 
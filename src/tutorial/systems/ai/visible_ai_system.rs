@@ -1,8 +1,12 @@
+use super::reactions::{tile_reactions, unseen_entities};
+use super::skirmish_ai_system::equipped_weapon_range;
 use crate::{
-    raws::Reaction, Chasing, Equipped, Faction, Map, MyTurn, Name, Position, SpecialAbilities,
-    SpellTemplate, Viewshed, WantsToApproach, WantsToCastSpell, WantsToFlee, WantsToShoot, Weapon,
+    raws::Reaction, Ammunition, Chasing, Equipped, Faction, Map, MasterDungeonMap, MyTurn, Name,
+    Position, SpecialAbilities, SpellTemplate, StatusEffect, Viewshed, WantsToApproach,
+    WantsToCastSpell, WantsToFlee, WantsToSkirmish, Weapon,
 };
 use specs::prelude::*;
+use std::collections::HashSet;
 
 pub struct VisibleAI {}
 
@@ -23,7 +27,10 @@ type VisibleData<'a> = (
     ReadStorage<'a, SpellTemplate>,
     ReadStorage<'a, Equipped>,
     ReadStorage<'a, Weapon>,
-    WriteStorage<'a, WantsToShoot>,
+    WriteStorage<'a, WantsToSkirmish>,
+    ReadExpect<'a, MasterDungeonMap>,
+    ReadStorage<'a, Ammunition>,
+    ReadStorage<'a, StatusEffect>,
 );
 
 impl<'a> System<'a> for VisibleAI {
@@ -47,9 +54,14 @@ impl<'a> System<'a> for VisibleAI {
             spells,
             equipped,
             weapons,
-            mut wants_shoot,
+            mut wants_skirmish,
+            dm,
+            ammunition,
+            statuses,
         ) = data;
 
+        let unseen = unseen_entities(&statuses);
+
         for (entity, _turn, my_faction, pos, viewshed) in
             (&entities, &turns, &factions, &positions, &viewsheds).join()
         {
@@ -61,7 +73,14 @@ impl<'a> System<'a> for VisibleAI {
                 for visible_tile in viewshed.visible_tiles.iter() {
                     let idx = map.xy_idx(visible_tile.x, visible_tile.y);
                     if my_idx != idx {
-                        evaluate(idx, &factions, &my_faction.name, &mut reactions);
+                        evaluate(
+                            idx,
+                            &factions,
+                            &dm,
+                            &my_faction.name,
+                            &unseen,
+                            &mut reactions,
+                        );
                     }
                 }
 
@@ -108,14 +127,20 @@ impl<'a> System<'a> for VisibleAI {
                             }
 
                             if !done {
-                                for (weapon, equip) in (&weapons, &equipped).join() {
-                                    if let Some(wrange) = weapon.range {
-                                        if equip.owner == entity && wrange >= range as i32 {
-                                            wants_shoot
-                                                .insert(entity, WantsToShoot { target: reaction.2 })
-                                                .expect("Insert fail");
-                                            done = true;
-                                        }
+                                if let Some(wrange) =
+                                    equipped_weapon_range(entity, &equipped, &weapons, &ammunition)
+                                {
+                                    if wrange >= range as i32 {
+                                        wants_skirmish
+                                            .insert(
+                                                entity,
+                                                WantsToSkirmish {
+                                                    target: reaction.2,
+                                                    range: wrange,
+                                                },
+                                            )
+                                            .expect("Insert fail");
+                                        done = true;
                                     }
                                 }
                             }
@@ -155,20 +180,12 @@ impl<'a> System<'a> for VisibleAI {
 fn evaluate(
     idx: usize,
     factions: &ReadStorage<Faction>,
+    dm: &MasterDungeonMap,
     my_faction: &str,
+    unseen: &HashSet<Entity>,
     reactions: &mut Vec<(usize, Reaction, Entity)>,
 ) {
-    crate::spatial::for_each_tile_content(idx, |other_entity| {
-        if let Some(faction) = factions.get(other_entity) {
-            reactions.push((
-                idx,
-                crate::raws::faction_reaction(
-                    my_faction,
-                    &faction.name,
-                    &crate::raws::RAWS.lock().unwrap(),
-                ),
-                other_entity,
-            ));
-        }
+    tile_reactions(idx, factions, dm, my_faction, unseen, |other, reaction| {
+        reactions.push((idx, reaction, other))
     });
 }
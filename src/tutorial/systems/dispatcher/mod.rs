@@ -24,10 +24,18 @@ construct_dispatcher!(
     (VisibilitySystem, "visibility", &[]),
     (EncumbranceSystem, "encumbrance", &[]),
     (InitiativeSystem, "initiative", &[]),
+    (StatusTickSystem, "status_ticks", &[]),
+    (HazardSystem, "hazards", &[]),
+    (SummonSystem, "summons", &[]),
+    (RegenerationSystem, "regeneration", &[]),
     (TurnStatusSystem, "turnstatus", &[]),
     (QuipSystem, "quips", &[]),
+    (AllyAI, "ally", &[]),
+    (ItemAI, "item_ai", &[]),
     (AdjacentAI, "adjacent", &[]),
     (VisibleAI, "visible", &[]),
+    (SkirmishAI, "skirmish", &[]),
+    (ScheduleAI, "schedule", &[]),
     (ApproachAI, "approach", &[]),
     (FleeAI, "flee", &[]),
     (ChaseAI, "chase", &[]),
@@ -35,6 +43,7 @@ construct_dispatcher!(
     (MovementSystem, "movement", &[]),
     (TriggerSystem, "triggers", &[]),
     (MeleeCombatSystem, "melee", &[]),
+    (ManeuverSystem, "maneuver", &[]),
     (RangedCombatSystem, "ranged", &[]),
     (ItemCollectionSystem, "pickup", &[]),
     (ItemEquipOnUse, "equip", &[]),
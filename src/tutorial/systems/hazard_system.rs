@@ -0,0 +1,167 @@
+use crate::effects::{add_effect, EffectType, Targets};
+use crate::{
+    fuel_turns, tile_opaque, DamageType, HazardKind, Map, Name, Pools, RunState, StatusImmunities,
+    StatusKind,
+};
+use specs::prelude::*;
+
+// What standing in a hazard costs per turn
+const FIRE_DAMAGE: i32 = 3;
+const GAS_DAMAGE: i32 = 1;
+// Webs hold for a turn past their last tick, since statuses count down before hazards do
+const WEB_HOLD_TURNS: i32 = 2;
+// One in this many turns on oil ends with a fall
+const OIL_SLIP_CHANCE: i32 = 4;
+
+pub struct HazardSystem {}
+
+type HazardData<'a> = (
+    ReadExpect<'a, RunState>,
+    WriteExpect<'a, Map>,
+    ReadStorage<'a, Pools>,
+    ReadStorage<'a, Name>,
+    ReadStorage<'a, StatusImmunities>,
+);
+
+impl<'a> System<'a> for HazardSystem {
+    type SystemData = HazardData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (runstate, mut map, pools, names, immunities) = data;
+
+        // Hazards tick along with statuses, once per player turn
+        if *runstate != RunState::AwaitingInput {
+            return;
+        }
+
+        let mut spread: Vec<(usize, HazardKind, i32)> = Vec::new();
+        let hazards: Vec<(usize, HazardKind, i32)> = map
+            .hazards
+            .iter()
+            .map(|(idx, hazard)| (*idx, hazard.kind, hazard.turns))
+            .collect();
+        for (idx, kind, turns) in hazards {
+            let mut occupants = Vec::new();
+            crate::spatial::for_each_tile_content(idx, |entity| {
+                if pools.get(entity).is_some() {
+                    occupants.push(entity);
+                }
+            });
+            let neighbors = neighbors(&map, idx);
+            // Creatures that can't be stunned walk straight through webs and oil
+            let can_stick = |target: &&Entity| {
+                immunities
+                    .get(**target)
+                    .is_none_or(|immune| !immune.kinds.contains(&StatusKind::Stunned))
+            };
+
+            let mut worn = 1;
+            match kind {
+                HazardKind::Fire => {
+                    hurt(&occupants, FIRE_DAMAGE, DamageType::Fire);
+                    for n in neighbors {
+                        // Oil and webs go up at once; grass and wood take a little longer
+                        let catches = match map.hazards.get(&n).map(|h| h.kind) {
+                            Some(HazardKind::Fire) => false,
+                            Some(HazardKind::Oil) | Some(HazardKind::Web) => true,
+                            _ => {
+                                fuel_turns(&map, n).is_some()
+                                    && crate::tutorial::rng::roll_dice(1, 3) == 1
+                            }
+                        };
+                        if catches {
+                            spread.push((n, HazardKind::Fire, 1));
+                        }
+                    }
+                }
+                HazardKind::Gas => {
+                    hurt(&occupants, GAS_DAMAGE, DamageType::Poison);
+                    // Gas thins out as it drifts, so clouds fade from the edges in
+                    if turns > 2 {
+                        for n in neighbors {
+                            if !tile_opaque(map.tiles[n])
+                                && !map.hazards.contains_key(&n)
+                                && crate::tutorial::rng::roll_dice(1, 2) == 1
+                            {
+                                spread.push((n, HazardKind::Gas, turns - 2));
+                            }
+                        }
+                    }
+                }
+                HazardKind::Web => {
+                    for target in occupants.iter().filter(can_stick) {
+                        add_effect(
+                            None,
+                            EffectType::Stun {
+                                turns: WEB_HOLD_TURNS,
+                            },
+                            Targets::Single { target: *target },
+                        );
+                    }
+                    // Struggling tears the strands
+                    if !occupants.is_empty() {
+                        worn += 1;
+                    }
+                }
+                HazardKind::Oil => {
+                    for target in occupants.iter().filter(can_stick) {
+                        if crate::tutorial::rng::roll_dice(1, OIL_SLIP_CHANCE) == 1 {
+                            add_effect(
+                                None,
+                                EffectType::Stun { turns: 1 },
+                                Targets::Single { target: *target },
+                            );
+                            if let (Some(name), true) = (names.get(*target), map.visible_tiles[idx])
+                            {
+                                crate::gamelog::Logger::new()
+                                    .npc_name(&name.name)
+                                    .append("slips on the oil!")
+                                    .log();
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(hazard) = map.hazards.get_mut(&idx) {
+                hazard.turns -= worn;
+                if hazard.turns < 1 {
+                    if kind == HazardKind::Fire {
+                        map.burn_out(idx);
+                    } else {
+                        map.hazards.remove(&idx);
+                    }
+                }
+            }
+        }
+
+        for (idx, kind, turns) in spread {
+            map.add_hazard(idx, kind, turns);
+        }
+    }
+}
+
+fn hurt(occupants: &[Entity], amount: i32, damage_type: DamageType) {
+    for target in occupants.iter() {
+        add_effect(
+            None,
+            EffectType::Damage {
+                amount,
+                damage_type: Some(damage_type),
+            },
+            Targets::Single { target: *target },
+        );
+    }
+}
+
+fn neighbors(map: &Map, idx: usize) -> Vec<usize> {
+    let x = idx as i32 % map.width;
+    let y = idx as i32 / map.width;
+    [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        .iter()
+        .filter(|(dx, dy)| {
+            x + dx > 0 && x + dx < map.width - 1 && y + dy > 0 && y + dy < map.height - 1
+        })
+        .map(|(dx, dy)| map.xy_idx(x + dx, y + dy))
+        .collect()
+}
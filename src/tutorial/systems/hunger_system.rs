@@ -1,5 +1,5 @@
 use crate::effects::{add_effect, EffectType, Targets};
-use crate::{HungerClock, HungerState, MyTurn};
+use crate::{HungerClock, HungerState, MyTurn, Pools};
 use specs::prelude::*;
 
 pub struct HungerSystem;
@@ -9,16 +9,18 @@ type HungerData<'a> = (
     WriteStorage<'a, HungerClock>,
     ReadExpect<'a, Entity>, // The player
     ReadStorage<'a, MyTurn>,
+    ReadStorage<'a, Pools>,
 );
 
 impl<'a> System<'a> for HungerSystem {
     type SystemData = HungerData<'a>;
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut hunger_clock, player_entity, turns) = data;
+        let (entities, mut hunger_clock, player_entity, turns, pools) = data;
 
         for (entity, mut clock, _myturn) in (&entities, &mut hunger_clock, &turns).join() {
-            clock.duration -= 1;
+            // Hauling a heavy load works up an appetite
+            clock.duration -= pools.get(entity).map_or(1, |p| p.burden.hunger_rate());
             if clock.duration < 1 {
                 match clock.state {
                     HungerState::WellFed => {
@@ -63,7 +65,10 @@ impl<'a> System<'a> for HungerSystem {
                         }
                         add_effect(
                             None,
-                            EffectType::Damage { amount: 1 },
+                            EffectType::Damage {
+                                amount: 1,
+                                damage_type: None,
+                            },
                             Targets::Single { target: entity },
                         );
                     }
@@ -1,9 +1,9 @@
-use super::obfuscate_name;
+use super::{obfuscate_name, player_can_see};
 use specs::prelude::*;
 
 use crate::{
-    EquipmentChanged, InBackpack, MagicItem, MasterDungeonMap, Name, ObfuscatedName, Position,
-    WantsToPickupItem,
+    BurdenLevel, EquipmentChanged, Equipped, InBackpack, Item, MagicItem, MasterDungeonMap, Name,
+    ObfuscatedName, Pools, Position, Stackable, Viewshed, WantsToPickupItem,
 };
 
 pub struct ItemCollectionSystem {}
@@ -18,6 +18,12 @@ type ItemCollectionData<'a> = (
     ReadStorage<'a, MagicItem>,
     ReadStorage<'a, ObfuscatedName>,
     ReadExpect<'a, MasterDungeonMap>,
+    ReadStorage<'a, Viewshed>,
+    Entities<'a>,
+    ReadStorage<'a, Equipped>,
+    WriteStorage<'a, Stackable>,
+    ReadStorage<'a, Item>,
+    ReadStorage<'a, Pools>,
 );
 
 impl<'a> System<'a> for ItemCollectionSystem {
@@ -34,18 +40,65 @@ impl<'a> System<'a> for ItemCollectionSystem {
             magic_items,
             obfuscated_names,
             dm,
+            viewsheds,
+            entities,
+            equipped,
+            mut stackables,
+            items,
+            pools,
         ) = data;
 
         for pickup in wants_pickup.join() {
+            // Nobody picks up more than they can haul
+            if let (Some(item), Some(pools)) =
+                (items.get(pickup.item), pools.get(pickup.collected_by))
+            {
+                let quantity = stackables.get(pickup.item).map_or(1, |s| s.quantity);
+                let new_weight = pools.total_weight + item.weight_lbs * quantity as f32;
+                if new_weight > BurdenLevel::hard_cap(pools.carry_capacity_lbs) {
+                    continue;
+                }
+            }
+
             positions.remove(pickup.item);
-            backpack
-                .insert(
-                    pickup.item,
-                    InBackpack {
-                        owner: pickup.collected_by,
-                    },
-                )
-                .expect("Unable to insert backpack entry");
+
+            // Potions, scrolls, rations and loose arrows go onto a matching stack the collector
+            // already has, whether it is in the pack or a readied quiver
+            let bundle = if stackables.get(pickup.item).is_some() {
+                names.get(pickup.item).and_then(|picked| {
+                    (&entities, &names, &stackables)
+                        .join()
+                        .find(|(other, other_name, _)| {
+                            *other != pickup.item
+                                && other_name.name == picked.name
+                                && (backpack
+                                    .get(*other)
+                                    .is_some_and(|b| b.owner == pickup.collected_by)
+                                    || equipped
+                                        .get(*other)
+                                        .is_some_and(|e| e.owner == pickup.collected_by))
+                        })
+                        .map(|(other, _, _)| other)
+                })
+            } else {
+                None
+            };
+            let logged_item = bundle.unwrap_or(pickup.item);
+            if let Some(bundle) = bundle {
+                if let Some(quantity) = stackables.get(pickup.item).map(|s| s.quantity) {
+                    stackables.get_mut(bundle).unwrap().quantity += quantity;
+                }
+                entities.delete(pickup.item).expect("Unable to delete");
+            } else {
+                backpack
+                    .insert(
+                        pickup.item,
+                        InBackpack {
+                            owner: pickup.collected_by,
+                        },
+                    )
+                    .expect("Unable to insert backpack entry");
+            }
             dirty
                 .insert(pickup.collected_by, EquipmentChanged {})
                 .expect("Unable to insert");
@@ -53,7 +106,22 @@ impl<'a> System<'a> for ItemCollectionSystem {
                 crate::gamelog::Logger::new()
                     .append("You pick up the")
                     .item_name(obfuscate_name(
-                        pickup.item,
+                        logged_item,
+                        &names,
+                        &magic_items,
+                        &obfuscated_names,
+                        &dm,
+                    ))
+                    .log();
+            } else if player_can_see(
+                positions.get(pickup.collected_by),
+                viewsheds.get(*player_entity),
+            ) {
+                crate::gamelog::Logger::new()
+                    .npc_name(&names.get(pickup.collected_by).unwrap().name)
+                    .append("picks up the")
+                    .item_name(obfuscate_name(
+                        logged_item,
                         &names,
                         &magic_items,
                         &obfuscated_names,
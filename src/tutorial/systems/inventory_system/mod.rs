@@ -1,6 +1,6 @@
 use specs::prelude::*;
 
-use crate::{MagicItem, MasterDungeonMap, Name, ObfuscatedName};
+use crate::{MagicItem, MasterDungeonMap, Name, ObfuscatedName, Position, Viewshed};
 
 mod collection_system;
 pub use collection_system::ItemCollectionSystem;
@@ -43,3 +43,12 @@ fn obfuscate_name(
         "Nameless item (bug)".to_string()
     }
 }
+
+// Used to decide whether the player should hear about what a monster does with its items
+fn player_can_see(pos: Option<&Position>, player_view: Option<&Viewshed>) -> bool {
+    if let (Some(pos), Some(view)) = (pos, player_view) {
+        view.visible_tiles.contains(&rltk::Point::new(pos.x, pos.y))
+    } else {
+        false
+    }
+}
@@ -1,6 +1,7 @@
+use super::player_can_see;
 use crate::{
-    CursedItem, EquipmentChanged, Equippable, Equipped, IdentifiedItem, InBackpack, Name,
-    WantsToUseItem,
+    CursedItem, EquipmentChanged, Equippable, Equipped, IdentifiedItem, InBackpack, Name, Position,
+    Viewshed, WantsToUseItem,
 };
 use specs::prelude::*;
 
@@ -17,6 +18,8 @@ type OnUseData<'a> = (
     WriteStorage<'a, EquipmentChanged>,
     WriteStorage<'a, IdentifiedItem>,
     ReadStorage<'a, CursedItem>,
+    ReadStorage<'a, Position>,
+    ReadStorage<'a, Viewshed>,
 );
 
 impl<'a> System<'a> for ItemEquipOnUse {
@@ -34,6 +37,8 @@ impl<'a> System<'a> for ItemEquipOnUse {
             mut dirty,
             mut identified_item,
             cursed,
+            positions,
+            viewsheds,
         ) = data;
 
         let mut remove_use = Vec::new();
@@ -50,12 +55,14 @@ impl<'a> System<'a> for ItemEquipOnUse {
                     if already_equipped.owner == target && already_equipped.slot == target_slot {
                         if cursed.get(item_entity).is_some() {
                             can_equip = false;
-                            crate::gamelog::Logger::new()
-                                .append("You cannot unequip")
-                                .item_name(&name.name)
-                                .color(rltk::WHITE)
-                                .append("- it is cursed")
-                                .log()
+                            if target == *player_entity {
+                                crate::gamelog::Logger::new()
+                                    .append("You cannot unequip")
+                                    .item_name(&name.name)
+                                    .color(rltk::WHITE)
+                                    .append("- it is cursed")
+                                    .log()
+                            }
                         } else {
                             to_unequip.push(item_entity);
                             if target == *player_entity {
@@ -104,6 +111,12 @@ impl<'a> System<'a> for ItemEquipOnUse {
                             .append("You equip")
                             .item_name(&names.get(useitem.item).unwrap().name)
                             .log();
+                    } else if player_can_see(positions.get(target), viewsheds.get(*player_entity)) {
+                        crate::gamelog::Logger::new()
+                            .npc_name(&names.get(target).unwrap().name)
+                            .append("equips the")
+                            .item_name(&names.get(useitem.item).unwrap().name)
+                            .log();
                     }
                 }
 
@@ -1,12 +1,41 @@
 use specs::prelude::*;
 
+use super::{obfuscate_name, player_can_see};
 use crate::{
-    add_effect, aoe_tiles, AreaOfEffect, EffectType, EquipmentChanged, IdentifiedItem, Map, Name,
-    Targets, WantsToCastSpell, WantsToUseItem,
+    add_effect, aim_profile, roll_against_bystander, shape_tiles, trace_projectile, trap_site_open,
+    AreaOfEffect, Attributes, Blink, BlocksTile, CombatData, DeploysTrap, Durability, EffectType,
+    EntryTrigger, EquipmentChanged, Equipped, GameDice, HungerClock, IdentifiedItem, MagicItem,
+    Map, MasterDungeonMap, Name, NaturalAttackDefense, ObfuscatedName, Pools, Position,
+    Resistances, Skills, StatusEffect, SwapPlaces, Targets, Viewshed, WantsToCastSpell,
+    WantsToUseItem, Weapon, Wearable,
 };
+use rltk::Point;
 
 pub struct ItemUseSystem;
 
+/// Everything a bolt needs to roll against whoever stands in its way
+type CombatStorages<'a> = (
+    ReadStorage<'a, Attributes>,
+    ReadStorage<'a, Skills>,
+    ReadStorage<'a, HungerClock>,
+    ReadStorage<'a, Equipped>,
+    ReadStorage<'a, Weapon>,
+    ReadStorage<'a, Wearable>,
+    ReadStorage<'a, NaturalAttackDefense>,
+    ReadStorage<'a, Resistances>,
+    ReadStorage<'a, StatusEffect>,
+    ReadStorage<'a, Durability>,
+);
+
+/// The storages a bolt needs to find its way to wherever it stops
+struct Flight<'s, 'a> {
+    map: &'s Map,
+    positions: &'s ReadStorage<'a, Position>,
+    pools: &'s ReadStorage<'a, Pools>,
+    blockers: &'s ReadStorage<'a, BlocksTile>,
+    combat: &'s CombatData<'s, 'a>,
+}
+
 type ItemUseData<'a> = (
     ReadExpect<'a, Entity>,
     ReadExpect<'a, Map>,
@@ -16,6 +45,18 @@ type ItemUseData<'a> = (
     ReadStorage<'a, AreaOfEffect>,
     WriteStorage<'a, EquipmentChanged>,
     WriteStorage<'a, IdentifiedItem>,
+    ReadStorage<'a, Position>,
+    ReadStorage<'a, Viewshed>,
+    ReadStorage<'a, MagicItem>,
+    ReadStorage<'a, ObfuscatedName>,
+    ReadExpect<'a, MasterDungeonMap>,
+    ReadStorage<'a, Pools>,
+    ReadStorage<'a, BlocksTile>,
+    CombatStorages<'a>,
+    ReadStorage<'a, Blink>,
+    ReadStorage<'a, SwapPlaces>,
+    ReadStorage<'a, DeploysTrap>,
+    ReadStorage<'a, EntryTrigger>,
 );
 
 impl<'a> System<'a> for ItemUseSystem {
@@ -31,8 +72,52 @@ impl<'a> System<'a> for ItemUseSystem {
             aoe,
             mut dirty,
             mut identified_item,
+            positions,
+            viewsheds,
+            magic_items,
+            obfuscated_names,
+            dm,
+            pools,
+            blockers,
+            (
+                attributes,
+                skills,
+                hunger_clock,
+                equipped,
+                weapons,
+                wearables,
+                natural,
+                resistances,
+                statuses,
+                durabilities,
+            ),
+            blinks,
+            swaps,
+            trap_kits,
+            triggers,
         ) = data;
 
+        let combat = CombatData {
+            entities: &entities,
+            attributes: &attributes,
+            skills: &skills,
+            hunger_clock: &hunger_clock,
+            equipped: &equipped,
+            weapons: &weapons,
+            wearables: &wearables,
+            natural: &natural,
+            resistances: &resistances,
+            statuses: &statuses,
+            durabilities: &durabilities,
+        };
+        let flight = Flight {
+            map: &map,
+            positions: &positions,
+            pools: &pools,
+            blockers: &blockers,
+            combat: &combat,
+        };
+
         for (entity, useitem) in (&entities, &use_items).join() {
             dirty
                 .insert(entity, EquipmentChanged {})
@@ -48,28 +133,48 @@ impl<'a> System<'a> for ItemUseSystem {
                         },
                     )
                     .expect("Unable to insert");
+            } else if player_can_see(positions.get(entity), viewsheds.get(*player_entity)) {
+                crate::gamelog::Logger::new()
+                    .npc_name(&names.get(entity).unwrap().name)
+                    .append("uses the")
+                    .item_name(obfuscate_name(
+                        useitem.item,
+                        &names,
+                        &magic_items,
+                        &obfuscated_names,
+                        &dm,
+                    ))
+                    .log();
+            }
+
+            let targets = match useitem.target {
+                None => Targets::Single { target: entity },
+                Some(target) => area_targets(
+                    &flight,
+                    entity,
+                    target,
+                    aoe.get(useitem.item),
+                    blinks.get(useitem.item).is_none() && swaps.get(useitem.item).is_none(),
+                ),
+            };
+
+            // A trap kit that can't be set stays in the pack rather than being used up
+            if let (Some(_), Targets::Tile { tile_idx }) = (trap_kits.get(useitem.item), &targets) {
+                if !trap_site_open(&map, *tile_idx as usize, &triggers) {
+                    if entity == *player_entity {
+                        crate::gamelog::Logger::new()
+                            .append("There is no room to set a trap there.")
+                            .log();
+                    }
+                    continue;
+                }
             }
 
             // Call the effects system
             add_effect(
                 Some(entity),
                 EffectType::ItemUse { item: useitem.item },
-                match useitem.target {
-                    None => Targets::Single {
-                        target: *player_entity,
-                    },
-                    Some(target) => {
-                        if let Some(aoe) = aoe.get(useitem.item) {
-                            Targets::Tiles {
-                                tiles: aoe_tiles(&*map, target, aoe.radius),
-                            }
-                        } else {
-                            Targets::Tile {
-                                tile_idx: map.xy_idx(target.x, target.y) as i32,
-                            }
-                        }
-                    }
-                },
+                targets,
             );
         }
 
@@ -88,6 +193,12 @@ type SpellUseData<'a> = (
     ReadStorage<'a, AreaOfEffect>,
     WriteStorage<'a, EquipmentChanged>,
     WriteStorage<'a, IdentifiedItem>,
+    ReadStorage<'a, Position>,
+    ReadStorage<'a, Pools>,
+    ReadStorage<'a, BlocksTile>,
+    CombatStorages<'a>,
+    ReadStorage<'a, Blink>,
+    ReadStorage<'a, SwapPlaces>,
 );
 
 impl<'a> System<'a> for SpellUseSystem {
@@ -103,8 +214,46 @@ impl<'a> System<'a> for SpellUseSystem {
             aoe,
             mut dirty,
             mut identified_item,
+            positions,
+            pools,
+            blockers,
+            (
+                attributes,
+                skills,
+                hunger_clock,
+                equipped,
+                weapons,
+                wearables,
+                natural,
+                resistances,
+                statuses,
+                durabilities,
+            ),
+            blinks,
+            swaps,
         ) = data;
 
+        let combat = CombatData {
+            entities: &entities,
+            attributes: &attributes,
+            skills: &skills,
+            hunger_clock: &hunger_clock,
+            equipped: &equipped,
+            weapons: &weapons,
+            wearables: &wearables,
+            natural: &natural,
+            resistances: &resistances,
+            statuses: &statuses,
+            durabilities: &durabilities,
+        };
+        let flight = Flight {
+            map: &map,
+            positions: &positions,
+            pools: &pools,
+            blockers: &blockers,
+            combat: &combat,
+        };
+
         for (entity, castitem) in (&entities, &wants_cast).join() {
             dirty
                 .insert(entity, EquipmentChanged {})
@@ -129,20 +278,14 @@ impl<'a> System<'a> for SpellUseSystem {
                     spell: castitem.spell,
                 },
                 match castitem.target {
-                    None => Targets::Single {
-                        target: *player_entity,
-                    },
-                    Some(target) => {
-                        if let Some(aoe) = aoe.get(castitem.spell) {
-                            Targets::Tiles {
-                                tiles: aoe_tiles(&*map, target, aoe.radius),
-                            }
-                        } else {
-                            Targets::Tile {
-                                tile_idx: map.xy_idx(target.x, target.y) as i32,
-                            }
-                        }
-                    }
+                    None => Targets::Single { target: entity },
+                    Some(target) => area_targets(
+                        &flight,
+                        entity,
+                        target,
+                        aoe.get(castitem.spell),
+                        blinks.get(castitem.spell).is_none() && swaps.get(castitem.spell).is_none(),
+                    ),
                 },
             );
         }
@@ -150,3 +293,62 @@ impl<'a> System<'a> for SpellUseSystem {
         wants_cast.clear();
     }
 }
+
+/// Where a targeted item or spell goes off, and which tiles it covers when it does. Bolts
+/// fly like any other projectile and take effect wherever they stop; blinking and swapping
+/// places don't travel, so they go straight to the chosen tile.
+fn area_targets(
+    flight: &Flight,
+    user: Entity,
+    target: Point,
+    aoe: Option<&AreaOfEffect>,
+    flies: bool,
+) -> Targets {
+    let map = flight.map;
+    let landed = if flies {
+        landing_point(flight, user, target)
+    } else {
+        target
+    };
+    match aoe {
+        Some(aoe) => {
+            let origin = flight
+                .positions
+                .get(user)
+                .map_or(landed, |pos| Point::new(pos.x, pos.y));
+            let aim = if aoe.shape.lands_on_target() {
+                landed
+            } else {
+                target
+            };
+            Targets::Tiles {
+                tiles: shape_tiles(map, origin, aim, aoe.shape),
+            }
+        }
+        None => Targets::Tile {
+            tile_idx: map.xy_idx(landed.x, landed.y) as i32,
+        },
+    }
+}
+
+fn landing_point(flight: &Flight, user: Entity, target: Point) -> Point {
+    if let Some(pos) = flight.positions.get(user) {
+        let aim = aim_profile(user, flight.combat);
+        trace_projectile(
+            flight.map,
+            Point::new(pos.x, pos.y),
+            target,
+            user,
+            flight.pools,
+            flight.blockers,
+            &mut |bystander| {
+                aim.as_ref().is_some_and(|aim| {
+                    roll_against_bystander(aim, bystander, flight.combat, &mut GameDice).is_some()
+                })
+            },
+        )
+        .landed
+    } else {
+        target
+    }
+}
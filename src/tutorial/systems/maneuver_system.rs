@@ -0,0 +1,89 @@
+use crate::{
+    apply_maneuver, resolve_maneuver, Attributes, CombatData, Durability, Equipped, GameDice,
+    HungerClock, Name, NaturalAttackDefense, Pools, Resistances, Skills, StatusEffect,
+    WantsToManeuver, Weapon, Wearable,
+};
+
+use specs::prelude::*;
+
+pub struct ManeuverSystem {}
+
+type ManeuverData<'a> = (
+    Entities<'a>,
+    WriteStorage<'a, WantsToManeuver>,
+    ReadStorage<'a, Name>,
+    ReadStorage<'a, Attributes>,
+    ReadStorage<'a, Skills>,
+    ReadStorage<'a, HungerClock>,
+    ReadStorage<'a, Pools>,
+    ReadStorage<'a, Equipped>,
+    ReadStorage<'a, Weapon>,
+    ReadStorage<'a, Wearable>,
+    ReadStorage<'a, NaturalAttackDefense>,
+    ReadStorage<'a, Resistances>,
+    ReadStorage<'a, StatusEffect>,
+    ReadStorage<'a, Durability>,
+);
+
+impl<'a> System<'a> for ManeuverSystem {
+    type SystemData = ManeuverData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_maneuver,
+            names,
+            attributes,
+            skills,
+            hunger_clock,
+            pools,
+            equipped_items,
+            weapons,
+            wearables,
+            natural,
+            resistances,
+            statuses,
+            durabilities,
+        ) = data;
+
+        let combat = CombatData {
+            entities: &entities,
+            attributes: &attributes,
+            skills: &skills,
+            hunger_clock: &hunger_clock,
+            equipped: &equipped_items,
+            weapons: &weapons,
+            wearables: &wearables,
+            natural: &natural,
+            resistances: &resistances,
+            statuses: &statuses,
+            durabilities: &durabilities,
+        };
+
+        for (entity, wants, name, attacker_pools) in
+            (&entities, &wants_maneuver, &names, &pools).join()
+        {
+            let target_alive = pools
+                .get(wants.target)
+                .is_some_and(|p| p.hit_points.current > 0);
+            if attacker_pools.hit_points.current > 0 && target_alive {
+                let target_name = names.get(wants.target).unwrap();
+                if let Some(success) =
+                    resolve_maneuver(wants.maneuver, entity, wants.target, &combat, &mut GameDice)
+                {
+                    apply_maneuver(
+                        entity,
+                        &name.name,
+                        wants.target,
+                        &target_name.name,
+                        wants.maneuver,
+                        success,
+                        &combat,
+                    );
+                }
+            }
+        }
+
+        wants_maneuver.clear();
+    }
+}
@@ -4,6 +4,9 @@ pub use ai::*;
 pub mod dispatcher;
 pub use dispatcher::*;
 
+pub mod hazard_system;
+pub use hazard_system::*;
+
 pub mod hunger_system;
 pub use hunger_system::*;
 
@@ -13,6 +16,9 @@ pub use inventory_system::*;
 pub mod lighting_system;
 pub use lighting_system::*;
 
+pub mod maneuver_system;
+pub use maneuver_system::*;
+
 pub mod map_indexing_system;
 pub use map_indexing_system::*;
 
@@ -28,6 +34,12 @@ pub use particle_system::*;
 pub mod ranged_combat_system;
 pub use ranged_combat_system::*;
 
+pub mod regeneration_system;
+pub use regeneration_system::*;
+
+pub mod summon_system;
+pub use summon_system::*;
+
 pub mod trigger_system;
 pub use trigger_system::*;
 
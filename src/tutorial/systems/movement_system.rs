@@ -1,6 +1,7 @@
 use crate::{
     ApplyMove, ApplyTeleport, EntityMoved, Map, OtherLevelPosition, Position, RunState, Viewshed,
 };
+use rltk::Point;
 use specs::prelude::*;
 
 pub struct MovementSystem {}
@@ -16,6 +17,7 @@ type MovementData<'a> = (
     WriteStorage<'a, Viewshed>,
     ReadExpect<'a, Entity>,
     WriteExpect<'a, RunState>,
+    WriteExpect<'a, Point>,
 );
 
 impl<'a> System<'a> for MovementSystem {
@@ -33,6 +35,7 @@ impl<'a> System<'a> for MovementSystem {
             mut viewsheds,
             player_entity,
             mut runstate,
+            mut player_pos,
         ) = data;
 
         // Apply teleports
@@ -81,6 +84,9 @@ impl<'a> System<'a> for MovementSystem {
             if let Some(vs) = viewsheds.get_mut(entity) {
                 vs.dirty = true;
             }
+            if entity == *player_entity {
+                *player_pos = Point::new(pos.x, pos.y);
+            }
             moved
                 .insert(entity, EntityMoved {})
                 .expect("Unable to insert");
@@ -1,7 +1,10 @@
 use crate::{
-    effects::*, skill_bonus, Attributes, EquipmentSlot, Equipped, HungerClock, HungerState, Map,
-    Name, NaturalAttackDefense, Pools, Position, Skill, Skills, WantsToShoot, Weapon,
-    WeaponAttribute, Wearable,
+    apply_attack, attack_profile, defense_profile, effects::*, load_ammo, resolve_attack,
+    roll_against_bystander, throw_profile, trace_projectile, Ammunition, AttackProfile,
+    AttackSource, Attributes, BlocksTile, CombatData, Durability, EquipmentChanged, EquipmentSlot,
+    Equipped, GameDice, HungerClock, Map, Name, NaturalAttackDefense, Pools, Position,
+    ProjectileStop, Renderable, Resistances, Skills, Stackable, StatusEffect, Throwable,
+    WantsToShoot, WantsToThrow, Weapon, Wearable,
 };
 use rltk::{to_cp437, Point, RGB};
 use specs::prelude::*;
@@ -20,10 +23,31 @@ type RangedCombatData<'a> = (
     ReadStorage<'a, Weapon>,
     ReadStorage<'a, Wearable>,
     ReadStorage<'a, NaturalAttackDefense>,
+    ReadStorage<'a, Resistances>,
+    ReadStorage<'a, StatusEffect>,
     ReadStorage<'a, Position>,
     ReadExpect<'a, Map>,
+    ReadStorage<'a, Ammunition>,
+    WriteStorage<'a, EquipmentChanged>,
+    WriteStorage<'a, WantsToThrow>,
+    ReadStorage<'a, Throwable>,
+    ReadStorage<'a, Renderable>,
+    ReadExpect<'a, Entity>,
+    ReadStorage<'a, BlocksTile>,
+    ReadStorage<'a, Durability>,
+    WriteStorage<'a, Stackable>,
 );
 
+/// The storages a projectile needs to find its way to whatever it hits
+struct Flight<'s, 'a> {
+    positions: &'s ReadStorage<'a, Position>,
+    pools: &'s ReadStorage<'a, Pools>,
+    blockers: &'s ReadStorage<'a, BlocksTile>,
+    names: &'s ReadStorage<'a, Name>,
+    map: &'s Map,
+    player: Entity,
+}
+
 impl<'a> System<'a> for RangedCombatSystem {
     type SystemData = RangedCombatData<'a>;
 
@@ -40,228 +64,265 @@ impl<'a> System<'a> for RangedCombatSystem {
             weapon,
             wearables,
             natural,
+            resistances,
+            statuses,
             positions,
             map,
+            ammunition,
+            mut dirty,
+            mut wants_throw,
+            throwables,
+            renderables,
+            player_entity,
+            blockers,
+            durabilities,
+            mut stackables,
         ) = data;
 
-        for (entity, wants_shoot, name, attacker_attributes, attacker_skills, attacker_pools) in (
-            &entities,
-            &wants_shoot,
-            &names,
-            &attributes,
-            &skills,
-            &pools,
-        )
-            .join()
+        let combat = CombatData {
+            entities: &entities,
+            attributes: &attributes,
+            skills: &skills,
+            hunger_clock: &hunger_clock,
+            equipped: &equipped_items,
+            weapons: &weapon,
+            wearables: &wearables,
+            natural: &natural,
+            resistances: &resistances,
+            statuses: &statuses,
+            durabilities: &durabilities,
+        };
+        let flight = Flight {
+            positions: &positions,
+            pools: &pools,
+            blockers: &blockers,
+            names: &names,
+            map: &map,
+            player: *player_entity,
+        };
+
+        for (entity, wants_shoot, name, attacker_pools) in
+            (&entities, &wants_shoot, &names, &pools).join()
         {
             // Are the attacker and defender alive? Only attack if they are
             let target_pools = pools.get(wants_shoot.target).unwrap();
-            let target_attributes = attributes.get(wants_shoot.target).unwrap();
-            let target_skills = skills.get(wants_shoot.target).unwrap();
-            if attacker_pools.hit_points.current > 0 && target_pools.hit_points.current > 0 {
-                let target_name = names.get(wants_shoot.target).unwrap();
+            if attacker_pools.hit_points.current <= 0 || target_pools.hit_points.current <= 0 {
+                continue;
+            }
 
-                // Fire projectile effect
-                let apos = positions.get(entity).unwrap();
-                let dpos = positions.get(wants_shoot.target).unwrap();
+            let mut attack = if let Some(attack) =
+                attack_profile(entity, AttackSource::Ranged, &combat, &mut GameDice)
+            {
+                attack
+            } else {
+                continue;
+            };
 
-                add_effect(
-                    None,
-                    EffectType::ParticleProjectile {
-                        glyph: to_cp437('*'),
-                        fg: RGB::named(rltk::CYAN),
-                        bg: RGB::named(rltk::BLACK),
-                        lifespan: 300.0,
-                        speed: 50.0,
-                        path: rltk::line2d(
-                            rltk::LineAlg::Bresenham,
-                            Point::new(apos.x, apos.y),
-                            Point::new(dpos.x, dpos.y),
-                        ),
-                    },
-                    Targets::Tile {
-                        tile_idx: map.xy_idx(apos.x, apos.y) as i32,
-                    },
-                );
+            // Bows and crossbows need something to shoot
+            let mut recovered = None;
+            if let Some(ammo_type) = attack.weapon.ammo {
+                let quiver = (&entities, &equipped_items, &ammunition)
+                    .join()
+                    .find(|(_, equip, ammo)| {
+                        equip.owner == entity
+                            && equip.slot == EquipmentSlot::Ammo
+                            && ammo.ammo_type == ammo_type
+                    })
+                    .map(|(quiver, _, _)| quiver);
 
-                // Define the basic unarmed attack - overriddent by wielding check below if a
-                // weapon is equipped
-                let mut weapon_info = Weapon {
-                    range: None,
-                    attribute: WeaponAttribute::Might,
-                    hit_bonus: 0,
-                    damage_n_dice: 1,
-                    damage_die_type: 4,
-                    damage_bonus: 0,
-                    proc_chance: None,
-                    proc_target: None,
+                let quiver = if let Some(quiver) = quiver {
+                    quiver
+                } else {
+                    if entity == *player_entity {
+                        crate::gamelog::Logger::new()
+                            .append(format!("You have no {} to fire!", ammo_type.name()))
+                            .log();
+                    }
+                    continue;
                 };
 
-                if let Some(nat) = natural.get(entity) {
-                    if !nat.attacks.is_empty() {
-                        let attack_index = if nat.attacks.len() == 1 {
-                            0
-                        } else {
-                            crate::tutorial::rng::roll_dice(1, nat.attacks.len() as i32) as usize
-                                - 1
-                        };
-                        weapon_info.hit_bonus = nat.attacks[attack_index].hit_bonus;
-                        weapon_info.damage_n_dice = nat.attacks[attack_index].damage_n_dice;
-                        weapon_info.damage_die_type = nat.attacks[attack_index].damage_die_type;
-                        weapon_info.damage_bonus = nat.attacks[attack_index].damage_bonus;
-                    }
+                let ammo = ammunition.get(quiver).unwrap();
+                load_ammo(&mut attack, quiver, ammo);
+                if crate::tutorial::rng::roll_dice(1, 100) <= ammo.recovery_chance {
+                    recovered = names.get(quiver).map(|n| n.name.clone());
                 }
-
-                let mut weapon_entity = None;
-                for (weaponentity, wielded, melee) in (&entities, &equipped_items, &weapon).join() {
-                    if wielded.owner == entity && wielded.slot == EquipmentSlot::Melee {
-                        weapon_info = melee.clone();
-                        weapon_entity = Some(weaponentity);
+                let left = stackables.get_mut(quiver).map_or(0, |stack| {
+                    stack.quantity -= 1;
+                    stack.quantity
+                });
+                if left < 1 {
+                    entities.delete(quiver).expect("Unable to delete");
+                    if entity == *player_entity {
+                        crate::gamelog::Logger::new()
+                            .append(format!("You are out of {}.", ammo_type.name()))
+                            .log();
                     }
                 }
+                dirty
+                    .insert(entity, EquipmentChanged {})
+                    .expect("Unable to insert");
+            }
+
+            let landed = fire_projectile(
+                entity,
+                &name.name,
+                wants_shoot.target,
+                to_cp437('*'),
+                &attack,
+                &combat,
+                &flight,
+            );
+            if let Some(pos) = positions.get(wants_shoot.target) {
+                crate::tutorial::noise::make_noise(Point::new(pos.x, pos.y));
+            }
+
+            // Some of what was fired can be picked up again where it landed
+            if let (Some(name), Some(tile_idx)) = (recovered, landed) {
+                add_effect(
+                    None,
+                    EffectType::RecoverAmmo { name },
+                    Targets::Tile { tile_idx },
+                );
+            }
+        }
 
-                let natural_roll = crate::tutorial::rng::roll_dice(1, 20);
-                let attribute_hit_bonus = if weapon_info.attribute == WeaponAttribute::Might {
-                    attacker_attributes.might.bonus
+        for (entity, wants_throw, name, attacker_pools) in
+            (&entities, &wants_throw, &names, &pools).join()
+        {
+            let target_alive = pools
+                .get(wants_throw.target)
+                .is_some_and(|p| p.hit_points.current > 0);
+            if attacker_pools.hit_points.current <= 0 || !target_alive {
+                continue;
+            }
+            let hit_bonus = throwables
+                .get(wants_throw.item)
+                .map_or(0, |throwable| throwable.hit_bonus);
+            let attack =
+                if let Some(attack) = throw_profile(entity, wants_throw.item, hit_bonus, &combat) {
+                    attack
                 } else {
-                    attacker_attributes.quickness.bonus
+                    continue;
                 };
-                let skill_hit_bonus = skill_bonus(Skill::Melee, &*attacker_skills);
-                let weapon_hit_bonus = weapon_info.hit_bonus;
-                let mut status_hit_bonus = 0;
-                if let Some(hc) = hunger_clock.get(entity) {
-                    // Well-fed grants +1
-                    if hc.state == HungerState::WellFed {
-                        status_hit_bonus += 1;
-                    }
-                }
-                let modified_hit_roll = natural_roll
-                    + attribute_hit_bonus
-                    + skill_hit_bonus
-                    + weapon_hit_bonus
-                    + status_hit_bonus;
 
-                let mut armor_item_bonus_f = 0.0;
-                for (wielded, armor) in (&equipped_items, &wearables).join() {
-                    if wielded.owner == wants_shoot.target {
-                        armor_item_bonus_f += armor.armor_class;
-                    }
-                }
+            let glyph = renderables
+                .get(wants_throw.item)
+                .map_or(to_cp437('*'), |r| r.glyph);
+            let landed = fire_projectile(
+                entity,
+                &name.name,
+                wants_throw.target,
+                glyph,
+                &attack,
+                &combat,
+                &flight,
+            );
+            if let Some(pos) = positions.get(wants_throw.target) {
+                crate::tutorial::noise::make_noise(Point::new(pos.x, pos.y));
+            }
 
-                let base_armor_class = match natural.get(wants_shoot.target) {
-                    None => 10,
-                    Some(nat) => nat.armor_class.unwrap_or(10),
-                };
-                let armor_quickness_bonus = target_attributes.quickness.bonus;
-                let armor_skill_bonus = skill_bonus(Skill::Defense, &*target_skills);
-                let armor_item_bonus = armor_item_bonus_f as i32;
-                let armor_class =
-                    base_armor_class + armor_quickness_bonus + armor_skill_bonus + armor_item_bonus;
+            // Hit or miss, the weapon ends up wherever it stopped
+            if let Some(tile_idx) = landed {
+                add_effect(
+                    None,
+                    EffectType::LandItem {
+                        item: wants_throw.item,
+                    },
+                    Targets::Tile { tile_idx },
+                );
+            }
+        }
+
+        wants_shoot.clear();
+        wants_throw.clear();
+    }
+}
 
-                if natural_roll != 1 && (natural_roll == 20 || modified_hit_roll > armor_class) {
-                    let base_damage = crate::tutorial::rng::roll_dice(
-                        weapon_info.damage_n_dice,
-                        weapon_info.damage_die_type,
-                    );
-                    let attr_damage_bonus = attacker_attributes.might.bonus;
-                    let skill_damage_bonus = skill_bonus(Skill::Melee, &*attacker_skills);
-                    let weapon_damage_bonus = weapon_info.damage_bonus;
+/// Flies a projectile along the line to the target and attacks whoever it reaches first,
+/// returning the tile it lands on
+fn fire_projectile(
+    shooter: Entity,
+    shooter_name: &str,
+    target: Entity,
+    glyph: rltk::FontCharType,
+    attack: &AttackProfile,
+    combat: &CombatData,
+    flight: &Flight,
+) -> Option<i32> {
+    let apos = flight.positions.get(shooter)?;
+    let dpos = flight.positions.get(target)?;
 
-                    let damage = i32::max(
-                        0,
-                        base_damage + attr_damage_bonus + skill_damage_bonus + weapon_damage_bonus,
-                    );
+    // Anyone in the way gets the same attack roll the target would; the first one it
+    // connects with takes the shot
+    let mut intercepted = None;
+    let trace = trace_projectile(
+        flight.map,
+        Point::new(apos.x, apos.y),
+        Point::new(dpos.x, dpos.y),
+        shooter,
+        flight.pools,
+        flight.blockers,
+        &mut |bystander| {
+            intercepted = roll_against_bystander(attack, bystander, combat, &mut GameDice);
+            intercepted.is_some()
+        },
+    );
 
-                    add_effect(
-                        Some(entity),
-                        EffectType::Damage { amount: damage },
-                        Targets::Single {
-                            target: wants_shoot.target,
-                        },
-                    );
-                    crate::gamelog::Logger::new()
-                        .npc_name(&name.name)
-                        .color(rltk::WHITE)
-                        .append("hits")
-                        .npc_name(&target_name.name)
-                        .append("for")
-                        .damage(damage)
-                        .color(rltk::WHITE)
-                        .append("hp.")
-                        .log();
+    add_effect(
+        None,
+        EffectType::ParticleProjectile {
+            glyph,
+            fg: RGB::named(rltk::CYAN),
+            bg: RGB::named(rltk::BLACK),
+            lifespan: 300.0,
+            speed: 50.0,
+            path: trace.path.clone(),
+        },
+        Targets::Tile {
+            tile_idx: flight.map.xy_idx(apos.x, apos.y) as i32,
+        },
+    );
 
-                    // Proc effects
-                    if let Some(chance) = &weapon_info.proc_chance {
-                        let roll = crate::tutorial::rng::roll_dice(1, 100);
-                        if roll <= (chance * 100.0) as i32 {
-                            let effect_target = if let Some(weapon_target) = weapon_info.proc_target
-                            {
-                                if weapon_target == "Self" {
-                                    Targets::Single { target: entity }
-                                } else {
-                                    Targets::Single {
-                                        target: wants_shoot.target,
-                                    }
-                                }
-                            } else {
-                                Targets::Single {
-                                    target: wants_shoot.target,
-                                }
-                            };
-                            add_effect(
-                                Some(entity),
-                                EffectType::ItemUse {
-                                    item: weapon_entity.unwrap(),
-                                },
-                                effect_target,
-                            );
-                        }
-                    }
-                } else if natural_roll == 1 {
-                    crate::gamelog::Logger::new()
-                        .item_name(&name.name)
-                        .color(rltk::WHITE)
-                        .item_name(&target_name.name)
-                        .color(rltk::WHITE)
-                        .append("but misjudges the timing!")
-                        .log();
-                    add_effect(
-                        None,
-                        EffectType::Particle {
-                            glyph: to_cp437('‼'),
-                            fg: RGB::named(rltk::BLUE),
-                            bg: RGB::named(rltk::BLACK),
-                            lifespan: 200.0,
-                        },
-                        Targets::Single {
-                            target: wants_shoot.target,
-                        },
-                    );
-                } else {
-                    crate::gamelog::Logger::new()
-                        .item_name(&name.name)
-                        .color(rltk::WHITE)
-                        .append("attacks")
-                        .item_name(&target_name.name)
-                        .color(rltk::WHITE)
-                        .append("but can't connect")
-                        .log();
-                    add_effect(
-                        None,
-                        EffectType::Particle {
-                            glyph: to_cp437('‼'),
-                            fg: RGB::named(rltk::CYAN),
-                            bg: RGB::named(rltk::BLACK),
-                            lifespan: 200.0,
-                        },
-                        Targets::Single {
-                            target: wants_shoot.target,
-                        },
-                    );
-                }
+    let victim = match trace.stop {
+        ProjectileStop::Target => Some(target),
+        ProjectileStop::Creature(in_the_way) => {
+            if let Some(in_the_way_name) = flight.names.get(in_the_way) {
+                crate::gamelog::Logger::new()
+                    .npc_name(&in_the_way_name.name)
+                    .color(rltk::WHITE)
+                    .append("is in the way!")
+                    .log();
             }
+            Some(in_the_way)
         }
+        ProjectileStop::Obstacle => {
+            if shooter == flight.player {
+                crate::gamelog::Logger::new()
+                    .append("Your shot is blocked by cover.")
+                    .log();
+            }
+            None
+        }
+    };
 
-        wants_shoot.clear();
+    if let Some(victim) = victim {
+        if let (Some(defense), Some(victim_name)) =
+            (defense_profile(victim, combat), flight.names.get(victim))
+        {
+            let result =
+                intercepted.unwrap_or_else(|| resolve_attack(attack, &defense, &mut GameDice));
+            apply_attack(
+                shooter,
+                shooter_name,
+                victim,
+                &victim_name.name,
+                attack,
+                &result,
+                combat,
+                &mut GameDice,
+            );
+        }
     }
+
+    Some(flight.map.xy_idx(trace.landed.x, trace.landed.y) as i32)
 }
@@ -0,0 +1,59 @@
+use crate::{
+    hp_regen_interval, mana_regen_interval, Attributes, HungerClock, HungerState, MasterDungeonMap,
+    Pools, RunState,
+};
+use specs::prelude::*;
+
+pub struct RegenerationSystem {}
+
+type RegenerationData<'a> = (
+    ReadExpect<'a, RunState>,
+    ReadExpect<'a, MasterDungeonMap>,
+    WriteStorage<'a, Pools>,
+    ReadStorage<'a, Attributes>,
+    ReadStorage<'a, HungerClock>,
+);
+
+impl<'a> System<'a> for RegenerationSystem {
+    type SystemData = RegenerationData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (runstate, dm, mut pools, attributes, hunger) = data;
+
+        // Everyone heals at the same pace as statuses tick, once per player turn
+        if *runstate != RunState::AwaitingInput {
+            return;
+        }
+
+        for (stats, attr, hunger) in (&mut pools, &attributes, hunger.maybe()).join() {
+            if stats.hit_points.current < 1 {
+                continue;
+            }
+
+            // A full belly speeds recovery; an empty one slows it, and starvation stops it
+            let pace = |interval: i32| match hunger.map(|h| h.state) {
+                Some(HungerState::WellFed) => Some(i32::max(1, interval * 3 / 4)),
+                Some(HungerState::Hungry) => Some(interval * 2),
+                Some(HungerState::Starving) => None,
+                _ => Some(interval),
+            };
+
+            let hp_interval = pace(hp_regen_interval(
+                attr.fitness.base + attr.fitness.modifiers,
+                stats.level,
+            ));
+            if hp_interval.is_some_and(|interval| dm.turn % interval == 0) {
+                stats.hit_points.current =
+                    i32::min(stats.hit_points.current + 1, stats.hit_points.max);
+            }
+
+            let mana_interval = pace(mana_regen_interval(
+                attr.intelligence.base + attr.intelligence.modifiers,
+                stats.level,
+            ));
+            if mana_interval.is_some_and(|interval| dm.turn % interval == 0) {
+                stats.mana.current = i32::min(stats.mana.current + 1, stats.mana.max);
+            }
+        }
+    }
+}
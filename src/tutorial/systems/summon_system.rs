@@ -0,0 +1,76 @@
+use crate::effects::{add_effect, EffectType, Targets};
+use crate::{Equipped, InBackpack, Map, Name, Position, RunState, Summoned};
+use specs::prelude::*;
+
+pub struct SummonSystem {}
+
+type SummonData<'a> = (
+    Entities<'a>,
+    ReadExpect<'a, RunState>,
+    ReadExpect<'a, Map>,
+    WriteStorage<'a, Summoned>,
+    ReadStorage<'a, Position>,
+    ReadStorage<'a, Name>,
+    ReadStorage<'a, Equipped>,
+    ReadStorage<'a, InBackpack>,
+);
+
+impl<'a> System<'a> for SummonSystem {
+    type SystemData = SummonData<'a>;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, runstate, map, mut summoned, positions, names, equipped, carried) = data;
+
+        // Summons count down with everything else, once per player turn
+        if *runstate != RunState::AwaitingInput {
+            return;
+        }
+
+        let mut expired = Vec::new();
+        for (entity, summon) in (&entities, &mut summoned).join() {
+            if let Some(turns) = summon.turns.as_mut() {
+                *turns -= 1;
+                if *turns < 1 {
+                    expired.push(entity);
+                }
+            }
+        }
+
+        for entity in expired {
+            if let Some(pos) = positions.get(entity) {
+                let idx = map.xy_idx(pos.x, pos.y);
+                crate::spatial::remove_entity(entity, idx);
+                add_effect(
+                    None,
+                    EffectType::Particle {
+                        glyph: rltk::to_cp437('░'),
+                        fg: rltk::RGB::named(rltk::LIGHT_GRAY),
+                        bg: rltk::RGB::named(rltk::BLACK),
+                        lifespan: 400.0,
+                    },
+                    Targets::Tile {
+                        tile_idx: idx as i32,
+                    },
+                );
+                if let (Some(name), true) = (names.get(entity), map.visible_tiles[idx]) {
+                    crate::gamelog::Logger::new()
+                        .npc_name(&name.name)
+                        .append("fades away.")
+                        .log();
+                }
+            }
+
+            for (item, equipped) in (&entities, &equipped).join() {
+                if equipped.owner == entity {
+                    entities.delete(item).expect("Unable to delete");
+                }
+            }
+            for (item, backpack) in (&entities, &carried).join() {
+                if backpack.owner == entity {
+                    entities.delete(item).expect("Unable to delete");
+                }
+            }
+            entities.delete(entity).expect("Unable to delete");
+        }
+    }
+}
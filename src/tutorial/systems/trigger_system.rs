@@ -41,7 +41,12 @@ impl<'a> System<'a> for TriggerSystem {
                         EffectType::TriggerFire { trigger: entity_id },
                         if let Some(aoe) = area_of_effect.get(entity_id) {
                             Targets::Tiles {
-                                tiles: aoe_tiles(&*map, rltk::Point::new(pos.x, pos.y), aoe.radius),
+                                tiles: shape_tiles(
+                                    &*map,
+                                    rltk::Point::new(pos.x, pos.y),
+                                    rltk::Point::new(pos.x, pos.y),
+                                    aoe.shape,
+                                ),
                             }
                         } else {
                             Targets::Tile {
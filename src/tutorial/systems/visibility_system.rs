@@ -1,4 +1,7 @@
-use crate::{BlocksVisibility, Hidden, Map, Name, Player, Position, Viewshed};
+use crate::{
+    spots_hidden, Attributes, BlocksVisibility, GameDice, Hidden, Map, Name, Player, Position,
+    Skills, Trap, Viewshed, DEFAULT_TRAP_DIFFICULTY,
+};
 use rltk::{field_of_view, Point};
 use specs::prelude::*;
 
@@ -13,14 +16,28 @@ type VisibilityData<'a> = (
     WriteStorage<'a, Hidden>,
     ReadStorage<'a, Name>,
     ReadStorage<'a, BlocksVisibility>,
+    ReadStorage<'a, Attributes>,
+    ReadStorage<'a, Skills>,
+    ReadStorage<'a, Trap>,
 );
 
 impl<'a> System<'a> for VisibilitySystem {
     type SystemData = VisibilityData<'a>;
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut map, entities, mut viewshed, pos, player, mut hidden, names, blocks_visibility) =
-            data;
+        let (
+            mut map,
+            entities,
+            mut viewshed,
+            pos,
+            player,
+            mut hidden,
+            names,
+            blocks_visibility,
+            attributes,
+            skills,
+            traps,
+        ) = data;
 
         map.view_blocked.clear();
         for (block_pos, _block) in (&pos, &blocks_visibility).join() {
@@ -48,10 +65,18 @@ impl<'a> System<'a> for VisibilitySystem {
                         map.revealed_tiles[idx] = true;
                         map.visible_tiles[idx] = true;
 
-                        // Chance to reveal hidden things
+                        // Chance to notice hidden things in passing
                         crate::spatial::for_each_tile_content(idx, |e| {
                             if hidden.get(e).is_some()
-                                && crate::tutorial::rng::roll_dice(1, 24) == 1
+                                && spots_hidden(
+                                    attributes.get(ent),
+                                    skills.get(ent),
+                                    traps
+                                        .get(e)
+                                        .map_or(DEFAULT_TRAP_DIFFICULTY, |t| t.difficulty),
+                                    false,
+                                    &mut GameDice,
+                                )
                             {
                                 if let Some(name) = names.get(e) {
                                     crate::gamelog::Logger::new()